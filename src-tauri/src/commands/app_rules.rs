@@ -0,0 +1,68 @@
+use crate::core::app_rules::{self, MAX_APP_RULES};
+use crate::core::MeritStorage;
+use crate::models::{AppRule, AppRuleUpsert};
+use tauri::{AppHandle, Emitter};
+
+#[tauri::command]
+pub async fn list_app_rules() -> Result<Vec<AppRule>, String> {
+    let storage = MeritStorage::instance();
+    let storage = storage.read();
+    Ok(storage.get_settings().app_rules)
+}
+
+#[tauri::command]
+pub async fn upsert_app_rule(app_handle: AppHandle, rule: AppRuleUpsert) -> Result<AppRule, String> {
+    let app_id = app_rules::normalize_match(rule.app_id);
+    let app_name = app_rules::normalize_match(rule.app_name);
+    if app_id.is_none() && app_name.is_none() {
+        return Err("app_required".to_string());
+    }
+
+    let id = match rule.id.as_deref().map(str::trim) {
+        Some(id) if app_rules::is_valid_id(id) => id.to_string(),
+        Some(_) => return Err("id_invalid".to_string()),
+        None => app_rules::generate_id(),
+    };
+    let saved = AppRule {
+        id,
+        app_id,
+        app_name,
+        mode: rule.mode,
+    };
+
+    let storage = MeritStorage::instance();
+    let mut storage = storage.write();
+    let mut settings = storage.get_settings();
+    match settings.app_rules.iter_mut().find(|r| r.id == saved.id) {
+        Some(existing) => *existing = saved.clone(),
+        None => {
+            if settings.app_rules.len() >= MAX_APP_RULES {
+                return Err("app_rules_limit_reached".to_string());
+            }
+            settings.app_rules.push(saved.clone());
+        }
+    }
+    storage.set_settings(settings);
+    crate::core::persistence::request_save();
+
+    let _ = app_handle.emit("settings-updated", storage.get_settings());
+    Ok(saved)
+}
+
+#[tauri::command]
+pub async fn delete_app_rule(app_handle: AppHandle, id: String) -> Result<(), String> {
+    let id = id.trim();
+    let storage = MeritStorage::instance();
+    let mut storage = storage.write();
+    let mut settings = storage.get_settings();
+    let before = settings.app_rules.len();
+    settings.app_rules.retain(|r| r.id != id);
+    if settings.app_rules.len() == before {
+        return Ok(());
+    }
+    storage.set_settings(settings);
+    crate::core::persistence::request_save();
+
+    let _ = app_handle.emit("settings-updated", storage.get_settings());
+    Ok(())
+}
@@ -0,0 +1,29 @@
+use crate::core::backup::{self, BackupInfo, BackupKind};
+use crate::core::persistence::{self, StateRecoveryReport};
+use tauri::AppHandle;
+
+#[tauri::command]
+pub async fn list_backups(app_handle: AppHandle) -> Result<Vec<BackupInfo>, String> {
+    backup::list(&app_handle)
+}
+
+#[tauri::command]
+pub async fn create_backup(app_handle: AppHandle) -> Result<BackupInfo, String> {
+    backup::create(&app_handle, BackupKind::Manual, None)
+}
+
+#[tauri::command]
+pub async fn restore_backup(app_handle: AppHandle, id: String) -> Result<(), String> {
+    backup::restore(&app_handle, &id)
+}
+
+#[tauri::command]
+pub async fn delete_backup(app_handle: AppHandle, id: String) -> Result<(), String> {
+    backup::delete(&app_handle, &id)
+}
+
+/// One-shot: returns the startup recovery report (if `state.json` had to be salvaged) and clears it.
+#[tauri::command]
+pub async fn take_state_recovery_report() -> Result<Option<StateRecoveryReport>, String> {
+    Ok(persistence::take_recovery_report())
+}
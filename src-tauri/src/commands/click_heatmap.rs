@@ -1,6 +1,8 @@
-use crate::core::{self, MeritStorage};
+use crate::core;
+use crate::core::heatmap_render::HeatmapRenderOptions;
 use crate::models::click_heatmap::{CLICK_HEATMAP_BASE_COLS, CLICK_HEATMAP_BASE_ROWS};
-use serde::Serialize;
+use crate::models::HeatmapKind;
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Manager, Monitor};
 
 #[derive(Debug, Clone, Serialize)]
@@ -74,56 +76,17 @@ pub async fn get_click_heatmap_grid(
     cols: u32,
     rows: u32,
     date_key: Option<String>,
+    kind: Option<HeatmapKind>,
 ) -> Result<ClickHeatmapGrid, String> {
+    let kind = kind.unwrap_or_default();
     let cols = clamp_grid_dim(cols, 8, 240, 64) as usize;
     let rows = clamp_grid_dim(rows, 6, 180, 36) as usize;
 
     let mut out = vec![0u64; cols.saturating_mul(rows)];
     let mut max = 0u64;
 
-    let base = core::history_db::load_click_heatmap_base(&monitor_id, date_key.as_deref());
-    let (cells, total_clicks) = match base {
-        Ok((cells, total_clicks)) if total_clicks > 0 || !cells.is_empty() => (cells, total_clicks),
-        Ok(_) | Err(_) => {
-            // Best-effort fallback to legacy in-memory state (e.g. before DB init/migration).
-            let storage = MeritStorage::instance();
-            let storage = storage.read();
-            let display = match date_key.as_deref() {
-                Some(key) => storage.click_heatmap_display_for_date(&monitor_id, key),
-                None => storage.click_heatmap_display(&monitor_id),
-            };
-            let total = display.map(|d| d.total_clicks).unwrap_or(0);
-
-            if let Some(display) = display {
-                for y in 0..CLICK_HEATMAP_BASE_ROWS {
-                    for x in 0..CLICK_HEATMAP_BASE_COLS {
-                        let idx = y * CLICK_HEATMAP_BASE_COLS + x;
-                        let count = display.grid.get(idx).copied().unwrap_or(0) as u64;
-                        if count == 0 {
-                            continue;
-                        }
-
-                        let tx = (x * cols) / CLICK_HEATMAP_BASE_COLS;
-                        let ty = (y * rows) / CLICK_HEATMAP_BASE_ROWS;
-                        let t_idx = ty * cols + tx;
-                        if let Some(slot) = out.get_mut(t_idx) {
-                            *slot = slot.saturating_add(count);
-                            max = max.max(*slot);
-                        }
-                    }
-                }
-            }
-
-            return Ok(ClickHeatmapGrid {
-                monitor_id,
-                cols: cols as u32,
-                rows: rows as u32,
-                counts: out,
-                max,
-                total_clicks: total,
-            });
-        }
-    };
+    let (cells, total_clicks) =
+        core::click_heatmap::load_base_cells(kind, &monitor_id, date_key.as_deref())?;
 
     for (idx, count) in cells {
         let idx = idx as usize;
@@ -153,6 +116,89 @@ pub async fn get_click_heatmap_grid(
 }
 
 #[tauri::command]
-pub async fn clear_click_heatmap(display_id: Option<String>, date_key: Option<String>) -> Result<(), String> {
-    core::history_db::clear_click_heatmap(display_id, date_key)
+pub async fn clear_click_heatmap(
+    app_handle: AppHandle,
+    display_id: Option<String>,
+    date_key: Option<String>,
+    kind: Option<HeatmapKind>,
+) -> Result<(), String> {
+    core::backup::snapshot_before(&app_handle, "clear_click_heatmap")?;
+    core::history_db::clear_heatmap(kind.unwrap_or_default(), display_id, date_key)
+}
+
+/// What `export_heatmap_png` renders and where it writes it. The render options sit at the top
+/// level, next to the display and date.
+#[derive(Debug, Clone, Deserialize)]
+pub struct HeatmapPngExport {
+    pub monitor_id: String,
+    /// A single day; `None` exports the all-time totals.
+    #[serde(default)]
+    pub date_key: Option<String>,
+    #[serde(default)]
+    pub kind: HeatmapKind,
+    #[serde(flatten)]
+    pub render: HeatmapRenderOptions,
+    #[serde(default)]
+    pub file_name: Option<String>,
+    #[serde(default)]
+    pub export_dir: Option<String>,
+    #[serde(default)]
+    pub export_path: Option<String>,
+}
+
+/// Renders a display's heatmap to PNG and writes it through the skin export path logic.
+#[tauri::command]
+pub async fn export_heatmap_png(
+    app_handle: AppHandle,
+    export: HeatmapPngExport,
+) -> Result<String, String> {
+    let HeatmapPngExport {
+        monitor_id,
+        date_key,
+        kind,
+        render,
+        file_name,
+        export_dir,
+        export_path,
+    } = export;
+    let (cells, _) = core::click_heatmap::load_base_cells(kind, &monitor_id, date_key.as_deref())?;
+
+    let aspect = core::click_heatmap::available_monitors(&app_handle)
+        .iter()
+        .find(|m| core::click_heatmap::monitor_id(m) == monitor_id)
+        .map(|m| (m.size().width as f64, m.size().height as f64))
+        .or_else(|| core::click_heatmap::known_monitor_size(&monitor_id));
+    let png = core::heatmap_render::render_png(
+        &cells,
+        CLICK_HEATMAP_BASE_COLS,
+        CLICK_HEATMAP_BASE_ROWS,
+        aspect,
+        &render,
+    )?;
+
+    let file_name = file_name.unwrap_or_else(|| {
+        let kind = match kind {
+            HeatmapKind::Click => "click",
+            HeatmapKind::Move => "move",
+        };
+        // Windows display names look like `\\.\DISPLAY1`.
+        let display: String = monitor_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' { c } else { '_' })
+            .collect();
+        format!(
+            "heatmap-{}-{}-{}.png",
+            kind,
+            display.trim_matches('_'),
+            date_key.as_deref().unwrap_or("total")
+        )
+    });
+    core::wooden_fish_skins::export_png_bytes_to_app_data(
+        &app_handle,
+        &file_name,
+        export_dir.as_deref(),
+        export_path.as_deref(),
+        &png,
+    )
+    .map_err(|e| format!("{e:#}"))
 }
@@ -0,0 +1,44 @@
+use crate::core::backup::BackupScrubReport;
+use crate::core::history_db::{self, HistoryDbStats, HistoryTableRows};
+use crate::core::MeritStorage;
+use crate::models::KeyPrivacyLevel;
+use serde::Serialize;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyScrubReport {
+    pub tables: Vec<HistoryTableRows>,
+    pub backups: BackupScrubReport,
+}
+
+#[tauri::command]
+pub async fn get_history_db_stats() -> Result<HistoryDbStats, String> {
+    history_db::db_stats()
+}
+
+/// Applies the retention settings immediately instead of waiting for the scheduled run.
+#[tauri::command]
+pub async fn prune_history(app_handle: AppHandle) -> Result<Vec<HistoryTableRows>, String> {
+    crate::core::backup::snapshot_before(&app_handle, "prune_history")?;
+    history_db::prune_now()
+}
+
+#[tauri::command]
+pub async fn vacuum_history(full: Option<bool>) -> Result<HistoryDbStats, String> {
+    history_db::vacuum_now(full.unwrap_or(false))?;
+    history_db::db_stats()
+}
+
+/// Reduces already-recorded key and shortcut counts to `level` (default: the configured level),
+/// including the copies in existing backups. No new snapshot is taken, since it would keep the
+/// details this is meant to remove.
+#[tauri::command]
+pub async fn scrub_key_details(
+    app_handle: AppHandle,
+    level: Option<KeyPrivacyLevel>,
+) -> Result<KeyScrubReport, String> {
+    let level = level.unwrap_or_else(|| MeritStorage::instance().read().key_privacy_level());
+    let tables = crate::core::key_privacy::scrub_stored(level)?;
+    let backups = crate::core::backup::scrub_key_details(&app_handle, level)?;
+    Ok(KeyScrubReport { tables, backups })
+}
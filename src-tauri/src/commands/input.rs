@@ -1,6 +1,7 @@
+use crate::core::listening_schedule::{self, ListeningScheduleStatus, MAX_PAUSE_MINUTES};
 use crate::core::{self, MeritStorage};
 use crate::models::Settings;
-use tauri::AppHandle;
+use tauri::{AppHandle, Emitter};
 
 #[tauri::command]
 pub async fn start_input_listening(app_handle: AppHandle) -> Result<(), String> {
@@ -12,18 +13,42 @@ pub async fn start_input_listening(app_handle: AppHandle) -> Result<(), String>
     }
 
     core::init_input_listener(app_handle.clone())?;
+    listening_schedule::resume_manually();
     core::set_listening_enabled(true);
-    let _ = crate::tray_menu::refresh_tray_menu(&app_handle);
+    listening_schedule::notify_listening_changed(&app_handle, true, None);
     Ok(())
 }
 
 #[tauri::command]
 pub async fn stop_input_listening(app_handle: AppHandle) -> Result<(), String> {
     core::set_listening_enabled(false);
-    let _ = crate::tray_menu::refresh_tray_menu(&app_handle);
+    listening_schedule::notify_listening_changed(&app_handle, false, None);
     Ok(())
 }
 
+/// Pauses listening for `minutes`; starting listening again cancels the timer.
+#[tauri::command]
+pub async fn pause_input_listening_for(app_handle: AppHandle, minutes: u32) -> Result<(), String> {
+    let minutes = minutes.clamp(1, MAX_PAUSE_MINUTES);
+    let until = chrono::Utc::now().timestamp_millis() + i64::from(minutes) * 60_000;
+    {
+        let storage = MeritStorage::instance();
+        let mut storage = storage.write();
+        let mut settings = storage.get_settings();
+        settings.listening_paused_until_ms = Some(until);
+        storage.set_settings(settings);
+        crate::core::persistence::request_save();
+        let _ = app_handle.emit("settings-updated", storage.get_settings());
+    }
+    listening_schedule::evaluate(&app_handle);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_listening_schedule_status() -> Result<ListeningScheduleStatus, String> {
+    Ok(listening_schedule::status())
+}
+
 #[tauri::command]
 pub async fn is_input_listening() -> Result<bool, String> {
     Ok(core::is_listening_enabled())
@@ -1,7 +1,13 @@
+use crate::core::data_import::ImportReport;
+use crate::core::layout_analysis::{self, LayoutAnalysis, LogicalLayout};
 use crate::core::merit_batcher::enqueue_merit_trigger;
 use crate::core::suppress_mouse_for;
 use crate::core::MeritStorage;
-use crate::models::{DailyStats, DailyStatsLite, InputOrigin, InputSource, MeritStatsLite, StatisticsAggregates};
+use crate::models::{
+    DailyStats, DailyStatsLite, InputOrigin, InputSource, KeyBigramStats, MeritStatsLite,
+    SessionRecord, SessionStats, StatisticsAggregates, StatisticsBucket, StatisticsGranularity,
+    TimelineBucket, TypingAccuracyDay, TypingSpeedDay,
+};
 use tauri::AppHandle;
 
 #[tauri::command]
@@ -56,28 +62,195 @@ pub async fn get_recent_days_lite(days: usize) -> Result<Vec<DailyStatsLite>, St
 }
 
 #[tauri::command]
-pub async fn get_history_aggregates(
+pub async fn export_all_data(
+    app_handle: AppHandle,
+    file_name: Option<String>,
+    export_dir: Option<String>,
+    export_path: Option<String>,
+) -> Result<String, String> {
+    crate::core::data_export::export_to_file(
+        &app_handle,
+        file_name.as_deref(),
+        export_dir.as_deref(),
+        export_path.as_deref(),
+    )
+}
+
+#[tauri::command]
+pub async fn import_data(
+    app_handle: AppHandle,
+    path: String,
+    state_path: Option<String>,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    crate::core::data_import::import_data(
+        &app_handle,
+        std::path::Path::new(&path),
+        state_path.as_deref().map(std::path::Path::new),
+        dry_run,
+    )
+}
+
+/// Trims an optional `YYYY-MM-DD` key, treating a blank one as absent, and rejects malformed keys.
+fn trimmed_date_key(v: Option<String>) -> Result<Option<String>, String> {
+    let Some(v) = v.map(|v| v.trim().to_string()).filter(|v| !v.is_empty()) else {
+        return Ok(None);
+    };
+    chrono::NaiveDate::parse_from_str(&v, "%Y-%m-%d")
+        .map_err(|_| format!("invalid date key: {}", v))?;
+    Ok(Some(v))
+}
+
+/// Trims an optional `YYYY-MM-DD` key, defaulting a blank one to today.
+fn parse_date_key(date_key: Option<String>) -> Result<String, String> {
+    Ok(trimmed_date_key(date_key)?
+        .unwrap_or_else(|| crate::core::date_key::today_key_arc().to_string()))
+}
+
+/// Trims optional `YYYY-MM-DD` bounds, treating blank ones as open, and rejects malformed keys
+/// and reversed ranges.
+fn parse_date_range(
     start_key: Option<String>,
     end_key: Option<String>,
-) -> Result<StatisticsAggregates, String> {
-    let start_key = start_key.and_then(|v| {
-        let t = v.trim().to_string();
-        if t.is_empty() { None } else { Some(t) }
-    });
-    let end_key = end_key.and_then(|v| {
-        let t = v.trim().to_string();
-        if t.is_empty() { None } else { Some(t) }
-    });
-
+) -> Result<(Option<String>, Option<String>), String> {
+    let (start_key, end_key) = (trimmed_date_key(start_key)?, trimmed_date_key(end_key)?);
     if let (Some(a), Some(b)) = (start_key.as_deref(), end_key.as_deref()) {
         if a > b {
             return Err("invalid date range: start_key > end_key".to_string());
         }
     }
+    Ok((start_key, end_key))
+}
+
+#[tauri::command]
+pub async fn get_history_aggregates(
+    start_key: Option<String>,
+    end_key: Option<String>,
+) -> Result<StatisticsAggregates, String> {
+    let (start_key, end_key) = parse_date_range(start_key, end_key)?;
 
     crate::core::history_db::load_statistics_aggregates(start_key.as_deref(), end_key.as_deref())
 }
 
+/// Finger load, hand balance, row usage and travel of the range's key counts for each of
+/// `layouts` (all supported ones by default), assuming they were typed on `source` (QWERTY).
+#[tauri::command]
+pub async fn analyze_keyboard_layouts(
+    start_key: Option<String>,
+    end_key: Option<String>,
+    source: Option<LogicalLayout>,
+    layouts: Option<Vec<LogicalLayout>>,
+) -> Result<Vec<LayoutAnalysis>, String> {
+    let (start_key, end_key) = parse_date_range(start_key, end_key)?;
+
+    let keyboard_layout = {
+        let storage = MeritStorage::instance();
+        let storage = storage.read();
+        storage.get_settings().keyboard_layout
+    };
+    let aggregates =
+        crate::core::history_db::load_statistics_aggregates(start_key.as_deref(), end_key.as_deref())?;
+    let layouts = layouts
+        .filter(|l| !l.is_empty())
+        .unwrap_or_else(|| LogicalLayout::ALL.to_vec());
+    Ok(layout_analysis::analyze(
+        &aggregates,
+        &keyboard_layout,
+        source.unwrap_or_default(),
+        &layouts,
+    ))
+}
+
+#[tauri::command]
+pub async fn get_history_buckets(
+    start_key: Option<String>,
+    end_key: Option<String>,
+    granularity: Option<StatisticsGranularity>,
+) -> Result<Vec<StatisticsBucket>, String> {
+    let (start_key, end_key) = parse_date_range(start_key, end_key)?;
+
+    let granularity = granularity.unwrap_or_default();
+    let today = {
+        let storage = MeritStorage::instance();
+        let storage = storage.read();
+        storage.get_stats().today.lite()
+    };
+    let today_key = today.date.to_string();
+
+    let mut buckets = crate::core::history_db::load_range_buckets(
+        start_key.as_deref(),
+        end_key.as_deref(),
+        Some(&today_key),
+        granularity,
+    )?;
+    let in_range = start_key.as_deref().is_none_or(|a| a <= today_key.as_str())
+        && end_key.as_deref().is_none_or(|b| today_key.as_str() <= b);
+    if in_range && today.total > 0 {
+        StatisticsBucket::fold_day(&mut buckets, granularity, &today);
+    }
+    Ok(buckets)
+}
+
+#[tauri::command]
+pub async fn get_day_timeline(date_key: Option<String>) -> Result<Vec<TimelineBucket>, String> {
+    let date_key = parse_date_key(date_key)?;
+    crate::core::history_db::load_timeline(&date_key)
+}
+
+/// Hourly and whole-day typing speed; today's figures lag the live event by under a second.
+#[tauri::command]
+pub async fn get_typing_speed(date_key: Option<String>) -> Result<TypingSpeedDay, String> {
+    let date_key = parse_date_key(date_key)?;
+    crate::core::history_db::load_typing_speed(&date_key)
+}
+
+/// Bigram counts over a date range with same-finger/same-hand totals and the `limit` most
+/// frequent pairs.
+#[tauri::command]
+pub async fn get_key_bigram_stats(
+    start_key: Option<String>,
+    end_key: Option<String>,
+    limit: Option<usize>,
+) -> Result<KeyBigramStats, String> {
+    let (start_key, end_key) = parse_date_range(start_key, end_key)?;
+
+    crate::core::key_bigrams::load_stats(
+        start_key.as_deref(),
+        end_key.as_deref(),
+        limit.unwrap_or(50).clamp(1, 1000),
+    )
+}
+
+/// Correction ratio and accuracy per hour and for the whole day.
+#[tauri::command]
+pub async fn get_typing_accuracy(date_key: Option<String>) -> Result<TypingAccuracyDay, String> {
+    let date_key = parse_date_key(date_key)?;
+    crate::core::typing_accuracy::day(&date_key)
+}
+
+#[tauri::command]
+pub async fn list_sessions(date_key: Option<String>) -> Result<Vec<SessionRecord>, String> {
+    let date_key = parse_date_key(date_key)?;
+    let mut sessions = crate::core::history_db::load_sessions(&date_key)?;
+    if let Some(open) = crate::core::sessions::current() {
+        if open.date_key == date_key {
+            sessions.push(open);
+        }
+    }
+    Ok(sessions)
+}
+
+/// Stats over finished sessions; the one in progress is not included.
+#[tauri::command]
+pub async fn get_session_stats(
+    start_key: Option<String>,
+    end_key: Option<String>,
+) -> Result<SessionStats, String> {
+    let (start_key, end_key) = parse_date_range(start_key, end_key)?;
+
+    crate::core::history_db::load_session_stats(start_key.as_deref(), end_key.as_deref())
+}
+
 #[tauri::command]
 pub async fn add_merit(
     app_handle: AppHandle,
@@ -105,6 +278,7 @@ pub async fn add_merit(
 
 #[tauri::command]
 pub async fn clear_history(app_handle: AppHandle) -> Result<(), String> {
+    crate::core::backup::snapshot_before(&app_handle, "clear_history")?;
     crate::core::history_db::clear_daily_stats();
     let storage = MeritStorage::instance();
     let mut storage = storage.write();
@@ -114,6 +288,7 @@ pub async fn clear_history(app_handle: AppHandle) -> Result<(), String> {
 
 #[tauri::command]
 pub async fn reset_all_merit(app_handle: AppHandle) -> Result<(), String> {
+    crate::core::backup::snapshot_before(&app_handle, "reset_all_merit")?;
     crate::core::history_db::clear_daily_stats();
     let storage = MeritStorage::instance();
     let mut storage = storage.write();
@@ -1,9 +1,12 @@
 pub mod autostart;
 pub mod app_icons;
 pub mod achievements;
+pub mod app_rules;
+pub mod backup;
 pub mod notifications;
 pub mod logs;
 pub mod click_heatmap;
+pub mod history;
 pub mod input;
 pub mod merit;
 pub mod permissions;
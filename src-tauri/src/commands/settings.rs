@@ -1,6 +1,8 @@
 use crate::core::wooden_fish_skins;
 use crate::core::MeritStorage;
-use crate::models::{MouseDistanceDisplaySettings, Settings, StatisticsBlockState};
+use crate::models::{
+    HistoryRetentionSettings, MouseDistanceDisplaySettings, Settings, StatisticsBlockState,
+};
 use tauri::{AppHandle, Emitter, LogicalSize, Manager, Size};
 
 const BASE_WINDOW_SIZE: f64 = 320.0;
@@ -89,6 +91,49 @@ fn normalize_heatmap_levels(levels: u8) -> u8 {
     levels.clamp(5, 15)
 }
 
+fn normalize_timeline_bucket_minutes(minutes: u32) -> u32 {
+    match minutes {
+        1 | 2 | 5 | 10 | 15 | 30 | 60 => minutes,
+        _ => 1,
+    }
+}
+
+fn normalize_timeline_retention_days(days: u32) -> u32 {
+    days.clamp(1, 3650)
+}
+
+fn normalize_session_idle_threshold_secs(secs: u32) -> u32 {
+    secs.clamp(30, 3600)
+}
+
+fn normalize_key_bigram_timeout_ms(ms: u32) -> u32 {
+    ms.clamp(100, 5000)
+}
+
+fn normalize_history_retention(mut retention: HistoryRetentionSettings) -> HistoryRetentionSettings {
+    // 0 means "forever"; otherwise cap at roughly a century.
+    for days in [
+        &mut retention.daily_stats_days,
+        &mut retention.key_counts_days,
+        &mut retention.mouse_button_counts_days,
+        &mut retention.hourly_days,
+        &mut retention.app_input_days,
+        &mut retention.click_heatmap_days,
+        &mut retention.sessions_days,
+    ] {
+        *days = (*days).min(36_500);
+    }
+    retention
+}
+
+fn normalize_local_api_port(port: u16) -> u16 {
+    if port < 1024 {
+        crate::core::local_api::DEFAULT_PORT
+    } else {
+        port
+    }
+}
+
 fn normalize_dock_margin_px(px: u32) -> u32 {
     px.clamp(0, 64)
 }
@@ -303,6 +348,23 @@ pub async fn update_settings(app_handle: AppHandle, settings: Settings) -> Resul
     settings.mouse_distance_displays =
         normalize_mouse_distance_displays(settings.mouse_distance_displays);
     settings.statistics_blocks = normalize_statistics_blocks(settings.statistics_blocks);
+    settings.timeline_bucket_minutes =
+        normalize_timeline_bucket_minutes(settings.timeline_bucket_minutes);
+    settings.timeline_retention_days =
+        normalize_timeline_retention_days(settings.timeline_retention_days);
+    settings.session_idle_threshold_secs =
+        normalize_session_idle_threshold_secs(settings.session_idle_threshold_secs);
+    settings.history_retention = normalize_history_retention(settings.history_retention);
+    settings.key_bigram_timeout_ms =
+        normalize_key_bigram_timeout_ms(settings.key_bigram_timeout_ms);
+    settings.app_rules = crate::core::app_rules::normalize(settings.app_rules);
+    settings.listening_schedule =
+        crate::core::listening_schedule::normalize(settings.listening_schedule);
+    settings.local_api_port = normalize_local_api_port(settings.local_api_port);
+    settings.local_api_token = settings.local_api_token.trim().to_string();
+    if settings.local_api_enabled && settings.local_api_token.is_empty() {
+        settings.local_api_token = crate::core::local_api::generate_token();
+    }
     settings.shortcut_toggle_main = normalize_shortcut(settings.shortcut_toggle_main);
     settings.shortcut_toggle_settings = normalize_shortcut(settings.shortcut_toggle_settings);
     settings.shortcut_toggle_listening = normalize_shortcut(settings.shortcut_toggle_listening);
@@ -315,6 +377,10 @@ pub async fn update_settings(app_handle: AppHandle, settings: Settings) -> Resul
     settings.shortcut_close_custom_statistics =
         normalize_shortcut(settings.shortcut_close_custom_statistics);
 
+    crate::core::history_db::set_retention(crate::core::history_db::RetentionPolicy::from_settings(
+        &settings,
+    ));
+
     let storage = MeritStorage::instance();
     let mut storage = storage.write();
     storage.update_settings(settings.clone());
@@ -322,6 +388,8 @@ pub async fn update_settings(app_handle: AppHandle, settings: Settings) -> Resul
 
     crate::core::mouse_distance::set_tracking_enabled(settings.enable_mouse_single);
     crate::core::keyboard_piano::apply_settings(&settings);
+    crate::core::local_api::apply_settings(&settings);
+    crate::core::listening_schedule::evaluate(&app_handle);
 
     let window = app_handle
         .get_webview_window("main")
@@ -367,3 +435,8 @@ pub async fn toggle_always_on_top(app_handle: AppHandle) -> Result<(), String> {
     settings.always_on_top = !settings.always_on_top;
     update_settings(app_handle, settings).await
 }
+
+#[tauri::command]
+pub async fn get_local_api_status() -> Result<crate::core::local_api::LocalApiStatus, String> {
+    Ok(crate::core::local_api::status())
+}
@@ -500,6 +500,7 @@ pub async fn show_sprite_studio_window(app_handle: AppHandle) -> Result<(), Stri
 #[tauri::command]
 pub async fn quit_app(app_handle: AppHandle) -> Result<(), String> {
     crate::core::window_placement::capture_all_now(&app_handle);
+    crate::core::sessions::close_now();
     crate::core::persistence::flush_now();
     app_handle.exit(0);
     Ok(())
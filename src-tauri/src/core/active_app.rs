@@ -444,7 +444,687 @@ mod imp {
     }
 }
 
-#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+#[cfg(target_os = "linux")]
+mod imp {
+    //! Foreground app sampling for Linux. Wayland compositors that expose an IPC socket (sway/i3,
+    //! Hyprland) and GNOME (through the "Window Calls" Shell extension) are queried directly;
+    //! everything else falls back to X11 (`_NET_ACTIVE_WINDOW` + `_NET_WM_PID` / `WM_CLASS`),
+    //! which also covers XWayland windows.
+
+    use super::set_cached;
+    use super::AppContext;
+    use parking_lot::Mutex;
+    use std::cell::Cell;
+    use std::io::{Read, Write};
+    use std::os::raw::{c_char, c_int, c_void};
+    use std::os::unix::net::UnixStream;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    const IPC_TIMEOUT: Duration = Duration::from_millis(300);
+    const GNOME_MIN_INTERVAL: Duration = Duration::from_millis(1500);
+    const GNOME_RETRY_AFTER_FAILURE: Duration = Duration::from_secs(30);
+
+    /// What a backend knows about the focused window.
+    #[derive(Debug, Default, PartialEq, Eq)]
+    pub(super) struct FocusedWindow {
+        /// Wayland `app_id` or X11 `WM_CLASS` class.
+        pub app_id: Option<String>,
+        pub pid: Option<u32>,
+    }
+
+    fn exe_of(pid: u32) -> Option<String> {
+        std::fs::read_link(format!("/proc/{}/exe", pid))
+            .ok()
+            .map(|p| p.to_string_lossy().trim_end_matches(" (deleted)").to_string())
+    }
+
+    fn comm_of(pid: u32) -> Option<String> {
+        std::fs::read_to_string(format!("/proc/{}/comm", pid))
+            .ok()
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    /// `org.gnome.Nautilus` -> `Nautilus`.
+    fn display_name(app_id: &str) -> String {
+        app_id.rsplit('.').next().unwrap_or(app_id).to_string()
+    }
+
+    /// Prefers the window class / app id (stable across distros and shared runtimes like Electron)
+    /// and falls back to the executable path.
+    pub(super) fn resolve(window: FocusedWindow) -> Option<AppContext> {
+        let app_id = window.app_id.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        match (app_id, window.pid.filter(|pid| *pid > 0)) {
+            (Some(app_id), _) => Some(AppContext {
+                name: Some(Arc::from(display_name(&app_id))),
+                id: Arc::from(app_id),
+            }),
+            (None, Some(pid)) => {
+                let exe = exe_of(pid)?;
+                let name = comm_of(pid).or_else(|| {
+                    std::path::Path::new(&exe)
+                        .file_name()
+                        .map(|s| s.to_string_lossy().to_string())
+                });
+                Some(AppContext {
+                    id: Arc::from(exe),
+                    name: name.map(Arc::from),
+                })
+            }
+            (None, None) => None,
+        }
+    }
+
+    fn env_set(name: &str) -> bool {
+        std::env::var_os(name).is_some_and(|v| !v.is_empty())
+    }
+
+    fn is_wayland() -> bool {
+        env_set("WAYLAND_DISPLAY")
+    }
+
+    fn is_gnome() -> bool {
+        std::env::var("XDG_CURRENT_DESKTOP")
+            .map(|v| v.split(':').any(|d| d.eq_ignore_ascii_case("gnome")))
+            .unwrap_or(false)
+    }
+
+    pub(super) fn query_frontmost_app() -> Option<AppContext> {
+        if env_set("SWAYSOCK") || env_set("I3SOCK") {
+            if let Some(window) = sway::focused() {
+                return resolve(window);
+            }
+        }
+        if env_set("HYPRLAND_INSTANCE_SIGNATURE") {
+            if let Some(window) = hyprland::focused() {
+                return resolve(window);
+            }
+        }
+        if is_wayland() && is_gnome() {
+            if let Some(app) = gnome::focused() {
+                return app;
+            }
+        }
+        if env_set("DISPLAY") {
+            return x11::focused().and_then(resolve);
+        }
+        None
+    }
+
+    static WATCHER_STARTED: AtomicBool = AtomicBool::new(false);
+
+    /// Event-driven updates are only available on a plain X11 session; Wayland backends are polled.
+    pub(super) fn start_foreground_watcher() -> bool {
+        if is_wayland() || !env_set("DISPLAY") {
+            return false;
+        }
+        if WATCHER_STARTED.swap(true, Ordering::Relaxed) {
+            return true;
+        }
+
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel::<bool>();
+        let spawned = std::thread::Builder::new()
+            .name("active_app_x11_watcher".to_string())
+            .spawn(move || x11::watch(ready_tx, || {
+                let next = crate::core::perf::time(
+                    crate::core::perf::TimerKind::ActiveAppQuery,
+                    || x11::focused().and_then(resolve),
+                )
+                .unwrap_or_else(AppContext::unknown);
+                set_cached(next);
+            }))
+            .is_ok();
+
+        if spawned && matches!(ready_rx.recv_timeout(Duration::from_millis(800)), Ok(true)) {
+            return true;
+        }
+        WATCHER_STARTED.store(false, Ordering::Relaxed);
+        false
+    }
+
+    mod sway {
+        //! i3-compatible IPC (`$SWAYSOCK` / `$I3SOCK`): `GET_TREE` and find the focused container.
+
+        use super::{FocusedWindow, IPC_TIMEOUT};
+        use super::{Read, UnixStream, Write};
+
+        const MAGIC: &[u8] = b"i3-ipc";
+        const GET_TREE: u32 = 4;
+
+        fn request(message_type: u32) -> Option<Vec<u8>> {
+            let path = std::env::var_os("SWAYSOCK").or_else(|| std::env::var_os("I3SOCK"))?;
+            let mut stream = UnixStream::connect(path).ok()?;
+            stream.set_read_timeout(Some(IPC_TIMEOUT)).ok()?;
+            stream.set_write_timeout(Some(IPC_TIMEOUT)).ok()?;
+
+            let mut msg = Vec::with_capacity(14);
+            msg.extend_from_slice(MAGIC);
+            msg.extend_from_slice(&0u32.to_ne_bytes());
+            msg.extend_from_slice(&message_type.to_ne_bytes());
+            stream.write_all(&msg).ok()?;
+
+            let mut header = [0u8; 14];
+            stream.read_exact(&mut header).ok()?;
+            if &header[..6] != MAGIC {
+                return None;
+            }
+            let len = u32::from_ne_bytes(header[6..10].try_into().ok()?) as usize;
+            let mut body = vec![0u8; len];
+            stream.read_exact(&mut body).ok()?;
+            Some(body)
+        }
+
+        pub(in super::super) fn parse_focused(tree: &serde_json::Value) -> Option<FocusedWindow> {
+            if tree.get("focused").and_then(|v| v.as_bool()) == Some(true) {
+                let app_id = tree
+                    .get("app_id")
+                    .and_then(|v| v.as_str())
+                    .or_else(|| {
+                        tree.pointer("/window_properties/class")
+                            .and_then(|v| v.as_str())
+                    })
+                    .map(str::to_string);
+                let pid = tree
+                    .get("pid")
+                    .and_then(|v| v.as_u64())
+                    .and_then(|v| u32::try_from(v).ok());
+                // A focused workspace or output has neither.
+                if app_id.is_some() || pid.is_some() {
+                    return Some(FocusedWindow { app_id, pid });
+                }
+                return None;
+            }
+            ["nodes", "floating_nodes"]
+                .iter()
+                .filter_map(|key| tree.get(*key).and_then(|v| v.as_array()))
+                .flatten()
+                .find_map(parse_focused)
+        }
+
+        pub(super) fn focused() -> Option<FocusedWindow> {
+            let body = request(GET_TREE)?;
+            let tree: serde_json::Value = serde_json::from_slice(&body).ok()?;
+            parse_focused(&tree)
+        }
+    }
+
+    mod hyprland {
+        //! Hyprland's request socket: `j/activewindow` returns the focused client as JSON.
+
+        use super::{FocusedWindow, IPC_TIMEOUT};
+        use super::{Read, UnixStream, Write};
+        use std::path::PathBuf;
+
+        fn socket_path() -> Option<PathBuf> {
+            let sig = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+            let runtime = std::env::var_os("XDG_RUNTIME_DIR").map(PathBuf::from);
+            [
+                runtime.map(|dir| dir.join("hypr").join(&sig).join(".socket.sock")),
+                Some(PathBuf::from("/tmp/hypr").join(&sig).join(".socket.sock")),
+            ]
+            .into_iter()
+            .flatten()
+            .find(|p| p.exists())
+        }
+
+        pub(super) fn focused() -> Option<FocusedWindow> {
+            let mut stream = UnixStream::connect(socket_path()?).ok()?;
+            stream.set_read_timeout(Some(IPC_TIMEOUT)).ok()?;
+            stream.set_write_timeout(Some(IPC_TIMEOUT)).ok()?;
+            stream.write_all(b"j/activewindow").ok()?;
+            let mut body = Vec::new();
+            stream.read_to_end(&mut body).ok()?;
+
+            let window: serde_json::Value = serde_json::from_slice(&body).ok()?;
+            let app_id = window
+                .get("class")
+                .or_else(|| window.get("initialClass"))
+                .and_then(|v| v.as_str())
+                .map(str::to_string);
+            let pid = window
+                .get("pid")
+                .and_then(|v| v.as_i64())
+                .and_then(|v| u32::try_from(v).ok());
+            (app_id.is_some() || pid.is_some()).then_some(FocusedWindow { app_id, pid })
+        }
+    }
+
+    mod gnome {
+        //! GNOME on Wayland does not expose the focused window to clients. If the "Window Calls"
+        //! Shell extension is installed, its D-Bus `List` method does. Calls go through `gdbus`
+        //! and are rate limited since they spawn a process.
+
+        use super::{resolve, AppContext, FocusedWindow, Mutex};
+        use super::{GNOME_MIN_INTERVAL, GNOME_RETRY_AFTER_FAILURE};
+        use once_cell::sync::Lazy;
+        use std::time::Instant;
+
+        struct Cache {
+            next_query_at: Option<Instant>,
+            last: Option<AppContext>,
+        }
+
+        static CACHE: Lazy<Mutex<Cache>> = Lazy::new(|| {
+            Mutex::new(Cache {
+                next_query_at: None,
+                last: None,
+            })
+        });
+
+        /// `gdbus` prints `('<json>',)`.
+        pub(in super::super) fn parse_list_output(raw: &str) -> Option<FocusedWindow> {
+            let json = raw.trim().strip_prefix("('")?.strip_suffix("',)")?;
+            let windows: serde_json::Value = serde_json::from_str(json).ok()?;
+            let focused = windows
+                .as_array()?
+                .iter()
+                .find(|w| w.get("focus").and_then(|v| v.as_bool()) == Some(true))?;
+            Some(FocusedWindow {
+                app_id: focused
+                    .get("wm_class")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string),
+                pid: focused
+                    .get("pid")
+                    .and_then(|v| v.as_u64())
+                    .and_then(|v| u32::try_from(v).ok()),
+            })
+        }
+
+        fn query() -> Option<FocusedWindow> {
+            let out = std::process::Command::new("gdbus")
+                .args([
+                    "call",
+                    "--session",
+                    "--dest",
+                    "org.gnome.Shell",
+                    "--object-path",
+                    "/org/gnome/Shell/Extensions/Windows",
+                    "--method",
+                    "org.gnome.Shell.Extensions.Windows.List",
+                ])
+                .output()
+                .ok()?;
+            if !out.status.success() {
+                return None;
+            }
+            parse_list_output(&String::from_utf8_lossy(&out.stdout))
+        }
+
+        /// `None` when the extension is unavailable, so callers can try X11 instead.
+        pub(super) fn focused() -> Option<Option<AppContext>> {
+            let now = Instant::now();
+            let mut cache = CACHE.lock();
+            if cache.next_query_at.is_some_and(|at| now < at) {
+                return cache.last.clone().map(Some);
+            }
+
+            match query() {
+                Some(window) => {
+                    cache.next_query_at = Some(now + GNOME_MIN_INTERVAL);
+                    let app = resolve(window);
+                    cache.last = app.clone();
+                    Some(app)
+                }
+                None => {
+                    cache.next_query_at = Some(now + GNOME_RETRY_AFTER_FAILURE);
+                    cache.last = None;
+                    None
+                }
+            }
+        }
+    }
+
+    /// Talks to the X server through libxcb rather than Xlib: xcb hands errors back with each
+    /// reply instead of through a process-wide error handler, so a window that disappears between
+    /// reads (`BadWindow`) only fails that read.
+    mod x11 {
+        use super::{c_char, c_int, c_void};
+        use super::{Cell, FocusedWindow};
+
+        type Connection = c_void;
+        type Window = u32;
+        type Atom = u32;
+
+        const ANY_PROPERTY_TYPE: Atom = 0;
+        const CW_EVENT_MASK: u32 = 1 << 11;
+        const EVENT_MASK_PROPERTY_CHANGE: u32 = 1 << 22;
+        const PROPERTY_NOTIFY: u8 = 28;
+        /// In 32-bit units, as the protocol counts property lengths.
+        const PROPERTY_MAX_LONGS: u32 = 1024;
+
+        #[repr(C)]
+        #[derive(Clone, Copy)]
+        struct Cookie {
+            sequence: u32,
+        }
+
+        #[repr(C)]
+        struct ScreenIterator {
+            data: *const Screen,
+            rem: c_int,
+            index: c_int,
+        }
+
+        /// Only the leading field is read.
+        #[repr(C)]
+        struct Screen {
+            root: Window,
+        }
+
+        #[repr(C)]
+        struct InternAtomReply {
+            response_type: u8,
+            pad0: u8,
+            sequence: u16,
+            length: u32,
+            atom: Atom,
+        }
+
+        #[repr(C)]
+        struct GetPropertyReply {
+            response_type: u8,
+            format: u8,
+            sequence: u16,
+            length: u32,
+            type_: Atom,
+            bytes_after: u32,
+            value_len: u32,
+            pad0: [u8; 12],
+        }
+
+        #[repr(C)]
+        struct PropertyNotifyEvent {
+            response_type: u8,
+            pad0: u8,
+            sequence: u16,
+            window: Window,
+            atom: Atom,
+            time: u32,
+            state: u8,
+            pad1: [u8; 3],
+        }
+
+        // Linked directly: building needs `libxcb1-dev` (listed with the other Linux packages in
+        // the CI and release workflows) and running needs `libxcb1`, which WebKitGTK already pulls in.
+        #[link(name = "xcb")]
+        extern "C" {
+            fn xcb_connect(display_name: *const c_char, screen: *mut c_int) -> *mut Connection;
+            fn xcb_connection_has_error(conn: *mut Connection) -> c_int;
+            fn xcb_disconnect(conn: *mut Connection);
+            fn xcb_get_setup(conn: *mut Connection) -> *const c_void;
+            fn xcb_setup_roots_iterator(setup: *const c_void) -> ScreenIterator;
+            fn xcb_screen_next(iter: *mut ScreenIterator);
+            fn xcb_intern_atom(
+                conn: *mut Connection,
+                only_if_exists: u8,
+                name_len: u16,
+                name: *const c_char,
+            ) -> Cookie;
+            fn xcb_intern_atom_reply(
+                conn: *mut Connection,
+                cookie: Cookie,
+                error: *mut *mut c_void,
+            ) -> *mut InternAtomReply;
+            fn xcb_get_property(
+                conn: *mut Connection,
+                delete: u8,
+                window: Window,
+                property: Atom,
+                type_: Atom,
+                long_offset: u32,
+                long_length: u32,
+            ) -> Cookie;
+            fn xcb_get_property_reply(
+                conn: *mut Connection,
+                cookie: Cookie,
+                error: *mut *mut c_void,
+            ) -> *mut GetPropertyReply;
+            fn xcb_get_property_value(reply: *const GetPropertyReply) -> *const u8;
+            fn xcb_get_property_value_length(reply: *const GetPropertyReply) -> c_int;
+            fn xcb_change_window_attributes(
+                conn: *mut Connection,
+                window: Window,
+                value_mask: u32,
+                value_list: *const u32,
+            ) -> Cookie;
+            fn xcb_flush(conn: *mut Connection) -> c_int;
+            fn xcb_wait_for_event(conn: *mut Connection) -> *mut PropertyNotifyEvent;
+        }
+
+        extern "C" {
+            fn free(ptr: *mut c_void);
+        }
+
+        /// Frees a reply or error xcb allocated with `malloc`; null is fine.
+        unsafe fn release<T>(ptr: *mut T) {
+            free(ptr as *mut c_void);
+        }
+
+        struct Atoms {
+            net_active_window: Atom,
+            net_wm_pid: Atom,
+            wm_class: Atom,
+        }
+
+        /// One connection per sampling thread.
+        struct Conn {
+            conn: *mut Connection,
+            root: Window,
+            atoms: Atoms,
+        }
+
+        impl Conn {
+            fn open() -> Option<Self> {
+                unsafe {
+                    let mut screen: c_int = 0;
+                    let conn = xcb_connect(std::ptr::null(), &mut screen);
+                    if conn.is_null() {
+                        return None;
+                    }
+                    if xcb_connection_has_error(conn) != 0 {
+                        xcb_disconnect(conn);
+                        return None;
+                    }
+                    let mut roots = xcb_setup_roots_iterator(xcb_get_setup(conn));
+                    for _ in 0..screen {
+                        xcb_screen_next(&mut roots);
+                    }
+                    if roots.data.is_null() {
+                        xcb_disconnect(conn);
+                        return None;
+                    }
+                    let root = (*roots.data).root;
+                    let atom = |name: &str| {
+                        let cookie = xcb_intern_atom(
+                            conn,
+                            0,
+                            name.len() as u16,
+                            name.as_ptr() as *const c_char,
+                        );
+                        let mut error = std::ptr::null_mut();
+                        let reply = xcb_intern_atom_reply(conn, cookie, &mut error);
+                        release(error);
+                        if reply.is_null() {
+                            return 0;
+                        }
+                        let atom = (*reply).atom;
+                        release(reply);
+                        atom
+                    };
+                    Some(Self {
+                        conn,
+                        root,
+                        atoms: Atoms {
+                            net_active_window: atom("_NET_ACTIVE_WINDOW"),
+                            net_wm_pid: atom("_NET_WM_PID"),
+                            wm_class: atom("WM_CLASS"),
+                        },
+                    })
+                }
+            }
+
+            /// Raw property bytes plus the item format (8/16/32). `None` if the request failed,
+            /// e.g. because the window is gone.
+            fn property(&self, window: Window, property: Atom) -> Option<(Vec<u8>, u8)> {
+                unsafe {
+                    let cookie = xcb_get_property(
+                        self.conn,
+                        0,
+                        window,
+                        property,
+                        ANY_PROPERTY_TYPE,
+                        0,
+                        PROPERTY_MAX_LONGS,
+                    );
+                    let mut error = std::ptr::null_mut();
+                    let reply = xcb_get_property_reply(self.conn, cookie, &mut error);
+                    if !error.is_null() || reply.is_null() {
+                        release(error);
+                        release(reply);
+                        return None;
+                    }
+                    let len = usize::try_from(xcb_get_property_value_length(reply)).unwrap_or(0);
+                    let value = xcb_get_property_value(reply);
+                    let bytes = if len == 0 || value.is_null() {
+                        Vec::new()
+                    } else {
+                        std::slice::from_raw_parts(value, len).to_vec()
+                    };
+                    let format = (*reply).format;
+                    release(reply);
+                    Some((bytes, format))
+                }
+            }
+
+            fn cardinal(&self, window: Window, property: Atom) -> Option<u32> {
+                let (bytes, format) = self.property(window, property)?;
+                if format != 32 {
+                    return None;
+                }
+                Some(u32::from_ne_bytes(bytes.get(..4)?.try_into().ok()?))
+            }
+
+            fn focused(&self) -> Option<FocusedWindow> {
+                let window = self.cardinal(self.root, self.atoms.net_active_window)?;
+                if window == 0 {
+                    return None;
+                }
+                let pid = self.cardinal(window, self.atoms.net_wm_pid);
+                let app_id = self
+                    .property(window, self.atoms.wm_class)
+                    .filter(|(_, format)| *format == 8)
+                    .and_then(|(bytes, _)| parse_wm_class(&bytes));
+                Some(FocusedWindow { app_id, pid })
+            }
+        }
+
+        /// `WM_CLASS` is `instance\0class\0`; the class is what desktop entries match on.
+        pub(in super::super) fn parse_wm_class(bytes: &[u8]) -> Option<String> {
+            let mut parts = bytes
+                .split(|b| *b == 0)
+                .map(|p| String::from_utf8_lossy(p).trim().to_string());
+            let instance = parts.next().filter(|s| !s.is_empty());
+            parts.next().filter(|s| !s.is_empty()).or(instance)
+        }
+
+        thread_local! {
+            static CONN: Cell<Option<&'static Conn>> = const { Cell::new(None) };
+        }
+
+        pub(super) fn focused() -> Option<FocusedWindow> {
+            let conn = CONN.with(|cell| {
+                if cell.get().is_none() {
+                    // Leaked on purpose: one connection per sampling thread for the process lifetime.
+                    cell.set(Conn::open().map(|c| &*Box::leak(Box::new(c))));
+                }
+                cell.get()
+            })?;
+            conn.focused()
+        }
+
+        /// Blocks until the connection breaks, calling `on_change` whenever the root window's
+        /// active-window property changes.
+        pub(super) fn watch(ready: std::sync::mpsc::Sender<bool>, on_change: impl Fn()) {
+            let Some(conn) = Conn::open() else {
+                let _ = ready.send(false);
+                return;
+            };
+            unsafe {
+                let mask = EVENT_MASK_PROPERTY_CHANGE;
+                xcb_change_window_attributes(conn.conn, conn.root, CW_EVENT_MASK, &mask);
+                xcb_flush(conn.conn);
+            }
+            let _ = ready.send(true);
+
+            loop {
+                let event = unsafe { xcb_wait_for_event(conn.conn) };
+                if event.is_null() {
+                    // Only happens once the connection has failed.
+                    unsafe { xcb_disconnect(conn.conn) };
+                    return;
+                }
+                // Errors for requests without a reply arrive here too, as response type 0.
+                let changed = unsafe {
+                    (*event).response_type & 0x7f == PROPERTY_NOTIFY
+                        && (*event).atom == conn.atoms.net_active_window
+                };
+                unsafe { release(event) };
+                if changed {
+                    on_change();
+                }
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn finds_focused_sway_container() {
+            let tree = serde_json::json!({
+                "type": "root",
+                "nodes": [{
+                    "type": "workspace",
+                    "nodes": [
+                        {"focused": false, "app_id": "foot", "pid": 10},
+                        {"focused": false, "nodes": [], "floating_nodes": [
+                            {"focused": true, "app_id": null, "pid": 42,
+                             "window_properties": {"class": "Firefox", "instance": "Navigator"}}
+                        ]}
+                    ]
+                }]
+            });
+            assert_eq!(
+                sway::parse_focused(&tree),
+                Some(FocusedWindow {
+                    app_id: Some("Firefox".to_string()),
+                    pid: Some(42)
+                })
+            );
+        }
+
+        #[test]
+        fn parses_wm_class_and_gnome_window_list() {
+            assert_eq!(
+                x11::parse_wm_class(b"gnome-terminal-server\0Gnome-terminal\0").as_deref(),
+                Some("Gnome-terminal")
+            );
+            let window = gnome::parse_list_output(
+                "('[{\"wm_class\":\"org.gnome.Nautilus\",\"pid\":7,\"focus\":true}]',)\n",
+            )
+            .unwrap();
+            let app = resolve(window).unwrap();
+            assert_eq!(&*app.id, "org.gnome.Nautilus");
+            assert_eq!(app.name.as_deref(), Some("Nautilus"));
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
 mod imp {
     use super::AppContext;
 
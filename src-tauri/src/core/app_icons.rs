@@ -193,7 +193,455 @@ mod imp {
     }
 }
 
-#[cfg(not(target_os = "macos"))]
+#[cfg(target_os = "linux")]
+mod imp {
+    //! Resolves an app id (Wayland `app_id`, X11 `WM_CLASS`, or an executable path) to a desktop
+    //! entry, follows its `Icon=` through the freedesktop icon theme lookup and renders it to a
+    //! square PNG.
+
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine as _;
+    use once_cell::sync::Lazy;
+    use parking_lot::Mutex;
+    use std::collections::HashMap;
+    use std::path::{Path, PathBuf};
+    use std::time::{Duration, Instant};
+
+    const ICON_SIZE: u32 = 64;
+    const ENTRY_INDEX_TTL: Duration = Duration::from_secs(300);
+    const ICON_EXTENSIONS: &[&str] = &["png", "svg"];
+
+    pub(super) fn get_app_icon_png_base64(app_id: &str) -> Option<String> {
+        let dirs = data_dirs();
+        let path = find_icon_path(app_id, &dirs, &theme_names(&dirs))?;
+        let png = render_png(&path, ICON_SIZE)?;
+        Some(STANDARD.encode(png))
+    }
+
+    fn home_dir() -> Option<PathBuf> {
+        std::env::var_os("HOME")
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    }
+
+    fn env_dir(name: &str, fallback: &str) -> Option<PathBuf> {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| home_dir().map(|h| h.join(fallback)))
+    }
+
+    /// `$XDG_DATA_HOME` followed by `$XDG_DATA_DIRS`, most specific first.
+    fn data_dirs() -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = env_dir("XDG_DATA_HOME", ".local/share").into_iter().collect();
+        let system = std::env::var("XDG_DATA_DIRS")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+        dirs.extend(system.split(':').filter(|d| !d.is_empty()).map(PathBuf::from));
+        dirs
+    }
+
+    /// Minimal key-file parser: `section -> key -> value`, skipping localized keys.
+    fn parse_key_file(text: &str) -> HashMap<String, HashMap<String, String>> {
+        let mut out: HashMap<String, HashMap<String, String>> = HashMap::new();
+        let mut section = String::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.to_string();
+                continue;
+            }
+            if let Some((key, value)) = line.split_once('=') {
+                let key = key.trim();
+                if key.contains('[') {
+                    continue;
+                }
+                out.entry(section.clone())
+                    .or_default()
+                    .entry(key.to_string())
+                    .or_insert_with(|| value.trim().to_string());
+            }
+        }
+        out
+    }
+
+    #[derive(Debug, Clone)]
+    struct DesktopEntry {
+        /// File name without `.desktop`, e.g. `org.gnome.Nautilus`.
+        id: String,
+        wm_class: Option<String>,
+        exec: Option<String>,
+        icon: String,
+    }
+
+    /// Basename of the program in an `Exec=` line, skipping an `env VAR=value` prefix.
+    fn exec_program(exec: &str) -> Option<String> {
+        exec.split_whitespace()
+            .map(|t| t.trim_matches('"'))
+            // Skip `env` and `VAR=value` assignments.
+            .find(|t| *t != "env" && (t.starts_with('/') || !t.contains('=')))
+            .and_then(|t| Path::new(t).file_name())
+            .map(|n| n.to_string_lossy().to_string())
+    }
+
+    fn read_desktop_entry(path: &Path) -> Option<DesktopEntry> {
+        let text = std::fs::read_to_string(path).ok()?;
+        let mut file = parse_key_file(&text);
+        let entry = file.remove("Desktop Entry")?;
+        Some(DesktopEntry {
+            id: path.file_stem()?.to_string_lossy().to_string(),
+            wm_class: entry.get("StartupWMClass").cloned(),
+            exec: entry.get("Exec").and_then(|e| exec_program(e)),
+            icon: entry.get("Icon").filter(|v| !v.is_empty())?.clone(),
+        })
+    }
+
+    fn collect_desktop_entries(dir: &Path, out: &mut Vec<DesktopEntry>, depth: u32) {
+        let Ok(read) = std::fs::read_dir(dir) else {
+            return;
+        };
+        for entry in read.flatten() {
+            let path = entry.path();
+            if path.is_dir() && depth < 2 {
+                collect_desktop_entries(&path, out, depth + 1);
+            } else if path.extension().is_some_and(|e| e == "desktop") {
+                out.extend(read_desktop_entry(&path));
+            }
+        }
+    }
+
+    /// Desktop entries in lookup order; an entry in an earlier data dir shadows later ones.
+    fn desktop_entries(dirs: &[PathBuf]) -> Vec<DesktopEntry> {
+        let mut out = Vec::new();
+        for dir in dirs {
+            collect_desktop_entries(&dir.join("applications"), &mut out, 0);
+        }
+        out
+    }
+
+    struct EntryIndex {
+        built_at: Instant,
+        dirs: Vec<PathBuf>,
+        entries: Vec<DesktopEntry>,
+    }
+
+    static ENTRY_INDEX: Lazy<Mutex<Option<EntryIndex>>> = Lazy::new(|| Mutex::new(None));
+
+    fn cached_desktop_entries(dirs: &[PathBuf]) -> Vec<DesktopEntry> {
+        let mut index = ENTRY_INDEX.lock();
+        if let Some(cached) = index.as_ref() {
+            if cached.built_at.elapsed() < ENTRY_INDEX_TTL && cached.dirs.as_slice() == dirs {
+                return cached.entries.clone();
+            }
+        }
+        let entries = desktop_entries(dirs);
+        *index = Some(EntryIndex {
+            built_at: Instant::now(),
+            dirs: dirs.to_vec(),
+            entries: entries.clone(),
+        });
+        entries
+    }
+
+    /// Picks the desktop entry that best matches `app_id`, by decreasing confidence.
+    fn match_desktop_entry<'a>(app_id: &str, entries: &'a [DesktopEntry]) -> Option<&'a DesktopEntry> {
+        let is_path = app_id.starts_with('/');
+        let base = if is_path {
+            Path::new(app_id).file_name()?.to_string_lossy().to_string()
+        } else {
+            app_id.to_string()
+        };
+        let eq = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+        let last_segment = |id: &str| id.rsplit('.').next().unwrap_or(id).to_string();
+
+        let rules: [&dyn Fn(&DesktopEntry) -> bool; 4] = [
+            &|e| !is_path && eq(&e.id, &base),
+            &|e| !is_path && e.wm_class.as_deref().is_some_and(|c| eq(c, &base)),
+            &|e| eq(&last_segment(&e.id), &base),
+            &|e| e.exec.as_deref().is_some_and(|x| eq(x, &base)),
+        ];
+        rules.iter().find_map(|rule| entries.iter().find(|e| rule(e)))
+    }
+
+    /// Configured icon theme (GTK, then KDE), if any; `hicolor` is always appended by the lookup.
+    fn configured_theme() -> Option<String> {
+        let config = env_dir("XDG_CONFIG_HOME", ".config")?;
+        let read = |rel: &str, section: &str, key: &str| {
+            let text = std::fs::read_to_string(config.join(rel)).ok()?;
+            parse_key_file(&text)
+                .get(section)?
+                .get(key)
+                .cloned()
+                .filter(|v| !v.is_empty())
+        };
+        read("gtk-3.0/settings.ini", "Settings", "gtk-icon-theme-name")
+            .or_else(|| read("gtk-4.0/settings.ini", "Settings", "gtk-icon-theme-name"))
+            .or_else(|| read("kdeglobals", "Icons", "Theme"))
+    }
+
+    fn icon_base_dirs(dirs: &[PathBuf]) -> Vec<PathBuf> {
+        let mut out: Vec<PathBuf> = home_dir().map(|h| h.join(".icons")).into_iter().collect();
+        out.extend(dirs.iter().map(|d| d.join("icons")));
+        out
+    }
+
+    #[derive(Debug)]
+    struct ThemeDir {
+        path: String,
+        size: u32,
+        min_size: u32,
+        max_size: u32,
+        kind: ThemeDirKind,
+        threshold: u32,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum ThemeDirKind {
+        Fixed,
+        Scalable,
+        Threshold,
+    }
+
+    impl ThemeDir {
+        /// `DirectorySizeDistance` from the icon theme spec.
+        fn distance(&self, size: u32) -> u32 {
+            let (min, max) = match self.kind {
+                ThemeDirKind::Fixed => (self.size, self.size),
+                ThemeDirKind::Scalable => (self.min_size, self.max_size),
+                ThemeDirKind::Threshold => (
+                    self.size.saturating_sub(self.threshold),
+                    self.size.saturating_add(self.threshold),
+                ),
+            };
+            if size < min {
+                min - size
+            } else {
+                size.saturating_sub(max)
+            }
+        }
+    }
+
+    struct Theme {
+        name: String,
+        dirs: Vec<ThemeDir>,
+        inherits: Vec<String>,
+    }
+
+    fn load_theme(name: &str, bases: &[PathBuf]) -> Option<Theme> {
+        let text = bases
+            .iter()
+            .find_map(|b| std::fs::read_to_string(b.join(name).join("index.theme")).ok())?;
+        let file = parse_key_file(&text);
+        let header = file.get("Icon Theme")?;
+        let list = |key: &str| -> Vec<String> {
+            header
+                .get(key)
+                .map(|v| {
+                    v.split(',')
+                        .map(|s| s.trim().to_string())
+                        .filter(|s| !s.is_empty())
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let dirs = list("Directories")
+            .into_iter()
+            .filter_map(|dir| {
+                let section = file.get(&dir)?;
+                let num = |key: &str| section.get(key).and_then(|v| v.parse::<u32>().ok());
+                if num("Scale").unwrap_or(1) != 1 {
+                    return None;
+                }
+                let size = num("Size")?;
+                let kind = match section.get("Type").map(String::as_str) {
+                    Some("Fixed") => ThemeDirKind::Fixed,
+                    Some("Scalable") => ThemeDirKind::Scalable,
+                    _ => ThemeDirKind::Threshold,
+                };
+                Some(ThemeDir {
+                    path: dir,
+                    size,
+                    min_size: num("MinSize").unwrap_or(size),
+                    max_size: num("MaxSize").unwrap_or(size),
+                    kind,
+                    threshold: num("Threshold").unwrap_or(2),
+                })
+            })
+            .collect();
+        Some(Theme {
+            name: name.to_string(),
+            dirs,
+            inherits: list("Inherits"),
+        })
+    }
+
+    fn lookup_in_theme(icon: &str, theme: &str, bases: &[PathBuf], size: u32) -> Option<PathBuf> {
+        let theme = load_theme(theme, bases)?;
+        let mut best: Option<(u32, PathBuf)> = None;
+        for dir in &theme.dirs {
+            let distance = dir.distance(size);
+            if best.as_ref().is_some_and(|(d, _)| *d <= distance) {
+                continue;
+            }
+            let hit = bases.iter().find_map(|base| {
+                ICON_EXTENSIONS.iter().find_map(|ext| {
+                    let p = base
+                        .join(&theme.name)
+                        .join(&dir.path)
+                        .join(format!("{}.{}", icon, ext));
+                    p.is_file().then_some(p)
+                })
+            });
+            if let Some(hit) = hit {
+                best = Some((distance, hit));
+            }
+        }
+        best.map(|(_, p)| p)
+    }
+
+    /// Theme chain to search: the configured theme, its parents, then `hicolor`.
+    fn theme_names(dirs: &[PathBuf]) -> Vec<String> {
+        let bases = icon_base_dirs(dirs);
+        let mut out = Vec::new();
+        let mut queue: Vec<String> = configured_theme().into_iter().collect();
+        while let Some(name) = queue.pop() {
+            if out.contains(&name) || out.len() > 16 {
+                continue;
+            }
+            if let Some(theme) = load_theme(&name, &bases) {
+                queue.extend(theme.inherits.into_iter().rev());
+            }
+            out.push(name);
+        }
+        if !out.iter().any(|n| n == "hicolor") {
+            out.push("hicolor".to_string());
+        }
+        out
+    }
+
+    fn lookup_icon(icon: &str, dirs: &[PathBuf], themes: &[String]) -> Option<PathBuf> {
+        let icon_path = Path::new(icon);
+        if icon_path.is_absolute() {
+            return icon_path.is_file().then(|| icon_path.to_path_buf());
+        }
+
+        let bases = icon_base_dirs(dirs);
+        for theme in themes {
+            if let Some(hit) = lookup_in_theme(icon, theme, &bases, ICON_SIZE) {
+                return Some(hit);
+            }
+        }
+        // Unthemed fallbacks.
+        dirs.iter()
+            .map(|d| d.join("pixmaps"))
+            .chain(bases.iter().cloned())
+            .find_map(|dir| {
+                ICON_EXTENSIONS.iter().find_map(|ext| {
+                    let p = dir.join(format!("{}.{}", icon, ext));
+                    p.is_file().then_some(p)
+                })
+            })
+    }
+
+    fn find_icon_path(app_id: &str, dirs: &[PathBuf], themes: &[String]) -> Option<PathBuf> {
+        let entries = cached_desktop_entries(dirs);
+        if let Some(entry) = match_desktop_entry(app_id, &entries) {
+            if let Some(hit) = lookup_icon(&entry.icon, dirs, themes) {
+                return Some(hit);
+            }
+        }
+        // Many apps name their icon after the window class.
+        if app_id.starts_with('/') {
+            return None;
+        }
+        lookup_icon(app_id, dirs, themes)
+            .or_else(|| lookup_icon(&app_id.to_ascii_lowercase(), dirs, themes))
+    }
+
+    /// Loads a PNG or SVG and renders it centered into a `size`x`size` PNG.
+    fn render_png(path: &Path, size: u32) -> Option<Vec<u8>> {
+        use resvg::tiny_skia::{FilterQuality, Pixmap, PixmapPaint, Transform};
+
+        let data = std::fs::read(path).ok()?;
+        let mut canvas = Pixmap::new(size, size)?;
+        let fit = |w: f32, h: f32| {
+            let scale = size as f32 / w.max(h).max(1.0);
+            let dx = (size as f32 - w * scale) / 2.0;
+            let dy = (size as f32 - h * scale) / 2.0;
+            Transform::from_scale(scale, scale).post_translate(dx, dy)
+        };
+
+        if path.extension().is_some_and(|e| e.eq_ignore_ascii_case("svg")) {
+            let tree = resvg::usvg::Tree::from_data(&data, &resvg::usvg::Options::default()).ok()?;
+            let s = tree.size();
+            resvg::render(&tree, fit(s.width(), s.height()), &mut canvas.as_mut());
+        } else {
+            let src = Pixmap::decode_png(&data).ok()?;
+            let paint = PixmapPaint {
+                quality: FilterQuality::Bicubic,
+                ..PixmapPaint::default()
+            };
+            canvas.draw_pixmap(
+                0,
+                0,
+                src.as_ref(),
+                &paint,
+                fit(src.width() as f32, src.height() as f32),
+                None,
+            );
+        }
+        canvas.encode_png().ok()
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn write(path: &Path, text: &str) {
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, text).unwrap();
+        }
+
+        #[test]
+        fn resolves_icons_through_desktop_entries_and_theme_sizes() {
+            let root = std::env::temp_dir().join(format!("cz-icons-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&root);
+            let share = root.join("share");
+            write(
+                &share.join("applications/org.example.Editor.desktop"),
+                "[Desktop Entry]\nName=Editor\nExec=env GDK_BACKEND=x11 /opt/editor/bin/editor %F\nIcon=example-editor\nStartupWMClass=ExampleEditor\n",
+            );
+            write(
+                &share.join("icons/hicolor/index.theme"),
+                "[Icon Theme]\nName=Hicolor\nDirectories=16x16/apps,48x48/apps,scalable/apps\n\n[16x16/apps]\nSize=16\nType=Fixed\n\n[48x48/apps]\nSize=48\nType=Threshold\n\n[scalable/apps]\nSize=128\nMinSize=32\nMaxSize=512\nType=Scalable\n",
+            );
+            write(&share.join("icons/hicolor/16x16/apps/example-editor.png"), "");
+            write(&share.join("icons/hicolor/48x48/apps/example-editor.png"), "");
+            write(&share.join("icons/hicolor/scalable/apps/example-editor.svg"), "");
+            write(&share.join("pixmaps/legacy.png"), "");
+
+            let dirs = vec![share.clone()];
+            let themes = vec!["hicolor".to_string()];
+            let expected = share.join("icons/hicolor/scalable/apps/example-editor.svg");
+            for app_id in ["org.example.Editor", "exampleeditor", "Editor", "/opt/editor/bin/editor"] {
+                assert_eq!(find_icon_path(app_id, &dirs, &themes), Some(expected.clone()), "{}", app_id);
+            }
+            assert_eq!(
+                find_icon_path("legacy", &dirs, &themes),
+                Some(share.join("pixmaps/legacy.png"))
+            );
+            assert_eq!(find_icon_path("missing", &dirs, &themes), None);
+
+            let _ = std::fs::remove_dir_all(&root);
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux")))]
 mod imp {
     pub(super) fn get_app_icon_png_base64(_app_id: &str) -> Option<String> {
         None
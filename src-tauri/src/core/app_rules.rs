@@ -0,0 +1,143 @@
+//! Per-app counting rules stored in `Settings::app_rules`.
+
+use crate::core::active_app::AppContext;
+use crate::models::{AppRule, AppRuleMode};
+use rand::Rng;
+use std::collections::HashSet;
+
+pub const MAX_APP_RULES: usize = 200;
+const MAX_MATCH_CHARS: usize = 512;
+const MAX_ID_CHARS: usize = 64;
+
+/// How input attributed to an app should be recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppCounting {
+    Count,
+    CountWithoutDetails,
+    Ignore,
+}
+
+fn matches(rule: &AppRule, app: &AppContext) -> bool {
+    let eq = |pattern: Option<&str>, value: Option<&str>| {
+        pattern
+            .zip(value)
+            .is_some_and(|(p, v)| p.eq_ignore_ascii_case(v))
+    };
+    eq(rule.app_id.as_deref(), Some(app.id.as_ref())) || eq(rule.app_name.as_deref(), app.name.as_deref())
+}
+
+/// The most restrictive matching rule wins. With any `count_only` rule present, apps that don't
+/// match one are ignored, including input that couldn't be attributed to an app.
+pub fn resolve(rules: &[AppRule], app: &AppContext) -> AppCounting {
+    let mut allowlisted = false;
+    let mut without_details = false;
+    for rule in rules.iter().filter(|r| matches(r, app)) {
+        match rule.mode {
+            AppRuleMode::Ignore => return AppCounting::Ignore,
+            AppRuleMode::CountWithoutDetails => without_details = true,
+            AppRuleMode::CountOnly => allowlisted = true,
+        }
+    }
+
+    if !allowlisted && rules.iter().any(|r| r.mode == AppRuleMode::CountOnly) {
+        return AppCounting::Ignore;
+    }
+    if without_details {
+        AppCounting::CountWithoutDetails
+    } else {
+        AppCounting::Count
+    }
+}
+
+pub fn generate_id() -> String {
+    let now = chrono::Utc::now().timestamp_millis();
+    let rand_part: u32 = rand::thread_rng().gen();
+    format!("ar_{}_{:08x}", now, rand_part)
+}
+
+pub fn is_valid_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_ID_CHARS
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+pub fn normalize_match(value: Option<String>) -> Option<String> {
+    let value = value?;
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    Some(trimmed.chars().take(MAX_MATCH_CHARS).collect())
+}
+
+/// Trims matchers, drops rules that match nothing and gives every rule a unique id.
+pub fn normalize(rules: Vec<AppRule>) -> Vec<AppRule> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::with_capacity(rules.len().min(MAX_APP_RULES));
+    for mut rule in rules {
+        if out.len() >= MAX_APP_RULES {
+            break;
+        }
+        rule.app_id = normalize_match(rule.app_id);
+        rule.app_name = normalize_match(rule.app_name);
+        if rule.app_id.is_none() && rule.app_name.is_none() {
+            continue;
+        }
+
+        rule.id = rule.id.trim().to_string();
+        while !is_valid_id(&rule.id) || seen.contains(&rule.id) {
+            rule.id = generate_id();
+        }
+        seen.insert(rule.id.clone());
+        out.push(rule);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    fn app(id: &str, name: Option<&str>) -> AppContext {
+        AppContext {
+            id: Arc::from(id),
+            name: name.map(Arc::from),
+        }
+    }
+
+    fn rule(app_id: Option<&str>, app_name: Option<&str>, mode: AppRuleMode) -> AppRule {
+        AppRule {
+            id: String::new(),
+            app_id: app_id.map(str::to_string),
+            app_name: app_name.map(str::to_string),
+            mode,
+        }
+    }
+
+    #[test]
+    fn resolves_modes_and_allowlist() {
+        let editor = app("com.example.editor", Some("Editor"));
+        let vault = app("com.example.vault", Some("Vault"));
+        let game = app("game.exe", None);
+
+        let rules = normalize(vec![
+            rule(Some("COM.EXAMPLE.VAULT"), None, AppRuleMode::Ignore),
+            rule(None, Some("editor"), AppRuleMode::CountWithoutDetails),
+            rule(Some("  "), None, AppRuleMode::Ignore),
+        ]);
+        assert_eq!(rules.len(), 2);
+        assert_ne!(rules[0].id, rules[1].id);
+        assert_eq!(resolve(&rules, &vault), AppCounting::Ignore);
+        assert_eq!(resolve(&rules, &editor), AppCounting::CountWithoutDetails);
+        assert_eq!(resolve(&rules, &game), AppCounting::Count);
+
+        let mut allowlist = rules;
+        allowlist.push(rule(None, Some("Editor"), AppRuleMode::CountOnly));
+        assert_eq!(resolve(&allowlist, &editor), AppCounting::CountWithoutDetails);
+        assert_eq!(resolve(&allowlist, &game), AppCounting::Ignore);
+        assert_eq!(resolve(&allowlist, &AppContext::unknown()), AppCounting::Ignore);
+    }
+}
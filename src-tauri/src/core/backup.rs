@@ -0,0 +1,414 @@
+use crate::core::{app_log, history_db, persistence, MeritStorage};
+use crate::models::KeyPrivacyLevel;
+use chrono::{Datelike, Local, TimeZone};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+const BACKUP_DIR_NAME: &str = "backups";
+const META_FILE_NAME: &str = "meta.json";
+const STATE_FILE_NAME: &str = "state.json";
+const HISTORY_FILE_NAME: &str = "history.sqlite3";
+
+const KEEP_DAILY: usize = 7;
+const KEEP_WEEKLY: usize = 4;
+const KEEP_AUTOMATIC: usize = 10;
+
+const STARTUP_GRACE: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupKind {
+    Daily,
+    Weekly,
+    /// Taken right before a destructive action (clear/reset/restore).
+    Automatic,
+    Manual,
+}
+
+impl BackupKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            BackupKind::Daily => "daily",
+            BackupKind::Weekly => "weekly",
+            BackupKind::Automatic => "auto",
+            BackupKind::Manual => "manual",
+        }
+    }
+
+    /// `None` means snapshots of this kind are only removed by the user.
+    fn keep(self) -> Option<usize> {
+        match self {
+            BackupKind::Daily => Some(KEEP_DAILY),
+            BackupKind::Weekly => Some(KEEP_WEEKLY),
+            BackupKind::Automatic => Some(KEEP_AUTOMATIC),
+            BackupKind::Manual => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupInfo {
+    pub id: String,
+    pub kind: BackupKind,
+    /// What triggered an automatic snapshot, e.g. `clear_history`.
+    #[serde(default)]
+    pub reason: Option<String>,
+    pub created_at_ms: i64,
+    pub app_version: String,
+    #[serde(default)]
+    pub state_bytes: u64,
+    #[serde(default)]
+    pub history_bytes: u64,
+}
+
+fn backup_root(app: &AppHandle) -> Result<PathBuf, String> {
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to resolve app data dir: {}", e))?
+        .join(BACKUP_DIR_NAME);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create backup dir: {}", e))?;
+    Ok(dir)
+}
+
+/// Backup ids are directory names; anything that could escape the backup root is rejected.
+fn backup_dir(app: &AppHandle, id: &str) -> Result<PathBuf, String> {
+    let valid = !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(format!("Invalid backup id: {}", id));
+    }
+    let dir = backup_root(app)?.join(id);
+    if !dir.join(META_FILE_NAME).is_file() {
+        return Err(format!("Backup not found: {}", id));
+    }
+    Ok(dir)
+}
+
+fn file_len(path: &Path) -> u64 {
+    fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+fn read_meta(dir: &Path) -> Option<BackupInfo> {
+    let bytes = fs::read(dir.join(META_FILE_NAME)).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn write_meta(dir: &Path, info: &BackupInfo) -> Result<(), String> {
+    let meta = serde_json::to_vec_pretty(info)
+        .map_err(|e| format!("Failed to serialize backup meta: {}", e))?;
+    fs::write(dir.join(META_FILE_NAME), meta)
+        .map_err(|e| format!("Failed to write backup meta: {}", e))
+}
+
+fn list_in(root: &Path) -> Vec<BackupInfo> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut out: Vec<BackupInfo> = entries
+        .flatten()
+        .filter(|e| e.path().is_dir())
+        .filter_map(|e| read_meta(&e.path()))
+        .collect();
+    out.sort_by_key(|b| std::cmp::Reverse(b.created_at_ms));
+    out
+}
+
+pub fn list(app: &AppHandle) -> Result<Vec<BackupInfo>, String> {
+    Ok(list_in(&backup_root(app)?))
+}
+
+/// Drops the oldest snapshots of each kind beyond its retention count.
+fn prune(root: &Path) {
+    let backups = list_in(root);
+    for kind in [BackupKind::Daily, BackupKind::Weekly, BackupKind::Automatic] {
+        let Some(keep) = kind.keep() else {
+            continue;
+        };
+        for stale in backups.iter().filter(|b| b.kind == kind).skip(keep) {
+            let _ = fs::remove_dir_all(root.join(&stale.id));
+        }
+    }
+}
+
+fn unique_id(root: &Path, kind: BackupKind) -> String {
+    let base = format!("{}-{}", kind.as_str(), Local::now().format("%Y%m%d-%H%M%S"));
+    let mut id = base.clone();
+    let mut n = 1;
+    while root.join(&id).exists() {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+    id
+}
+
+/// Snapshots `state.json` (from memory) and the history DB into a new backup directory.
+pub fn create(app: &AppHandle, kind: BackupKind, reason: Option<&str>) -> Result<BackupInfo, String> {
+    let root = backup_root(app)?;
+    let id = unique_id(&root, kind);
+
+    // Assemble in a temp dir so a half-written snapshot is never listed.
+    let tmp = root.join(format!("{}.tmp", id));
+    let _ = fs::remove_dir_all(&tmp);
+    fs::create_dir_all(&tmp).map_err(|e| format!("Failed to create backup dir: {}", e))?;
+
+    let res = (|| -> Result<BackupInfo, String> {
+        let state_path = tmp.join(STATE_FILE_NAME);
+        persistence::write_snapshot_to(&state_path)
+            .map_err(|e| format!("Failed to back up state: {}", e))?;
+        let history_path = tmp.join(HISTORY_FILE_NAME);
+        history_db::backup_to(history_path.clone())?;
+
+        let info = BackupInfo {
+            id: id.clone(),
+            kind,
+            reason: reason.map(|r| r.to_string()),
+            created_at_ms: chrono::Utc::now().timestamp_millis(),
+            app_version: app.package_info().version.to_string(),
+            state_bytes: file_len(&state_path),
+            history_bytes: file_len(&history_path),
+        };
+        write_meta(&tmp, &info)?;
+        fs::rename(&tmp, root.join(&id))
+            .map_err(|e| format!("Failed to finalize backup: {}", e))?;
+        Ok(info)
+    })();
+
+    if res.is_err() {
+        let _ = fs::remove_dir_all(&tmp);
+    }
+    prune(&root);
+    res
+}
+
+/// Called by destructive commands before they run; callers abort if this fails.
+pub fn snapshot_before(app: &AppHandle, action: &str) -> Result<(), String> {
+    let res = create(app, BackupKind::Automatic, Some(action));
+    let _ = app_log::append(
+        app,
+        app_log::AppLogRecord {
+            ts_ms: chrono::Utc::now().timestamp_millis(),
+            level: if res.is_ok() { "info" } else { "error" }.to_string(),
+            scope: "backup".to_string(),
+            message: "snapshot_before".to_string(),
+            data: Some(match &res {
+                Ok(info) => serde_json::json!({ "action": action, "id": info.id }),
+                Err(e) => serde_json::json!({ "action": action, "error": e }),
+            }),
+        },
+    );
+    res.map(|_| ())
+}
+
+pub fn delete(app: &AppHandle, id: &str) -> Result<(), String> {
+    let dir = backup_dir(app, id)?;
+    fs::remove_dir_all(&dir).map_err(|e| format!("Failed to delete backup {}: {}", id, e))
+}
+
+/// Restores both files from a snapshot. The current data is snapshotted first so a restore can
+/// itself be undone.
+pub fn restore(app: &AppHandle, id: &str) -> Result<(), String> {
+    let dir = backup_dir(app, id)?;
+    snapshot_before(app, "restore_backup")?;
+
+    let history_path = dir.join(HISTORY_FILE_NAME);
+    if history_path.is_file() {
+        history_db::restore_from(history_path)?;
+    }
+    let state_path = dir.join(STATE_FILE_NAME);
+    if state_path.is_file() {
+        persistence::restore_state_file(&state_path)
+            .map_err(|e| format!("Failed to restore state: {}", e))?;
+    }
+
+    let storage = MeritStorage::instance();
+    let storage = storage.read();
+    let _ = app.emit("merit-updated", storage.get_stats().lite());
+    let _ = app.emit("achievements-updated", storage.get_achievements());
+    let _ = app.emit("settings-updated", storage.get_settings());
+    Ok(())
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackupScrubReport {
+    pub scrubbed: usize,
+    /// Ids of backups that could not be rewritten and still hold the old key detail.
+    pub failed: Vec<String>,
+}
+
+fn scrub_dir(dir: &Path, level: KeyPrivacyLevel) -> Result<(), String> {
+    let state_path = dir.join(STATE_FILE_NAME);
+    if state_path.is_file() {
+        persistence::scrub_state_file(&state_path, level)
+            .map_err(|e| format!("Failed to scrub backed-up state: {}", e))?;
+    }
+    let history_path = dir.join(HISTORY_FILE_NAME);
+    if history_path.is_file() {
+        history_db::scrub_key_details_in(&history_path, level)?;
+    }
+    Ok(())
+}
+
+/// Brings every backup down to `level` as well; otherwise restoring one would bring back the
+/// detail that was just scrubbed.
+pub fn scrub_key_details(
+    app: &AppHandle,
+    level: KeyPrivacyLevel,
+) -> Result<BackupScrubReport, String> {
+    let mut report = BackupScrubReport::default();
+    if level == KeyPrivacyLevel::Full {
+        return Ok(report);
+    }
+    let root = backup_root(app)?;
+    for mut info in list_in(&root) {
+        let dir = root.join(&info.id);
+        match scrub_dir(&dir, level) {
+            Ok(()) => {
+                info.state_bytes = file_len(&dir.join(STATE_FILE_NAME));
+                info.history_bytes = file_len(&dir.join(HISTORY_FILE_NAME));
+                let _ = write_meta(&dir, &info);
+                report.scrubbed += 1;
+            }
+            Err(e) => {
+                eprintln!("Failed to scrub backup {}: {}", info.id, e);
+                report.failed.push(info.id);
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn date_of(ms: i64) -> Option<chrono::NaiveDate> {
+    Local.timestamp_millis_opt(ms).single().map(|t| t.date_naive())
+}
+
+/// Kinds whose most recent snapshot is older than the current day/ISO week.
+fn due_kinds(backups: &[BackupInfo], today: chrono::NaiveDate) -> Vec<BackupKind> {
+    let latest = |kind: BackupKind| {
+        backups
+            .iter()
+            .filter(|b| b.kind == kind)
+            .find_map(|b| date_of(b.created_at_ms))
+    };
+    let mut due = Vec::new();
+    if latest(BackupKind::Daily) != Some(today) {
+        due.push(BackupKind::Daily);
+    }
+    if latest(BackupKind::Weekly).map(|d| d.iso_week()) != Some(today.iso_week()) {
+        due.push(BackupKind::Weekly);
+    }
+    due
+}
+
+fn run_scheduled(app: &AppHandle) {
+    let Ok(backups) = list(app) else {
+        return;
+    };
+    for kind in due_kinds(&backups, Local::now().date_naive()) {
+        if let Err(e) = create(app, kind, None) {
+            let _ = app_log::append(
+                app,
+                app_log::AppLogRecord {
+                    ts_ms: chrono::Utc::now().timestamp_millis(),
+                    level: "error".to_string(),
+                    scope: "backup".to_string(),
+                    message: "scheduled_backup_failed".to_string(),
+                    data: Some(serde_json::json!({ "kind": kind, "error": e })),
+                },
+            );
+        }
+    }
+}
+
+pub fn init(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_GRACE).await;
+        loop {
+            let app = app_handle.clone();
+            let _ = tauri::async_runtime::spawn_blocking(move || run_scheduled(&app)).await;
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(kind: BackupKind, date: &str) -> BackupInfo {
+        let day = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        let at = Local
+            .from_local_datetime(&day.and_hms_opt(12, 0, 0).unwrap())
+            .unwrap();
+        BackupInfo {
+            id: format!("{}-{}", kind.as_str(), date),
+            kind,
+            reason: None,
+            created_at_ms: at.timestamp_millis(),
+            app_version: "0.0.0".to_string(),
+            state_bytes: 0,
+            history_bytes: 0,
+        }
+    }
+
+    #[test]
+    fn due_kinds_follow_day_and_iso_week() {
+        let monday = chrono::NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+        assert_eq!(
+            due_kinds(&[], monday),
+            [BackupKind::Daily, BackupKind::Weekly]
+        );
+
+        let backups = [
+            info(BackupKind::Daily, "2024-03-03"),
+            info(BackupKind::Weekly, "2024-03-03"),
+        ];
+        // Sunday belongs to the previous ISO week.
+        assert_eq!(
+            due_kinds(&backups, monday),
+            [BackupKind::Daily, BackupKind::Weekly]
+        );
+
+        let backups = [
+            info(BackupKind::Daily, "2024-03-04"),
+            info(BackupKind::Weekly, "2024-03-04"),
+        ];
+        let friday = chrono::NaiveDate::from_ymd_opt(2024, 3, 8).unwrap();
+        assert_eq!(due_kinds(&backups, friday), [BackupKind::Daily]);
+    }
+
+    #[test]
+    fn prune_keeps_newest_per_kind_and_all_manual() {
+        let root = std::env::temp_dir().join(format!(
+            "cyber-zen-backup-prune-{}",
+            chrono::Utc::now().timestamp_millis()
+        ));
+        let mut backups = Vec::new();
+        for day in 1..=(KEEP_DAILY + 2) {
+            backups.push(info(BackupKind::Daily, &format!("2024-03-{:02}", day)));
+            backups.push(info(BackupKind::Manual, &format!("2024-03-{:02}", day)));
+        }
+        for b in &backups {
+            let dir = root.join(&b.id);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(META_FILE_NAME), serde_json::to_vec(b).unwrap()).unwrap();
+        }
+
+        prune(&root);
+        let left = list_in(&root);
+        let daily: Vec<_> = left.iter().filter(|b| b.kind == BackupKind::Daily).collect();
+        assert_eq!(daily.len(), KEEP_DAILY);
+        assert_eq!(daily.last().unwrap().id, "daily-2024-03-03");
+        assert_eq!(
+            left.iter().filter(|b| b.kind == BackupKind::Manual).count(),
+            KEEP_DAILY + 2
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,4 +1,6 @@
-use crate::core::{history_db, MeritStorage};
+use crate::core::app_rules::AppCounting;
+use crate::core::{active_app, history_db, MeritStorage};
+use crate::models::{HeatmapKind, InputOrigin};
 use crate::models::click_heatmap::{CLICK_HEATMAP_BASE_COLS, CLICK_HEATMAP_BASE_LEN, CLICK_HEATMAP_BASE_ROWS};
 use once_cell::sync::Lazy;
 use parking_lot::RwLock;
@@ -225,20 +227,107 @@ fn opposite_space(space: CoordinateSpace) -> CoordinateSpace {
     }
 }
 
-pub fn record_global_click(app_handle: &AppHandle, preferred_space: CoordinateSpace, x: f64, y: f64) {
-    crate::core::perf::time(crate::core::perf::TimerKind::ClickHeatmap, || {
-    let enabled = {
-        let storage = MeritStorage::instance();
-        let storage = storage.read();
-        storage.get_settings().enable_mouse_single && storage.click_heatmap_recording_enabled()
-    };
+/// Size in physical pixels of a monitor seen by the last refresh.
+pub fn known_monitor_size(display_id: &str) -> Option<(f64, f64)> {
+    MONITORS
+        .read()
+        .iter()
+        .find(|m| m.id.as_ref() == display_id)
+        .map(|m| (m.width, m.height))
+}
 
-    if !enabled {
-        return;
+/// Non-zero base grid cells and the total count for a display, either all-time or for one day.
+///
+/// Click maps fall back to the legacy in-memory state while the history DB has nothing yet.
+pub fn load_base_cells(
+    kind: HeatmapKind,
+    display_id: &str,
+    date_key: Option<&str>,
+) -> Result<(Vec<(u32, u32)>, u64), String> {
+    let base = history_db::load_heatmap_base(kind, display_id, date_key);
+    match base {
+        Ok((cells, total)) if total > 0 || !cells.is_empty() => Ok((cells, total)),
+        Ok(_) if kind == HeatmapKind::Move => Ok((Vec::new(), 0)),
+        Err(e) if kind == HeatmapKind::Move => Err(e),
+        Ok(_) | Err(_) => {
+            // Best-effort fallback to legacy in-memory state (e.g. before DB init/migration).
+            let storage = MeritStorage::instance();
+            let storage = storage.read();
+            let display = match date_key {
+                Some(key) => storage.click_heatmap_display_for_date(display_id, key),
+                None => storage.click_heatmap_display(display_id),
+            };
+            let Some(display) = display else {
+                return Ok((Vec::new(), 0));
+            };
+            let cells = display
+                .grid
+                .iter()
+                .take(CLICK_HEATMAP_BASE_LEN)
+                .enumerate()
+                .filter(|(_, count)| **count > 0)
+                .map(|(idx, count)| (idx as u32, *count))
+                .collect();
+            Ok((cells, display.total_clicks))
+        }
+    }
+}
+
+/// Base grid cell for a point relative to a monitor's top-left corner, in physical pixels.
+fn cell_index(rel_x: f64, rel_y: f64, width: f64, height: f64) -> Option<usize> {
+    if !(rel_x.is_finite() && rel_y.is_finite()) {
+        return None;
+    }
+    if rel_x < 0.0 || rel_y < 0.0 || width <= 0.0 || height <= 0.0 {
+        return None;
     }
 
     let cols = CLICK_HEATMAP_BASE_COLS;
     let rows = CLICK_HEATMAP_BASE_ROWS;
+    let rel_x = rel_x.floor() as u64;
+    let rel_y = rel_y.floor() as u64;
+    let w = width as u64;
+    let h = height as u64;
+    if w == 0 || h == 0 {
+        return None;
+    }
+
+    let cell_x = ((rel_x.saturating_mul(cols as u64)) / w) as usize;
+    let cell_y = ((rel_y.saturating_mul(rows as u64)) / h) as usize;
+
+    let cx = cell_x.min(cols - 1);
+    let cy = cell_y.min(rows - 1);
+    let idx = cy.saturating_mul(cols) + cx;
+    (idx < CLICK_HEATMAP_BASE_LEN).then_some(idx)
+}
+
+fn recording_enabled() -> bool {
+    let storage = MeritStorage::instance();
+    let storage = storage.read();
+    storage.get_settings().enable_mouse_single
+        && storage.click_heatmap_recording_enabled()
+        && storage.app_counting(InputOrigin::Global, Some(&active_app::current_or_unknown()))
+            != AppCounting::Ignore
+}
+
+/// A cursor position sample for the movement heatmap, rate-limited by `mouse_distance`.
+///
+/// Coordinates are physical pixels relative to the monitor's top-left corner.
+pub fn record_move_sample(display_id: &Arc<str>, rel_x: f64, rel_y: f64, width: f64, height: f64) {
+    let Some(idx) = cell_index(rel_x, rel_y, width, height) else {
+        return;
+    };
+    if !recording_enabled() {
+        return;
+    }
+    history_db::record_heatmap_cell(HeatmapKind::Move, Arc::clone(display_id), idx);
+}
+
+pub fn record_global_click(app_handle: &AppHandle, preferred_space: CoordinateSpace, x: f64, y: f64) {
+    crate::core::perf::time(crate::core::perf::TimerKind::ClickHeatmap, || {
+    if !recording_enabled() {
+        return;
+    }
 
     refresh_monitors_if_stale(app_handle, Duration::from_secs(2));
     let monitors_version = MONITORS_VERSION.load(Ordering::Relaxed);
@@ -258,32 +347,12 @@ pub fn record_global_click(app_handle: &AppHandle, preferred_space: CoordinateSp
     let Some((rel_x, rel_y)) = rel else {
         return;
     };
-
-    if !(rel_x.is_finite() && rel_y.is_finite()) {
+    let Some(idx) = cell_index(rel_x, rel_y, monitor.width, monitor.height) else {
         return;
-    }
-
-    if rel_x < 0.0 || rel_y < 0.0 {
-        return;
-    }
-
-    let rel_x = rel_x.floor() as u64;
-    let rel_y = rel_y.floor() as u64;
-    let w = monitor.width as u64;
-    let h = monitor.height as u64;
-
-    let cell_x = ((rel_x.saturating_mul(cols as u64)) / w) as usize;
-    let cell_y = ((rel_y.saturating_mul(rows as u64)) / h) as usize;
-
-    let cx = cell_x.min(cols - 1);
-    let cy = cell_y.min(rows - 1);
-    let idx = cy.saturating_mul(cols) + cx;
-    if idx >= CLICK_HEATMAP_BASE_LEN {
-        return;
-    }
+    };
 
     let display_id = Arc::clone(&monitor.id);
-    let queued = history_db::record_click_heatmap_cell(Arc::clone(&display_id), idx);
+    let queued = history_db::record_heatmap_cell(HeatmapKind::Click, Arc::clone(&display_id), idx);
     if !queued {
         let storage = MeritStorage::instance();
         let mut storage = storage.write();
@@ -0,0 +1,282 @@
+use crate::core::{history_db, MeritStorage};
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Seek, Write};
+use tauri::AppHandle;
+use zip::write::{SimpleFileOptions, ZipWriter};
+
+pub const EXPORT_FORMAT: &str = "cyber-zen-data-export";
+pub const EXPORT_FORMAT_VERSION: u32 = 1;
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.json";
+pub const SETTINGS_FILE_NAME: &str = "state/settings.json";
+pub const ACHIEVEMENTS_FILE_NAME: &str = "state/achievements.json";
+pub const TEMPLATES_FILE_NAME: &str = "state/custom_statistics_templates.json";
+pub const TODAY_FILE_NAME: &str = "state/today.json";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportTableEntry {
+    pub table: String,
+    pub file: String,
+    pub columns: Vec<String>,
+    pub rows: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExportManifest {
+    pub format: String,
+    pub format_version: u32,
+    pub app_version: String,
+    pub exported_at_ms: i64,
+    pub schema_version: i64,
+    pub tables: Vec<ExportTableEntry>,
+    /// JSON documents taken from the in-memory state (what `state.json` would contain).
+    pub state_files: Vec<String>,
+}
+
+pub fn table_file_name(table: &str) -> String {
+    format!("tables/{}.csv", table)
+}
+
+fn default_file_name() -> String {
+    format!(
+        "cyber-zen-data-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    )
+}
+
+/// `None` (NULL) is written as an empty field and an empty string as `""`, so imports can tell
+/// them apart.
+fn push_csv_field(out: &mut String, field: Option<&str>) {
+    let Some(field) = field else {
+        return;
+    };
+    if field.is_empty() || field.contains([',', '"', '\n', '\r']) {
+        out.push('"');
+        out.push_str(&field.replace('"', "\"\""));
+        out.push('"');
+    } else {
+        out.push_str(field);
+    }
+}
+
+fn push_csv_row<'a>(out: &mut String, fields: impl IntoIterator<Item = Option<&'a str>>) {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        push_csv_field(out, field);
+    }
+    out.push('\n');
+}
+
+fn value_to_csv(value: ValueRef<'_>) -> Option<String> {
+    Some(match value {
+        ValueRef::Null => return None,
+        ValueRef::Integer(v) => v.to_string(),
+        ValueRef::Real(v) => v.to_string(),
+        ValueRef::Text(v) => String::from_utf8_lossy(v).into_owned(),
+        ValueRef::Blob(v) => {
+            use base64::Engine;
+            base64::engine::general_purpose::STANDARD.encode(v)
+        }
+    })
+}
+
+/// Serializes a whole table as CSV with a header row. Rows are sorted by every column in order
+/// (key columns come first in each table) so exports of the same data are byte-identical.
+pub(crate) fn table_to_csv(conn: &Connection, table: &str) -> Result<(Vec<String>, String, u64), String> {
+    let stmt = conn
+        .prepare(&format!("SELECT * FROM {table}"))
+        .map_err(|e| format!("Failed to prepare export query for {}: {}", table, e))?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    drop(stmt);
+
+    let order_by = (1..=columns.len())
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut stmt = conn
+        .prepare(&format!("SELECT * FROM {table} ORDER BY {order_by}"))
+        .map_err(|e| format!("Failed to prepare export query for {}: {}", table, e))?;
+
+    let mut out = String::new();
+    push_csv_row(&mut out, columns.iter().map(|c| Some(c.as_str())));
+
+    let mut rows = stmt
+        .query([])
+        .map_err(|e| format!("Failed to query {} for export: {}", table, e))?;
+    let mut count = 0u64;
+    let mut fields: Vec<Option<String>> = Vec::with_capacity(columns.len());
+    while let Some(row) = rows
+        .next()
+        .map_err(|e| format!("Failed to read {} row for export: {}", table, e))?
+    {
+        fields.clear();
+        for idx in 0..columns.len() {
+            let value = row
+                .get_ref(idx)
+                .map_err(|e| format!("Failed to read {} column for export: {}", table, e))?;
+            fields.push(value_to_csv(value));
+        }
+        push_csv_row(&mut out, fields.iter().map(|f| f.as_deref()));
+        count += 1;
+    }
+
+    Ok((columns, out, count))
+}
+
+fn write_entry<W: Write + Seek>(
+    writer: &mut ZipWriter<W>,
+    name: &str,
+    bytes: &[u8],
+) -> Result<(), String> {
+    let options = SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(bytes.len() as u64 >= u32::MAX as u64);
+    writer
+        .start_file(name, options)
+        .map_err(|e| format!("Failed to create zip entry {}: {}", name, e))?;
+    writer
+        .write_all(bytes)
+        .map_err(|e| format!("Failed to write zip entry {}: {}", name, e))
+}
+
+fn write_json_entry<W: Write + Seek, T: Serialize>(
+    writer: &mut ZipWriter<W>,
+    name: &str,
+    value: &T,
+) -> Result<(), String> {
+    let bytes = serde_json::to_vec_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", name, e))?;
+    write_entry(writer, name, &bytes)
+}
+
+/// Writes every history table plus the persisted settings/achievements/templates into `writer`.
+pub fn write_archive<W: Write + Seek>(writer: W, app_version: &str) -> Result<ExportManifest, String> {
+    let mut zip = ZipWriter::new(writer);
+
+    let (schema_version, tables) = history_db::with_read_snapshot(|conn, schema_version| {
+        let mut tables = Vec::with_capacity(history_db::EXPORT_TABLES.len());
+        for &table in history_db::EXPORT_TABLES {
+            let (columns, csv, rows) = table_to_csv(conn, table)?;
+            let file = table_file_name(table);
+            write_entry(&mut zip, &file, csv.as_bytes())?;
+            tables.push(ExportTableEntry {
+                table: table.to_string(),
+                file,
+                columns,
+                rows,
+            });
+        }
+        Ok((schema_version, tables))
+    })?;
+
+    let (settings, achievements, templates, today) = {
+        let storage = MeritStorage::instance();
+        let storage = storage.read();
+        (
+            storage.get_settings(),
+            storage.get_achievements(),
+            storage.get_custom_statistics_templates(),
+            storage.get_stats().today,
+        )
+    };
+    write_json_entry(&mut zip, SETTINGS_FILE_NAME, &settings)?;
+    write_json_entry(&mut zip, ACHIEVEMENTS_FILE_NAME, &achievements)?;
+    write_json_entry(&mut zip, TEMPLATES_FILE_NAME, &templates)?;
+    // Today's counters only reach SQLite once the day is archived.
+    write_json_entry(&mut zip, TODAY_FILE_NAME, &today)?;
+
+    let manifest = ExportManifest {
+        format: EXPORT_FORMAT.to_string(),
+        format_version: EXPORT_FORMAT_VERSION,
+        app_version: app_version.to_string(),
+        exported_at_ms: chrono::Utc::now().timestamp_millis(),
+        schema_version,
+        tables,
+        state_files: vec![
+            SETTINGS_FILE_NAME.to_string(),
+            ACHIEVEMENTS_FILE_NAME.to_string(),
+            TEMPLATES_FILE_NAME.to_string(),
+            TODAY_FILE_NAME.to_string(),
+        ],
+    };
+    write_json_entry(&mut zip, MANIFEST_FILE_NAME, &manifest)?;
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finish zip archive: {}", e))?;
+    Ok(manifest)
+}
+
+pub fn export_to_file(
+    app: &AppHandle,
+    file_name: Option<&str>,
+    export_dir: Option<&str>,
+    export_path: Option<&str>,
+) -> Result<String, String> {
+    let file_name = file_name
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+        .unwrap_or_else(default_file_name);
+    let path = crate::core::wooden_fish_skins::resolve_export_zip_path(
+        app,
+        export_path,
+        export_dir,
+        &file_name,
+    )
+    .map_err(|e| format!("{e:#}"))?;
+
+    // Write next to the destination first so a failed export never leaves a truncated archive.
+    let tmp = path.with_extension("zip.tmp");
+    let file = fs::File::create(&tmp)
+        .map_err(|e| format!("Failed to create export file {}: {}", tmp.display(), e))?;
+    let res = write_archive(file, &app.package_info().version.to_string());
+    if let Err(e) = res {
+        let _ = fs::remove_file(&tmp);
+        return Err(e);
+    }
+    let _ = fs::remove_file(&path);
+    fs::rename(&tmp, &path)
+        .map_err(|e| format!("Failed to finalize export file {}: {}", path.display(), e))?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_fields_are_quoted_only_when_needed() {
+        let mut out = String::new();
+        push_csv_row(
+            &mut out,
+            [Some("plain"), Some("a,b"), Some("say \"hi\""), Some("line\nbreak"), Some(""), None],
+        );
+        assert_eq!(out, "plain,\"a,b\",\"say \"\"hi\"\"\",\"line\nbreak\",\"\",\n");
+    }
+
+    #[test]
+    fn table_to_csv_includes_header_and_sorted_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"
+CREATE TABLE daily_app_input (date_key TEXT, app_id TEXT, name TEXT, keyboard INTEGER, mouse_single INTEGER);
+INSERT INTO daily_app_input VALUES ('2024-03-02', 'b', NULL, 1, 2);
+INSERT INTO daily_app_input VALUES ('2024-03-01', 'a', 'Editor, Pro', 3, 4);
+"#,
+        )
+        .unwrap();
+
+        let (columns, csv, rows) = table_to_csv(&conn, "daily_app_input").unwrap();
+        assert_eq!(columns, ["date_key", "app_id", "name", "keyboard", "mouse_single"]);
+        assert_eq!(rows, 2);
+        assert_eq!(
+            csv,
+            "date_key,app_id,name,keyboard,mouse_single\n2024-03-01,a,\"Editor, Pro\",3,4\n2024-03-02,b,,1,2\n"
+        );
+    }
+}
@@ -0,0 +1,462 @@
+use crate::core::data_export::{
+    ExportManifest, ACHIEVEMENTS_FILE_NAME, EXPORT_FORMAT, MANIFEST_FILE_NAME, TODAY_FILE_NAME,
+};
+use crate::core::history_db::{self, ImportMergeReport, ImportSource};
+use crate::core::MeritStorage;
+use crate::models::{AchievementState, DailyStats, MeritStats};
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Seek};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+use zip::ZipArchive;
+
+const SQLITE_MAGIC: &[u8] = b"SQLite format 3\0";
+const ZIP_MAGIC: &[u8] = b"PK\x03\x04";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportReport {
+    pub dry_run: bool,
+    #[serde(flatten)]
+    pub merge: ImportMergeReport,
+    /// Whether the imported data contains the current day, which is merged into the live counters.
+    pub live_day_merged: bool,
+    pub new_achievement_unlocks: usize,
+}
+
+/// Only the parts of another machine's `state.json` that can be merged.
+#[derive(Debug, Default, Deserialize)]
+struct ImportedState {
+    #[serde(default)]
+    stats: Option<MeritStats>,
+    #[serde(default)]
+    achievements: Option<AchievementState>,
+}
+
+/// What staging found besides the rows themselves.
+#[derive(Debug, Default)]
+struct Staged {
+    achievements: Option<AchievementState>,
+    exported_at_ms: Option<i64>,
+}
+
+/// FNV-1a over the source files, so the same file imported again is recognized.
+fn fingerprint(paths: &[&Path]) -> Result<String, String> {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut buf = vec![0u8; 64 * 1024];
+    for path in paths {
+        let mut file = fs::File::open(path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        loop {
+            let n = file
+                .read(&mut buf)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            if n == 0 {
+                break;
+            }
+            for &b in &buf[..n] {
+                hash ^= b as u64;
+                hash = hash.wrapping_mul(0x0100_0000_01b3);
+            }
+        }
+    }
+    Ok(format!("fnv1a64:{:016x}", hash))
+}
+
+/// Parses CSV as written by `data_export`. Empty fields become `None` (exported NULLs).
+fn parse_csv(text: &str) -> Result<Vec<Vec<Option<String>>>, String> {
+    let mut rows = Vec::new();
+    let mut row: Vec<Option<String>> = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+
+    let finish_field = |row: &mut Vec<Option<String>>, field: &mut String, quoted: &mut bool| {
+        let value = std::mem::take(field);
+        row.push(if value.is_empty() && !*quoted {
+            None
+        } else {
+            Some(value)
+        });
+        *quoted = false;
+    };
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                _ => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' if field.is_empty() => {
+                in_quotes = true;
+                quoted = true;
+            }
+            ',' => finish_field(&mut row, &mut field, &mut quoted),
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                finish_field(&mut row, &mut field, &mut quoted);
+                rows.push(std::mem::take(&mut row));
+            }
+            _ => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("Unterminated quoted CSV field".to_string());
+    }
+    if !field.is_empty() || quoted || !row.is_empty() {
+        finish_field(&mut row, &mut field, &mut quoted);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let stmt = conn
+        .prepare(&format!("SELECT * FROM {table} LIMIT 0"))
+        .map_err(|e| format!("Failed to inspect {}: {}", table, e))?;
+    Ok(stmt.column_names().iter().map(|c| c.to_string()).collect())
+}
+
+/// Loads one exported table into the staging DB. Columns unknown to the current schema are
+/// dropped; columns missing from the CSV fall back to their defaults.
+fn stage_csv_table(conn: &Connection, table: &str, csv: &str) -> Result<u64, String> {
+    let mut rows = parse_csv(csv)?.into_iter();
+    let Some(header) = rows.next() else {
+        return Ok(0);
+    };
+    let known = table_columns(conn, table)?;
+    let picked: Vec<(usize, String)> = header
+        .into_iter()
+        .enumerate()
+        .filter_map(|(idx, name)| name.filter(|n| known.contains(n)).map(|n| (idx, n)))
+        .collect();
+    if picked.is_empty() {
+        return Ok(0);
+    }
+
+    let columns = picked
+        .iter()
+        .map(|(_, name)| name.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    let placeholders = (1..=picked.len())
+        .map(|i| format!("?{i}"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut stmt = conn
+        .prepare(&format!(
+            "INSERT OR REPLACE INTO {table}({columns}) VALUES ({placeholders})"
+        ))
+        .map_err(|e| format!("Failed to prepare staging insert for {}: {}", table, e))?;
+
+    let mut count = 0u64;
+    for row in rows {
+        let values = picked
+            .iter()
+            .map(|(idx, _)| row.get(*idx).cloned().flatten());
+        stmt.execute(rusqlite::params_from_iter(values))
+            .map_err(|e| format!("Failed to stage {} row: {}", table, e))?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// Adds a day that only lives in JSON state (e.g. the exporting machine's live day). Days the
+/// staged history already has are skipped: they were drained from the same state earlier.
+fn stage_day_if_missing(conn: &Connection, day: &DailyStats) -> Result<(), String> {
+    if day.total == 0 {
+        return Ok(());
+    }
+    if history_db::load_day_from(conn, &day.date.to_string())?.is_some() {
+        return Ok(());
+    }
+    let mut day = day.clone();
+    day.recompute_counters();
+    history_db::upsert_daily(conn, &day)
+}
+
+fn read_zip_entry<R: Read + Seek>(
+    archive: &mut ZipArchive<R>,
+    name: &str,
+) -> Result<Option<String>, String> {
+    let mut entry = match archive.by_name(name) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("Failed to open {} in archive: {}", name, e)),
+    };
+    let mut text = String::new();
+    entry
+        .read_to_string(&mut text)
+        .map_err(|e| format!("Failed to read {} from archive: {}", name, e))?;
+    Ok(Some(text))
+}
+
+fn stage_archive<R: Read + Seek>(conn: &Connection, reader: R) -> Result<Staged, String> {
+    let mut archive =
+        ZipArchive::new(reader).map_err(|e| format!("Failed to open archive: {}", e))?;
+    let manifest = read_zip_entry(&mut archive, MANIFEST_FILE_NAME)?
+        .ok_or_else(|| "Archive has no manifest.json".to_string())?;
+    let manifest: ExportManifest = serde_json::from_str(&manifest)
+        .map_err(|e| format!("Failed to parse manifest.json: {}", e))?;
+    if manifest.format != EXPORT_FORMAT {
+        return Err(format!("Unsupported archive format: {}", manifest.format));
+    }
+    if manifest.schema_version > history_db::SCHEMA_VERSION {
+        return Err(format!(
+            "Archive schema version {} is newer than supported version {}",
+            manifest.schema_version,
+            history_db::SCHEMA_VERSION
+        ));
+    }
+
+    for entry in &manifest.tables {
+        if !history_db::EXPORT_TABLES.contains(&entry.table.as_str()) {
+            continue;
+        }
+        let Some(csv) = read_zip_entry(&mut archive, &entry.file)? else {
+            return Err(format!("Archive is missing {}", entry.file));
+        };
+        stage_csv_table(conn, &entry.table, &csv)?;
+    }
+
+    if let Some(json) = read_zip_entry(&mut archive, TODAY_FILE_NAME)? {
+        let today: DailyStats = serde_json::from_str(&json)
+            .map_err(|e| format!("Failed to parse {}: {}", TODAY_FILE_NAME, e))?;
+        stage_day_if_missing(conn, &today)?;
+    }
+
+    let achievements = match read_zip_entry(&mut archive, ACHIEVEMENTS_FILE_NAME)? {
+        Some(json) => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse {}: {}", ACHIEVEMENTS_FILE_NAME, e))?,
+        None => None,
+    };
+    Ok(Staged {
+        achievements,
+        exported_at_ms: Some(manifest.exported_at_ms),
+    })
+}
+
+fn sidecar(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_os_string();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+/// Copies another machine's `history.sqlite3` (and its WAL, if any) so the source is never
+/// written to; opening the copy migrates it to the current schema.
+fn stage_sqlite(source: &Path, staging: &Path) -> Result<Connection, String> {
+    fs::copy(source, staging)
+        .map_err(|e| format!("Failed to copy {}: {}", source.display(), e))?;
+    let wal = sidecar(source, "-wal");
+    if wal.exists() {
+        fs::copy(&wal, sidecar(staging, "-wal"))
+            .map_err(|e| format!("Failed to copy {}: {}", wal.display(), e))?;
+    }
+    history_db::open_staging_db(staging)
+}
+
+fn stage_state_file(conn: &Connection, path: &Path) -> Result<Option<AchievementState>, String> {
+    let json = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    let state: ImportedState = serde_json::from_str(&json)
+        .map_err(|e| format!("Failed to parse {}: {}", path.display(), e))?;
+    if let Some(stats) = state.stats {
+        for day in stats.history.iter().chain(std::iter::once(&stats.today)) {
+            stage_day_if_missing(conn, day)?;
+        }
+    }
+    Ok(state.achievements)
+}
+
+fn stage(source: &Path, state_path: Option<&Path>, staging: &Path) -> Result<Staged, String> {
+    let mut magic = [0u8; 16];
+    let read = fs::File::open(source)
+        .and_then(|mut f| f.read(&mut magic))
+        .map_err(|e| format!("Failed to read {}: {}", source.display(), e))?;
+    let magic = &magic[..read];
+
+    let (conn, mut staged) = if magic.starts_with(ZIP_MAGIC) {
+        let conn = history_db::open_staging_db(staging)?;
+        let file = fs::File::open(source)
+            .map_err(|e| format!("Failed to open {}: {}", source.display(), e))?;
+        let staged = stage_archive(&conn, std::io::BufReader::new(file))?;
+        (conn, staged)
+    } else if magic.starts_with(SQLITE_MAGIC) {
+        (stage_sqlite(source, staging)?, Staged::default())
+    } else {
+        return Err(format!(
+            "{} is neither an exported archive nor a history database",
+            source.display()
+        ));
+    };
+
+    if let Some(state_path) = state_path {
+        if let Some(state_achievements) = stage_state_file(&conn, state_path)? {
+            match staged.achievements.as_mut() {
+                Some(a) => {
+                    a.merge_from(&state_achievements);
+                }
+                None => staged.achievements = Some(state_achievements),
+            }
+        }
+    }
+    Ok(staged)
+}
+
+fn remove_staging(staging: &Path) {
+    let _ = fs::remove_file(staging);
+    let _ = fs::remove_file(sidecar(staging, "-wal"));
+    let _ = fs::remove_file(sidecar(staging, "-shm"));
+}
+
+/// Merges an exported archive or a `history.sqlite3` (optionally with its `state.json`) into the
+/// local history. With `dry_run` nothing is written and the report describes what would change.
+pub fn import_data(
+    app: &AppHandle,
+    source: &Path,
+    state_path: Option<&Path>,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    let staging = std::env::temp_dir().join(format!(
+        "cyber-zen-import-{}.sqlite3",
+        chrono::Utc::now().timestamp_millis()
+    ));
+    let res = import_staged(app, source, state_path, &staging, dry_run);
+    remove_staging(&staging);
+    res
+}
+
+fn import_staged(
+    app: &AppHandle,
+    source: &Path,
+    state_path: Option<&Path>,
+    staging: &Path,
+    dry_run: bool,
+) -> Result<ImportReport, String> {
+    let Staged {
+        achievements,
+        exported_at_ms,
+    } = stage(source, state_path, staging)?;
+    let mut files = vec![source];
+    files.extend(state_path);
+    let import_source = ImportSource {
+        id: fingerprint(&files)?,
+        exported_at_ms,
+    };
+
+    // Held from reading the live day's key until it is merged, so a rollover at midnight can't
+    // move the live day in between.
+    let storage = MeritStorage::instance();
+    let mut storage = storage.write();
+    let live_date = storage.live_date_key();
+    let merge = history_db::merge_import_db(
+        staging.to_path_buf(),
+        Some(live_date.clone()),
+        Some(import_source),
+        dry_run,
+    )?;
+
+    let conn = history_db::open_staging_db(staging)?;
+    let live_day = history_db::load_day_from(&conn, &live_date)?;
+    drop(conn);
+
+    let new_achievement_unlocks = if dry_run {
+        let mut local = storage.get_achievements();
+        achievements
+            .as_ref()
+            .map(|a| local.merge_from(a))
+            .unwrap_or(0)
+    } else {
+        storage.merge_imported_history(live_day.as_ref(), merge.imported_total, app);
+        let unlocks = achievements
+            .as_ref()
+            .map(|a| storage.merge_achievements(a, app))
+            .unwrap_or(0);
+        let level = storage.key_privacy_level();
+        drop(storage);
+        // Imported rows must not bring back key detail the current privacy level forbids.
+        crate::core::key_privacy::scrub_stored(level)?;
+        unlocks
+    };
+
+    Ok(ImportReport {
+        dry_run,
+        merge,
+        live_day_merged: live_day.is_some(),
+        new_achievement_unlocks,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_csv_handles_quotes_and_nulls() {
+        let rows = parse_csv("a,b,c\n1,\"x, \"\"y\"\"\",\n2,\"\",\"line\nbreak\"\n").unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec![Some("a".into()), Some("b".into()), Some("c".into())],
+                vec![Some("1".into()), Some("x, \"y\"".into()), None],
+                vec![Some("2".into()), Some(String::new()), Some("line\nbreak".into())],
+            ]
+        );
+        assert!(parse_csv("\"open").is_err());
+    }
+
+    #[test]
+    fn stage_csv_table_skips_unknown_columns() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE daily_hourly (date_key TEXT, hour INTEGER, total INTEGER, keyboard INTEGER, mouse_single INTEGER, PRIMARY KEY(date_key, hour));",
+        )
+        .unwrap();
+
+        let csv = "date_key,hour,total,keyboard,mouse_single,future\n2024-03-01,9,5,3,2,x\n";
+        assert_eq!(stage_csv_table(&conn, "daily_hourly", csv).unwrap(), 1);
+        let total: i64 = conn
+            .query_row("SELECT total FROM daily_hourly WHERE hour=9", [], |r| r.get(0))
+            .unwrap();
+        assert_eq!(total, 5);
+    }
+
+    #[test]
+    fn export_round_trip_keeps_empty_strings_and_nulls_apart() {
+        let schema = "CREATE TABLE app_meta (app_id TEXT PRIMARY KEY, last_name TEXT NOT NULL, note TEXT);";
+        let source = Connection::open_in_memory().unwrap();
+        source.execute_batch(schema).unwrap();
+        source
+            .execute_batch("INSERT INTO app_meta VALUES ('a', '', NULL), ('b', 'Editor', '');")
+            .unwrap();
+        let (_, csv, _) = crate::core::data_export::table_to_csv(&source, "app_meta").unwrap();
+
+        let target = Connection::open_in_memory().unwrap();
+        target.execute_batch(schema).unwrap();
+        assert_eq!(stage_csv_table(&target, "app_meta", &csv).unwrap(), 2);
+        let rows: Vec<(String, String, Option<String>)> = target
+            .prepare("SELECT app_id, last_name, note FROM app_meta ORDER BY app_id")
+            .unwrap()
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?)))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(
+            rows,
+            [
+                ("a".to_string(), String::new(), None),
+                ("b".to_string(), "Editor".to_string(), Some(String::new())),
+            ]
+        );
+    }
+}
@@ -0,0 +1,290 @@
+//! Raw `/dev/input/event*` capture for Linux sessions where rdev's X11 hook sees nothing (Wayland).
+//!
+//! Reading the device nodes needs membership in the `input` group (or an equivalent udev ACL).
+//! Events are observed, never grabbed, so other clients keep receiving them.
+
+use parking_lot::Mutex;
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::os::raw::c_long;
+use std::path::Path;
+use std::sync::{mpsc, Arc};
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy)]
+pub enum RawInputEvent {
+    /// A keyboard `KEY_*` code; `repeat` is set for kernel autorepeat.
+    KeyDown { code: u16, repeat: bool },
+    KeyUp { code: u16 },
+    /// A `BTN_*` mouse button code (`BTN_LEFT..=BTN_TASK`).
+    MouseDown { button: u16 },
+    MouseUp { button: u16 },
+    /// Relative pointer motion, summed per `SYN_REPORT` frame.
+    MouseMove { dx: i32, dy: i32 },
+    /// Wheel notches (`REL_HWHEEL`/`REL_WHEEL`), summed per `SYN_REPORT` frame.
+    Scroll { dx: i32, dy: i32 },
+}
+
+const BTN_LEFT: u16 = 0x110;
+/// Last of the mouse buttons (`BTN_TASK`).
+const BTN_MOUSE_LAST: u16 = 0x117;
+
+const DEV_INPUT_DIR: &str = "/dev/input";
+const SYSFS_INPUT_DIR: &str = "/sys/class/input";
+const RESCAN_INTERVAL: Duration = Duration::from_secs(3);
+
+const EV_SYN: u16 = 0;
+const EV_KEY: u16 = 1;
+const EV_REL: u16 = 2;
+const SYN_REPORT: u16 = 0;
+const REL_X: u16 = 0;
+const REL_Y: u16 = 1;
+const REL_HWHEEL: u16 = 6;
+const REL_WHEEL: u16 = 8;
+
+/// `struct input_event`: a `timeval` (two longs), then type, code and value.
+const TIME_BYTES: usize = 2 * std::mem::size_of::<c_long>();
+const EVENT_BYTES: usize = TIME_BYTES + 8;
+
+#[derive(Debug, Clone)]
+pub enum RunError {
+    /// Input devices exist but none could be opened.
+    PermissionDenied(String),
+    Failed(String),
+}
+
+/// Whether the desktop session is Wayland, where rdev only sees XWayland clients.
+pub fn is_wayland_session() -> bool {
+    std::env::var("XDG_SESSION_TYPE").is_ok_and(|v| v.eq_ignore_ascii_case("wayland"))
+        || std::env::var_os("WAYLAND_DISPLAY").is_some_and(|v| !v.is_empty())
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct DeviceKind {
+    keyboard: bool,
+    pointer: bool,
+}
+
+/// Parses a sysfs capability bitmask: space separated hex words, most significant word first.
+fn parse_bitmask(text: &str) -> Vec<u64> {
+    text.split_whitespace()
+        .rev()
+        .map(|word| u64::from_str_radix(word, 16).unwrap_or(0))
+        .collect()
+}
+
+fn has_bit(words: &[u64], bit: usize) -> bool {
+    // Words are `unsigned long` sized in sysfs.
+    let word_bits = usize::BITS as usize;
+    words
+        .get(bit / word_bits)
+        .is_some_and(|w| w >> (bit % word_bits) & 1 == 1)
+}
+
+fn classify(key_caps: &str, rel_caps: &str) -> DeviceKind {
+    const KEY_A: usize = 30;
+    const KEY_Z: usize = 44;
+    const KEY_SPACE: usize = 57;
+
+    let keys = parse_bitmask(key_caps);
+    let rel = parse_bitmask(rel_caps);
+    DeviceKind {
+        keyboard: [KEY_A, KEY_Z, KEY_SPACE].iter().all(|&k| has_bit(&keys, k)),
+        pointer: has_bit(&rel, REL_X as usize)
+            && has_bit(&rel, REL_Y as usize)
+            && has_bit(&keys, BTN_LEFT as usize),
+    }
+}
+
+fn read_caps(name: &str, cap: &str) -> String {
+    std::fs::read_to_string(
+        Path::new(SYSFS_INPUT_DIR)
+            .join(name)
+            .join("device/capabilities")
+            .join(cap),
+    )
+    .unwrap_or_default()
+}
+
+fn device_kind(name: &str) -> DeviceKind {
+    classify(&read_caps(name, "key"), &read_caps(name, "rel"))
+}
+
+struct ScanResult {
+    candidates: usize,
+    denied: usize,
+}
+
+/// Opens any keyboard or pointer node not read yet and starts a reader thread for it.
+fn scan(tx: &mpsc::Sender<RawInputEvent>, active: &Arc<Mutex<HashSet<String>>>) -> ScanResult {
+    let mut result = ScanResult {
+        candidates: 0,
+        denied: 0,
+    };
+    let Ok(entries) = std::fs::read_dir(DEV_INPUT_DIR) else {
+        return result;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.starts_with("event") {
+            continue;
+        }
+        if active.lock().contains(&name) {
+            continue;
+        }
+        let kind = device_kind(&name);
+        if kind == DeviceKind::default() {
+            continue;
+        }
+        result.candidates += 1;
+
+        let file = match File::open(entry.path()) {
+            Ok(file) => file,
+            Err(e) => {
+                if e.kind() == ErrorKind::PermissionDenied {
+                    result.denied += 1;
+                }
+                continue;
+            }
+        };
+
+        active.lock().insert(name.clone());
+        let tx = tx.clone();
+        let active = Arc::clone(active);
+        std::thread::spawn(move || {
+            read_device(file, kind, &tx);
+            // Unplugged (ENODEV) or the consumer went away; allow a later rescan to reopen.
+            active.lock().remove(&name);
+        });
+    }
+    result
+}
+
+fn read_device(mut file: File, kind: DeviceKind, tx: &mpsc::Sender<RawInputEvent>) {
+    let mut buf = vec![0u8; EVENT_BYTES * 64];
+    let (mut dx, mut dy) = (0i32, 0i32);
+    let (mut wheel_x, mut wheel_y) = (0i32, 0i32);
+
+    loop {
+        let n = match file.read(&mut buf) {
+            Ok(0) => return,
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(_) => return,
+        };
+
+        for raw in buf[..n].chunks_exact(EVENT_BYTES) {
+            let ty = u16::from_ne_bytes([raw[TIME_BYTES], raw[TIME_BYTES + 1]]);
+            let code = u16::from_ne_bytes([raw[TIME_BYTES + 2], raw[TIME_BYTES + 3]]);
+            let value = i32::from_ne_bytes([
+                raw[TIME_BYTES + 4],
+                raw[TIME_BYTES + 5],
+                raw[TIME_BYTES + 6],
+                raw[TIME_BYTES + 7],
+            ]);
+
+            let event = match (ty, code) {
+                (EV_KEY, BTN_LEFT..=BTN_MOUSE_LAST) if kind.pointer => {
+                    match value {
+                        0 => Some(RawInputEvent::MouseUp { button: code }),
+                        1 => Some(RawInputEvent::MouseDown { button: code }),
+                        _ => None,
+                    }
+                }
+                // Skip the BTN_* range (0x100..0x160); KEY_* codes sit on either side of it.
+                (EV_KEY, 1..=0xff | 0x160..) if kind.keyboard => match value {
+                    0 => Some(RawInputEvent::KeyUp { code }),
+                    1 => Some(RawInputEvent::KeyDown { code, repeat: false }),
+                    2 => Some(RawInputEvent::KeyDown { code, repeat: true }),
+                    _ => None,
+                },
+                (EV_REL, REL_X) if kind.pointer => {
+                    dx = dx.saturating_add(value);
+                    None
+                }
+                (EV_REL, REL_Y) if kind.pointer => {
+                    dy = dy.saturating_add(value);
+                    None
+                }
+                (EV_REL, REL_HWHEEL) if kind.pointer => {
+                    wheel_x = wheel_x.saturating_add(value);
+                    None
+                }
+                (EV_REL, REL_WHEEL) if kind.pointer => {
+                    wheel_y = wheel_y.saturating_add(value);
+                    None
+                }
+                (EV_SYN, SYN_REPORT) => {
+                    if wheel_x != 0 || wheel_y != 0 {
+                        let scrolled = RawInputEvent::Scroll {
+                            dx: wheel_x,
+                            dy: wheel_y,
+                        };
+                        (wheel_x, wheel_y) = (0, 0);
+                        if tx.send(scrolled).is_err() {
+                            return;
+                        }
+                    }
+                    (dx != 0 || dy != 0).then(|| {
+                        let moved = RawInputEvent::MouseMove { dx, dy };
+                        (dx, dy) = (0, 0);
+                        moved
+                    })
+                }
+                _ => None,
+            };
+
+            if let Some(event) = event {
+                if tx.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Reads input devices until the process exits; only returns early if nothing can be opened.
+pub fn run(tx: mpsc::Sender<RawInputEvent>) -> Result<(), RunError> {
+    let active = Arc::new(Mutex::new(HashSet::new()));
+
+    let first = scan(&tx, &active);
+    if active.lock().is_empty() {
+        return Err(if first.denied > 0 {
+            RunError::PermissionDenied(format!(
+                "Permission denied reading {} input device(s) under {}: add your user to the `input` group (sudo usermod -aG input $USER) and log in again",
+                first.denied, DEV_INPUT_DIR
+            ))
+        } else if first.candidates == 0 {
+            RunError::Failed(format!("No keyboard or mouse devices found under {}", DEV_INPUT_DIR))
+        } else {
+            RunError::Failed(format!("Failed to open input devices under {}", DEV_INPUT_DIR))
+        });
+    }
+
+    // Keep scanning so devices plugged in later (or re-created after resume) are picked up.
+    loop {
+        std::thread::sleep(RESCAN_INTERVAL);
+        scan(&tx, &active);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_devices_from_sysfs_capabilities() {
+        // A typical keyboard: KEY_ESC..KEY_KPDOT and more in the lowest word.
+        let keyboard = classify("1000000000007 ff9f207ac14057ff febeffdfffefffff fffffffffffffffe", "0");
+        assert_eq!(keyboard, DeviceKind { keyboard: true, pointer: false });
+
+        // A mouse: BTN_LEFT..BTN_TASK (0x110..0x117) and REL_X | REL_Y | REL_WHEEL.
+        let mouse = classify("ff0000 0 0 0 0", "143");
+        assert_eq!(mouse, DeviceKind { keyboard: false, pointer: true });
+
+        // Power button: KEY_POWER only.
+        assert_eq!(classify("10000000000000 0", ""), DeviceKind::default());
+    }
+}
@@ -0,0 +1,43 @@
+//! Touch-typing finger assignment for physical keys (`key_codes` canonical codes).
+//!
+//! Codes name key positions, not characters, so the assignment holds for any logical layout.
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Hand {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Finger {
+    Pinky,
+    Ring,
+    Middle,
+    Index,
+}
+
+/// The finger that types `code` on a row-staggered keyboard; `None` for keys off the main block
+/// and for Space, which either thumb may press.
+pub fn finger_for(code: &str) -> Option<(Hand, Finger)> {
+    use Finger::*;
+    use Hand::*;
+    Some(match code {
+        // The ISO key next to left Shift has no code of its own; it is counted as `Backslash`.
+        "Backquote" | "Digit1" | "KeyQ" | "KeyA" | "KeyZ" | "Tab" | "CapsLock" | "ShiftLeft"
+        | "ControlLeft" => (Left, Pinky),
+        "Digit2" | "KeyW" | "KeyS" | "KeyX" => (Left, Ring),
+        "Digit3" | "KeyE" | "KeyD" | "KeyC" => (Left, Middle),
+        "Digit4" | "Digit5" | "KeyR" | "KeyT" | "KeyF" | "KeyG" | "KeyV" | "KeyB" => (Left, Index),
+        "Digit6" | "Digit7" | "KeyY" | "KeyU" | "KeyH" | "KeyJ" | "KeyN" | "KeyM" => (Right, Index),
+        "Digit8" | "KeyI" | "KeyK" | "Comma" => (Right, Middle),
+        "Digit9" | "KeyO" | "KeyL" | "Period" => (Right, Ring),
+        "Digit0" | "Minus" | "Equal" | "KeyP" | "BracketLeft" | "BracketRight" | "Backslash"
+        | "Semicolon" | "Quote" | "Slash" | "Enter" | "Backspace" | "ShiftRight"
+        | "ControlRight" => (Right, Pinky),
+        _ => return None,
+    })
+}
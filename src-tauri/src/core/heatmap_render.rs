@@ -0,0 +1,280 @@
+//! PNG rendering of click and movement heatmaps, for exports that don't go through the webview.
+//!
+//! Counts live on the square base grid (see `models::click_heatmap`), which is stretched over the
+//! monitor, so images are laid out in the monitor's aspect ratio rather than the grid's.
+
+use serde::{Deserialize, Serialize};
+
+const MIN_SIZE: u32 = 64;
+const MAX_SIZE: u32 = 2048;
+const MAX_BLUR_SIGMA: f32 = 64.0;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorRamp {
+    /// Blue through green and yellow to red.
+    #[default]
+    Heat,
+    Viridis,
+    Magma,
+    Grayscale,
+}
+
+impl ColorRamp {
+    fn stops(self) -> &'static [(f32, [u8; 3])] {
+        match self {
+            ColorRamp::Heat => &[
+                (0.0, [0, 0, 130]),
+                (0.25, [0, 120, 255]),
+                (0.5, [0, 220, 120]),
+                (0.75, [255, 220, 0]),
+                (1.0, [230, 20, 20]),
+            ],
+            ColorRamp::Viridis => &[
+                (0.0, [68, 1, 84]),
+                (0.25, [59, 82, 139]),
+                (0.5, [33, 145, 140]),
+                (0.75, [94, 201, 98]),
+                (1.0, [253, 231, 37]),
+            ],
+            ColorRamp::Magma => &[
+                (0.0, [0, 0, 4]),
+                (0.25, [81, 18, 124]),
+                (0.5, [183, 55, 121]),
+                (0.75, [252, 137, 97]),
+                (1.0, [252, 253, 191]),
+            ],
+            ColorRamp::Grayscale => &[(0.0, [0, 0, 0]), (1.0, [255, 255, 255])],
+        }
+    }
+
+    /// Color at `t` in `0.0..=1.0`, interpolated linearly between stops.
+    fn color(self, t: f32) -> [u8; 3] {
+        let t = t.clamp(0.0, 1.0);
+        let stops = self.stops();
+        let upper = stops
+            .iter()
+            .position(|(at, _)| *at >= t)
+            .unwrap_or(stops.len() - 1);
+        if upper == 0 {
+            return stops[0].1;
+        }
+        let (a_at, a) = stops[upper - 1];
+        let (b_at, b) = stops[upper];
+        let f = if b_at > a_at {
+            (t - a_at) / (b_at - a_at)
+        } else {
+            1.0
+        };
+        let mix = |i: usize| (a[i] as f32 + (b[i] as f32 - a[i] as f32) * f).round() as u8;
+        [mix(0), mix(1), mix(2)]
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatScale {
+    /// `ln(1 + v)`, so a few hot spots don't wash out everything else.
+    #[default]
+    Log,
+    Linear,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeatmapRenderOptions {
+    pub ramp: ColorRamp,
+    pub scale: HeatScale,
+    /// Gaussian blur sigma in output pixels; `0` keeps hard cell edges.
+    #[serde(alias = "blur_radius")]
+    pub blur_sigma: f32,
+    /// Leaves empty areas transparent and fades cold areas out, for overlaying on a screenshot.
+    pub transparent: bool,
+    /// Length of the longer image side in pixels.
+    pub size: u32,
+}
+
+impl Default for HeatmapRenderOptions {
+    fn default() -> Self {
+        Self {
+            ramp: ColorRamp::default(),
+            scale: HeatScale::default(),
+            blur_sigma: 6.0,
+            transparent: true,
+            size: 1024,
+        }
+    }
+}
+
+/// Image size for a monitor of `aspect` (width, height), with the longer side `size` pixels.
+fn output_size(aspect: Option<(f64, f64)>, size: u32) -> (u32, u32) {
+    let size = size.clamp(MIN_SIZE, MAX_SIZE);
+    let (w, h) = match aspect {
+        Some((w, h)) if w.is_finite() && h.is_finite() && w > 0.0 && h > 0.0 => (w, h),
+        _ => return (size, size),
+    };
+    let short = |long: f64, other: f64| ((size as f64 * other / long).round() as u32).max(1);
+    if w >= h {
+        (size, short(w, h))
+    } else {
+        (short(h, w), size)
+    }
+}
+
+/// Spreads each base cell's count over the output pixels it covers.
+fn rasterize(
+    cells: &[(u32, u32)],
+    base_cols: usize,
+    base_rows: usize,
+    width: usize,
+    height: usize,
+) -> Vec<f32> {
+    let mut out = vec![0f32; width * height];
+    if base_cols == 0 || base_rows == 0 {
+        return out;
+    }
+    for &(idx, count) in cells {
+        let idx = idx as usize;
+        if count == 0 || idx >= base_cols * base_rows {
+            continue;
+        }
+        let (x, y) = (idx % base_cols, idx / base_cols);
+        let x0 = x * width / base_cols;
+        let x1 = ((x + 1) * width / base_cols).max(x0 + 1).min(width);
+        let y0 = y * height / base_rows;
+        let y1 = ((y + 1) * height / base_rows).max(y0 + 1).min(height);
+        for py in y0..y1 {
+            for px in x0..x1 {
+                out[py * width + px] += count as f32;
+            }
+        }
+    }
+    out
+}
+
+/// Separable gaussian blur with clamped edges.
+fn blur(values: &mut [f32], width: usize, height: usize, sigma: f32) {
+    if !(sigma.is_finite() && sigma > 0.0) || width == 0 || height == 0 {
+        return;
+    }
+    let sigma = sigma.min(MAX_BLUR_SIGMA);
+    let radius = (sigma * 3.0).ceil() as isize;
+    let mut kernel: Vec<f32> = (-radius..=radius)
+        .map(|i| (-(i * i) as f32 / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let sum: f32 = kernel.iter().sum();
+    kernel.iter_mut().for_each(|k| *k /= sum);
+
+    let pass = |src: &[f32],
+                dst: &mut [f32],
+                len: usize,
+                lines: usize,
+                at: &dyn Fn(usize, usize) -> usize| {
+        for line in 0..lines {
+            for i in 0..len {
+                let mut acc = 0.0;
+                for (k, weight) in kernel.iter().enumerate() {
+                    let j = (i as isize + k as isize - radius).clamp(0, len as isize - 1) as usize;
+                    acc += src[at(line, j)] * weight;
+                }
+                dst[at(line, i)] = acc;
+            }
+        }
+    };
+
+    let mut tmp = vec![0f32; values.len()];
+    pass(values, &mut tmp, width, height, &|row, x| row * width + x);
+    pass(&tmp, values, height, width, &|col, y| y * width + col);
+}
+
+/// Renders base grid cells (as returned by `click_heatmap::load_base_cells`) to PNG bytes.
+///
+/// `aspect` is the monitor's size in any unit; without it the image is square.
+pub fn render_png(
+    cells: &[(u32, u32)],
+    base_cols: usize,
+    base_rows: usize,
+    aspect: Option<(f64, f64)>,
+    options: &HeatmapRenderOptions,
+) -> Result<Vec<u8>, String> {
+    let (width, height) = output_size(aspect, options.size);
+    let (w, h) = (width as usize, height as usize);
+
+    let mut values = rasterize(cells, base_cols, base_rows, w, h);
+    blur(&mut values, w, h, options.blur_sigma);
+
+    let scaled = |v: f32| match options.scale {
+        HeatScale::Log => v.ln_1p(),
+        HeatScale::Linear => v,
+    };
+    let max = values.iter().copied().fold(0f32, f32::max);
+    let max = scaled(max);
+
+    let mut rgba = Vec::with_capacity(w * h * 4);
+    for &v in &values {
+        let t = if max > 0.0 {
+            (scaled(v) / max).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let [r, g, b] = options.ramp.color(t);
+        let alpha = if !options.transparent {
+            255
+        } else if t <= 0.004 {
+            0
+        } else {
+            // Keep faint areas visible without hiding what's underneath.
+            ((0.25 + 0.75 * t) * 255.0).round() as u8
+        };
+        rgba.extend_from_slice(&[r, g, b, alpha]);
+    }
+
+    encode_png(width, height, &rgba)
+}
+
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder
+        .write_header()
+        .map_err(|e| format!("Failed to write PNG header: {}", e))?;
+    writer
+        .write_image_data(rgba)
+        .map_err(|e| format!("Failed to encode PNG: {}", e))?;
+    writer
+        .finish()
+        .map_err(|e| format!("Failed to finish PNG: {}", e))?;
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_to_monitor_aspect_and_blurs_without_losing_mass() {
+        assert_eq!(output_size(Some((2560.0, 1440.0)), 1024), (1024, 576));
+        assert_eq!(output_size(Some((1080.0, 1920.0)), 800), (450, 800));
+        assert_eq!(output_size(None, 10), (MIN_SIZE, MIN_SIZE));
+
+        let mut values = rasterize(&[(0, 3), (5, 1)], 4, 4, 8, 8);
+        assert_eq!(values[0], 3.0);
+        assert_eq!(values[9], 3.0);
+        assert_eq!(values[2 * 8 + 2], 1.0);
+        assert_eq!(values.iter().sum::<f32>(), 16.0);
+
+        // Away from the edges, blurring only spreads counts around.
+        let mut spot = vec![0f32; 32 * 32];
+        spot[16 * 32 + 16] = 10.0;
+        blur(&mut spot, 32, 32, 2.0);
+        assert!((spot.iter().sum::<f32>() - 10.0).abs() < 1e-3);
+        assert!(spot[16 * 32 + 16] < 10.0 && spot[16 * 32 + 17] > 0.0);
+
+        blur(&mut values, 8, 8, 0.0);
+        assert_eq!(values[0], 3.0);
+        assert_eq!(ColorRamp::Grayscale.color(0.5), [128, 128, 128]);
+        assert_eq!(ColorRamp::Heat.color(1.0), [230, 20, 20]);
+    }
+}
@@ -1,7 +1,13 @@
-use crate::models::{ClickHeatmapState, DailyStats};
+use crate::models::{
+    ClickHeatmapState, DailyStats, HeatmapKind, KeyBigramCount, KeyPrivacyLevel, SessionRecord,
+    SessionStats, Settings, StatisticsBucket, StatisticsGranularity, TimelineBucket,
+    TypingAccuracyHour, TypingSpeedDay, TypingSpeedHour,
+};
+use chrono::Timelike;
 use once_cell::sync::Lazy;
 use parking_lot::Mutex;
-use rusqlite::{params, Connection, OpenFlags};
+use rusqlite::{params, params_from_iter, Connection, OpenFlags, OptionalExtension, ParamsFromIter};
+use serde::Serialize;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -14,6 +20,7 @@ enum DbOp {
     BulkUpsertDaily(Vec<DailyStats>),
     ClearDaily,
     HeatmapDelta {
+        kind: HeatmapKind,
         date_key: Arc<str>,
         display_id: Arc<str>,
         idx: u32,
@@ -24,11 +31,62 @@ enum DbOp {
         reply: Sender<Result<(), String>>,
     },
     ClearHeatmap {
+        kind: HeatmapKind,
         display_id: Option<String>,
         date_key: Option<String>,
         reply: Sender<Result<(), String>>,
     },
-    Vacuum,
+    MergeImport {
+        source: PathBuf,
+        live_date: Option<String>,
+        import_source: Option<ImportSource>,
+        dry_run: bool,
+        reply: Sender<Result<ImportMergeReport, String>>,
+    },
+    TimelineDelta {
+        date_key: Arc<str>,
+        minute: u32,
+        delta: TimelineDelta,
+    },
+    TypingSpeedDelta {
+        date_key: Arc<str>,
+        hour: u32,
+        delta: TypingSpeedDelta,
+    },
+    CorrectionDelta {
+        date_key: Arc<str>,
+        hour: u32,
+        delta: CorrectionDelta,
+    },
+    KeyBigram {
+        date_key: Arc<str>,
+        first: Arc<str>,
+        second: Arc<str>,
+    },
+    SetRetention(RetentionPolicy),
+    Prune {
+        reply: Option<Sender<Result<Vec<HistoryTableRows>, String>>>,
+    },
+    InsertSession {
+        record: Box<SessionRecord>,
+        reply: Option<Sender<Result<(), String>>>,
+    },
+    Backup {
+        dest: PathBuf,
+        reply: Sender<Result<(), String>>,
+    },
+    Restore {
+        src: PathBuf,
+        reply: Sender<Result<(), String>>,
+    },
+    Vacuum {
+        full: bool,
+        reply: Option<Sender<Result<(), String>>>,
+    },
+    ScrubKeyDetails {
+        level: KeyPrivacyLevel,
+        reply: Sender<Result<Vec<HistoryTableRows>, String>>,
+    },
 }
 
 #[derive(Clone)]
@@ -49,6 +107,9 @@ fn now_ms() -> i64 {
 fn open_write_conn(path: &Path) -> Result<Connection, String> {
     let conn = Connection::open(path).map_err(|e| format!("Failed to open sqlite db: {}", e))?;
     let _ = conn.busy_timeout(Duration::from_secs(2));
+    // Only takes effect for new databases; existing ones switch over on their next full VACUUM.
+    conn.pragma_update(None, "auto_vacuum", "INCREMENTAL")
+        .map_err(|e| format!("Failed to set auto_vacuum=INCREMENTAL: {}", e))?;
     conn.pragma_update(None, "journal_mode", "WAL")
         .map_err(|e| format!("Failed to set journal_mode=WAL: {}", e))?;
     conn.pragma_update(None, "synchronous", "NORMAL")
@@ -64,14 +125,94 @@ fn open_read_conn(path: &Path) -> Result<Connection, String> {
     Ok(conn)
 }
 
-fn migrate(conn: &Connection) -> Result<(), String> {
+/// Latest schema version understood by this build. Bump together with a new `MIGRATIONS` entry.
+pub const SCHEMA_VERSION: i64 = 10;
+
+type MigrationFn = fn(&Connection) -> Result<(), String>;
+
+/// Ordered schema migrations keyed by the version they upgrade to. Each step runs in its own
+/// transaction together with the `schema_version` bump, so a failing step leaves the database at
+/// the previous version.
+const MIGRATIONS: &[(i64, MigrationFn)] = &[
+    (1, migrate_v1_base_schema),
+    (2, migrate_v2_normalize_daily_counters),
+    (3, migrate_v3_activity_timeline),
+    (4, migrate_v4_sessions),
+    (5, migrate_v5_typing_speed),
+    (6, migrate_v6_corrections),
+    (7, migrate_v7_key_bigrams),
+    (8, migrate_v8_scroll),
+    (9, migrate_v9_move_heatmap),
+    (10, migrate_v10_import_log),
+];
+
+fn read_schema_version(conn: &Connection) -> Result<i64, String> {
+    let raw: Option<String> = conn
+        .query_row(
+            "SELECT value FROM schema_meta WHERE key='schema_version'",
+            [],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to read schema_version: {}", e))?;
+    match raw {
+        Some(v) => v
+            .trim()
+            .parse::<i64>()
+            .map_err(|_| format!("Invalid schema_version value: {}", v)),
+        None => Ok(0),
+    }
+}
+
+fn write_schema_version(conn: &Connection, version: i64) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO schema_meta(key, value) VALUES('schema_version', ?1) ON CONFLICT(key) DO UPDATE SET value=excluded.value",
+        params![version.to_string()],
+    )
+    .map_err(|e| format!("Failed to write schema_version: {}", e))?;
+    Ok(())
+}
+
+fn migrate(conn: &mut Connection) -> Result<(), String> {
     conn.execute_batch(
         r#"
 CREATE TABLE IF NOT EXISTS schema_meta (
   key TEXT PRIMARY KEY,
   value TEXT NOT NULL
 );
+"#,
+    )
+    .map_err(|e| format!("Failed to create schema_meta: {}", e))?;
+
+    let current = read_schema_version(conn)?;
+    if current > SCHEMA_VERSION {
+        return Err(format!(
+            "history db schema version {} is newer than supported version {}; please update the app",
+            current, SCHEMA_VERSION
+        ));
+    }
 
+    for &(version, step) in MIGRATIONS {
+        if version <= current {
+            continue;
+        }
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
+        step(&tx).map_err(|e| format!("Failed to migrate sqlite schema to v{}: {}", version, e))?;
+        write_schema_version(&tx, version)?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit sqlite schema v{}: {}", version, e))?;
+    }
+
+    Ok(())
+}
+
+fn migrate_v1_base_schema(conn: &Connection) -> Result<(), String> {
+    // Databases created before `schema_version` existed already contain some or all of these
+    // tables, so every statement must stay idempotent.
+    conn.execute_batch(
+        r#"
 CREATE TABLE IF NOT EXISTS daily_stats (
   date_key TEXT PRIMARY KEY,
   total INTEGER NOT NULL,
@@ -162,9 +303,9 @@ CREATE TABLE IF NOT EXISTS click_heatmap_daily_meta (
 );
 
 CREATE INDEX IF NOT EXISTS idx_click_heatmap_daily_date ON click_heatmap_daily_meta(date_key);
-"#,
+"#
     )
-    .map_err(|e| format!("Failed to migrate sqlite schema: {}", e))?;
+    .map_err(|e| format!("Failed to create base tables: {}", e))?;
 
     Ok(())
 }
@@ -285,7 +426,7 @@ fn replace_daily_hourly(conn: &Connection, date_key: &str, hourly: &[crate::mode
         .map_err(|e| format!("Failed to clear daily_hourly: {}", e))?;
 
     let mut stmt = conn
-        .prepare("INSERT INTO daily_hourly(date_key, hour, total, keyboard, mouse_single) VALUES (?1, ?2, ?3, ?4, ?5)")
+        .prepare("INSERT INTO daily_hourly(date_key, hour, total, keyboard, mouse_single, scroll) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")
         .map_err(|e| format!("Failed to prepare daily_hourly insert: {}", e))?;
 
     for (idx, b) in hourly.iter().enumerate().take(24) {
@@ -293,10 +434,11 @@ fn replace_daily_hourly(conn: &Connection, date_key: &str, hourly: &[crate::mode
         let total = i64::try_from(b.total).unwrap_or(i64::MAX);
         let keyboard = i64::try_from(b.keyboard).unwrap_or(i64::MAX);
         let mouse_single = i64::try_from(b.mouse_single).unwrap_or(i64::MAX);
-        if total == 0 && keyboard == 0 && mouse_single == 0 {
+        let scroll = i64::try_from(b.scroll).unwrap_or(i64::MAX);
+        if total == 0 && keyboard == 0 && mouse_single == 0 && scroll == 0 {
             continue;
         }
-        stmt.execute(params![date_key, hour, total, keyboard, mouse_single])
+        stmt.execute(params![date_key, hour, total, keyboard, mouse_single, scroll])
             .map_err(|e| format!("Failed to insert daily_hourly: {}", e))?;
     }
     Ok(())
@@ -312,7 +454,7 @@ fn replace_daily_app_input(
 
     let mut stmt = conn
         .prepare(
-            "INSERT INTO daily_app_input(date_key, app_id, name, keyboard, mouse_single) VALUES (?1, ?2, ?3, ?4, ?5)",
+            "INSERT INTO daily_app_input(date_key, app_id, name, keyboard, mouse_single, scroll, scroll_ticks) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
         )
         .map_err(|e| format!("Failed to prepare daily_app_input insert: {}", e))?;
 
@@ -330,13 +472,23 @@ fn replace_daily_app_input(
 
         let keyboard = i64::try_from(v.keyboard).unwrap_or(i64::MAX);
         let mouse_single = i64::try_from(v.mouse_single).unwrap_or(i64::MAX);
+        let scroll = i64::try_from(v.scroll).unwrap_or(i64::MAX);
+        let scroll_ticks = i64::try_from(v.scroll_ticks).unwrap_or(i64::MAX);
         let name = v
             .name
             .as_deref()
             .map(|s| s.trim())
             .filter(|s| !s.is_empty());
 
-        stmt.execute(params![date_key, trimmed, name, keyboard, mouse_single])
+        stmt.execute(params![
+            date_key,
+            trimmed,
+            name,
+            keyboard,
+            mouse_single,
+            scroll,
+            scroll_ticks
+        ])
             .map_err(|e| format!("Failed to insert daily_app_input: {}", e))?;
 
         if let Some(name) = name {
@@ -347,7 +499,7 @@ fn replace_daily_app_input(
     Ok(())
 }
 
-fn upsert_daily(conn: &Connection, day: &DailyStats) -> Result<(), String> {
+fn write_daily_stats_row(conn: &Connection, day: &DailyStats) -> Result<(), String> {
     let date_key = day.date.to_string();
     let total = i64::try_from(day.total).unwrap_or(i64::MAX);
     let keyboard = i64::try_from(day.keyboard).unwrap_or(i64::MAX);
@@ -369,8 +521,15 @@ ON CONFLICT(date_key) DO UPDATE SET
         params![date_key, total, keyboard, mouse_single, payload_json, now_ms()],
     )
     .map_err(|e| format!("Failed to upsert daily_stats: {}", e))?;
+    Ok(())
+}
+
+/// Writes a full day (summary row plus normalized counters), replacing whatever was stored.
+pub fn upsert_daily(conn: &Connection, day: &DailyStats) -> Result<(), String> {
+    write_daily_stats_row(conn, day)?;
 
     // Normalize heavy counters for aggregation queries.
+    let date_key = day.date.to_string();
     replace_daily_key_counts(conn, &date_key, 0, &day.key_counts)?;
     let unshifted = if !day.key_counts_unshifted.is_empty() {
         &day.key_counts_unshifted
@@ -387,7 +546,101 @@ ON CONFLICT(date_key) DO UPDATE SET
     Ok(())
 }
 
-fn migrate_daily_counters_v2(conn: &mut Connection) -> Result<(), String> {
+/// Moves a day's counters out of its JSON payload. Writes only the columns the v1 schema has,
+/// rather than going through the current writers, so later schema changes can't affect it.
+fn v2_write_day_counters(conn: &Connection, date_key: &str, day: &DailyStats) -> Result<(), String> {
+    let err = |what: &str, e: rusqlite::Error| format!("Failed to migrate {}: {}", what, e);
+    let count = |v: u64| i64::try_from(v).unwrap_or(i64::MAX);
+    for table in [
+        "daily_key_counts",
+        "daily_shortcut_counts",
+        "daily_mouse_button_counts",
+        "daily_hourly",
+        "daily_app_input",
+    ] {
+        conn.execute(&format!("DELETE FROM {} WHERE date_key=?1", table), params![date_key])
+            .map_err(|e| err(table, e))?;
+    }
+
+    let unshifted = if !day.key_counts_unshifted.is_empty() {
+        &day.key_counts_unshifted
+    } else {
+        &day.key_counts
+    };
+    let mut stmt = conn
+        .prepare("INSERT INTO daily_key_counts(date_key, kind, code, count) VALUES (?1, ?2, ?3, ?4)")
+        .map_err(|e| err("daily_key_counts", e))?;
+    for (kind, counts) in [(0i64, &day.key_counts), (1, unshifted), (2, &day.key_counts_shifted)] {
+        for (code, n) in counts.iter().filter(|(_, n)| **n > 0) {
+            stmt.execute(params![date_key, kind, code.as_ref(), count(*n)])
+                .map_err(|e| err("daily_key_counts", e))?;
+        }
+    }
+
+    for (table, column, counts) in [
+        ("daily_shortcut_counts", "shortcut", &day.shortcut_counts),
+        ("daily_mouse_button_counts", "button", &day.mouse_button_counts),
+    ] {
+        let mut stmt = conn
+            .prepare(&format!(
+                "INSERT INTO {}(date_key, {}, count) VALUES (?1, ?2, ?3)",
+                table, column
+            ))
+            .map_err(|e| err(table, e))?;
+        for (key, n) in counts.iter().filter(|(_, n)| **n > 0) {
+            let key = key.trim();
+            if key.is_empty() {
+                continue;
+            }
+            stmt.execute(params![date_key, key, count(*n)])
+                .map_err(|e| err(table, e))?;
+        }
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO daily_hourly(date_key, hour, total, keyboard, mouse_single) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .map_err(|e| err("daily_hourly", e))?;
+    for (hour, b) in day.hourly.iter().enumerate().take(24) {
+        if b.total == 0 && b.keyboard == 0 && b.mouse_single == 0 {
+            continue;
+        }
+        stmt.execute(params![
+            date_key,
+            hour as i64,
+            count(b.total),
+            count(b.keyboard),
+            count(b.mouse_single)
+        ])
+        .map_err(|e| err("daily_hourly", e))?;
+    }
+
+    let mut stmt = conn
+        .prepare(
+            "INSERT INTO daily_app_input(date_key, app_id, name, keyboard, mouse_single) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )
+        .map_err(|e| err("daily_app_input", e))?;
+    for (app_id, v) in &day.app_input_counts {
+        let app_id = app_id.trim();
+        if app_id.is_empty() {
+            continue;
+        }
+        let name = v.name.as_deref().map(str::trim).filter(|s| !s.is_empty());
+        stmt.execute(params![date_key, app_id, name, count(v.keyboard), count(v.mouse_single)])
+            .map_err(|e| err("daily_app_input", e))?;
+        if let Some(name) = name {
+            let _ = conn.execute(
+                "INSERT INTO app_meta(app_id, last_name, updated_at_ms) VALUES (?1, ?2, ?3) ON CONFLICT(app_id) DO UPDATE SET last_name=excluded.last_name, updated_at_ms=excluded.updated_at_ms",
+                params![app_id, name, now_ms()],
+            );
+        }
+    }
+    Ok(())
+}
+
+fn migrate_v2_normalize_daily_counters(conn: &Connection) -> Result<(), String> {
+    // Builds that predate `schema_version` tracked this step with a dedicated marker.
     let already: bool = conn
         .query_row(
             "SELECT value FROM schema_meta WHERE key='daily_counters_normalized_v2'",
@@ -400,12 +653,8 @@ fn migrate_daily_counters_v2(conn: &mut Connection) -> Result<(), String> {
         return Ok(());
     }
 
-    let tx = conn
-        .transaction()
-        .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
-
     {
-        let mut stmt = tx
+        let mut stmt = conn
             .prepare("SELECT date_key, payload_json FROM daily_stats")
             .map_err(|e| format!("Failed to prepare daily_stats scan: {}", e))?;
         let rows = stmt
@@ -420,47 +669,260 @@ fn migrate_daily_counters_v2(conn: &mut Connection) -> Result<(), String> {
                 Err(_) => continue,
             };
 
-            replace_daily_key_counts(&tx, &date_key, 0, &day.key_counts)?;
-            let unshifted = if !day.key_counts_unshifted.is_empty() {
-                &day.key_counts_unshifted
-            } else {
-                &day.key_counts
-            };
-            replace_daily_key_counts(&tx, &date_key, 1, unshifted)?;
-            replace_daily_key_counts(&tx, &date_key, 2, &day.key_counts_shifted)?;
-            replace_daily_shortcut_counts(&tx, &date_key, &day.shortcut_counts)?;
-            replace_daily_mouse_button_counts(&tx, &date_key, &day.mouse_button_counts)?;
-            replace_daily_hourly(&tx, &date_key, &day.hourly)?;
-            replace_daily_app_input(&tx, &date_key, &day.app_input_counts)?;
+            v2_write_day_counters(conn, &date_key, &day)?;
 
             let stripped_json = serde_json::to_string(&strip_heavy_fields_for_storage(day))
                 .map_err(|e| format!("Failed to serialize stripped daily stats: {}", e))?;
-            let _ = tx.execute(
+            let _ = conn.execute(
                 "UPDATE daily_stats SET payload_json=?2, updated_at_ms=?3 WHERE date_key=?1",
                 params![date_key, stripped_json, now_ms()],
             );
         }
     }
 
-    let _ = tx.execute(
+    // Keep the legacy marker so older builds opening this file don't normalize twice.
+    let _ = conn.execute(
         "INSERT INTO schema_meta(key, value) VALUES('daily_counters_normalized_v2', '1') ON CONFLICT(key) DO UPDATE SET value='1'",
         [],
     );
-    tx.commit()
-        .map_err(|e| format!("Failed to commit daily counters migration: {}", e))?;
     Ok(())
 }
 
-fn apply_heatmap_batch(
-    conn: &mut Connection,
+fn migrate_v3_activity_timeline(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS activity_timeline (
+  date_key TEXT NOT NULL,
+  minute INTEGER NOT NULL,
+  keyboard INTEGER NOT NULL DEFAULT 0,
+  mouse_single INTEGER NOT NULL DEFAULT 0,
+  distance_px INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY(date_key, minute)
+);
+"#,
+    )
+    .map_err(|e| format!("Failed to create activity_timeline: {}", e))
+}
+
+fn migrate_v4_sessions(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS sessions (
+  start_ms INTEGER PRIMARY KEY,
+  end_ms INTEGER NOT NULL,
+  date_key TEXT NOT NULL,
+  keyboard INTEGER NOT NULL DEFAULT 0,
+  mouse_single INTEGER NOT NULL DEFAULT 0,
+  app_id TEXT,
+  app_name TEXT
+);
+CREATE INDEX IF NOT EXISTS idx_sessions_date ON sessions(date_key);
+"#,
+    )
+    .map_err(|e| format!("Failed to create sessions: {}", e))
+}
+
+fn migrate_v5_typing_speed(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS daily_typing_speed (
+  date_key TEXT NOT NULL,
+  hour INTEGER NOT NULL,
+  keys INTEGER NOT NULL DEFAULT 0,
+  active_ms INTEGER NOT NULL DEFAULT 0,
+  peak_kpm REAL NOT NULL DEFAULT 0,
+  PRIMARY KEY(date_key, hour)
+);
+"#,
+    )
+    .map_err(|e| format!("Failed to create daily_typing_speed: {}", e))
+}
+
+fn migrate_v6_corrections(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS daily_corrections (
+  date_key TEXT NOT NULL,
+  hour INTEGER NOT NULL,
+  typed_keys INTEGER NOT NULL DEFAULT 0,
+  correction_keys INTEGER NOT NULL DEFAULT 0,
+  bursts INTEGER NOT NULL DEFAULT 0,
+  burst_deletes INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY(date_key, hour)
+);
+"#,
+    )
+    .map_err(|e| format!("Failed to create daily_corrections: {}", e))
+}
+
+fn migrate_v7_key_bigrams(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS daily_key_bigrams (
+  date_key TEXT NOT NULL,
+  first_code TEXT NOT NULL,
+  second_code TEXT NOT NULL,
+  count INTEGER NOT NULL DEFAULT 0,
+  PRIMARY KEY(date_key, first_code, second_code)
+);
+"#,
+    )
+    .map_err(|e| format!("Failed to create daily_key_bigrams: {}", e))
+}
+
+fn migrate_v8_scroll(conn: &Connection) -> Result<(), String> {
+    // Day-level scroll counters live in `daily_stats.payload_json`.
+    for (table, column) in [
+        ("daily_hourly", "scroll"),
+        ("daily_app_input", "scroll"),
+        ("daily_app_input", "scroll_ticks"),
+    ] {
+        let exists: bool = conn
+            .query_row(
+                "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name=?2",
+                params![table, column],
+                |row| row.get::<_, i64>(0),
+            )
+            .map_err(|e| format!("Failed to inspect {}: {}", table, e))?
+            > 0;
+        if exists {
+            continue;
+        }
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} INTEGER NOT NULL DEFAULT 0", table, column),
+            [],
+        )
+        .map_err(|e| format!("Failed to add {}.{}: {}", table, column, e))?;
+    }
+    Ok(())
+}
+
+fn migrate_v9_move_heatmap(conn: &Connection) -> Result<(), String> {
+    // Same layout as the click heatmap tables; `total_clicks` counts position samples here.
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS move_heatmap_total_cells (
+  display_id TEXT NOT NULL,
+  idx INTEGER NOT NULL,
+  count INTEGER NOT NULL,
+  PRIMARY KEY(display_id, idx)
+);
+
+CREATE TABLE IF NOT EXISTS move_heatmap_daily_cells (
+  date_key TEXT NOT NULL,
+  display_id TEXT NOT NULL,
+  idx INTEGER NOT NULL,
+  count INTEGER NOT NULL,
+  PRIMARY KEY(date_key, display_id, idx)
+);
+
+CREATE TABLE IF NOT EXISTS move_heatmap_total_meta (
+  display_id TEXT PRIMARY KEY,
+  total_clicks INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS move_heatmap_daily_meta (
+  date_key TEXT NOT NULL,
+  display_id TEXT NOT NULL,
+  total_clicks INTEGER NOT NULL,
+  PRIMARY KEY(date_key, display_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_move_heatmap_daily_date ON move_heatmap_daily_meta(date_key);
+"#,
+    )
+    .map_err(|e| format!("Failed to create move heatmap tables: {}", e))
+}
+
+fn migrate_v10_import_log(conn: &Connection) -> Result<(), String> {
+    // Machine-local, so it is neither exported nor merged by imports.
+    conn.execute_batch(
+        r#"
+CREATE TABLE IF NOT EXISTS import_log (
+  source_id TEXT PRIMARY KEY,
+  exported_at_ms INTEGER,
+  imported_at_ms INTEGER NOT NULL
+);
+"#,
+    )
+    .map_err(|e| format!("Failed to create import_log table: {}", e))
+}
+
+/// Tables backing one heatmap kind.
+struct HeatmapTables {
+    total_cells: &'static str,
+    daily_cells: &'static str,
+    total_meta: &'static str,
+    daily_meta: &'static str,
+}
+
+fn heatmap_tables(kind: HeatmapKind) -> HeatmapTables {
+    match kind {
+        HeatmapKind::Click => HeatmapTables {
+            total_cells: "click_heatmap_total_cells",
+            daily_cells: "click_heatmap_daily_cells",
+            total_meta: "click_heatmap_total_meta",
+            daily_meta: "click_heatmap_daily_meta",
+        },
+        HeatmapKind::Move => HeatmapTables {
+            total_cells: "move_heatmap_total_cells",
+            daily_cells: "move_heatmap_daily_cells",
+            total_meta: "move_heatmap_total_meta",
+            daily_meta: "move_heatmap_daily_meta",
+        },
+    }
+}
+
+/// Heatmap deltas waiting for the next batch, for one kind.
+#[derive(Debug, Default)]
+struct PendingHeatmap {
     total_cells: HashMap<(Arc<str>, u32), u32>,
     daily_cells: HashMap<(Arc<str>, Arc<str>, u32), u32>,
     total_clicks: HashMap<Arc<str>, u64>,
     daily_clicks: HashMap<(Arc<str>, Arc<str>), u64>,
+}
+
+impl PendingHeatmap {
+    fn add(&mut self, date_key: Arc<str>, display_id: Arc<str>, idx: u32, delta: u32) {
+        self.total_cells
+            .entry((Arc::clone(&display_id), idx))
+            .and_modify(|v| *v = v.saturating_add(delta))
+            .or_insert(delta);
+        self.daily_cells
+            .entry((Arc::clone(&date_key), Arc::clone(&display_id), idx))
+            .and_modify(|v| *v = v.saturating_add(delta))
+            .or_insert(delta);
+
+        self.total_clicks
+            .entry(Arc::clone(&display_id))
+            .and_modify(|v| *v = v.saturating_add(delta as u64))
+            .or_insert(delta as u64);
+        self.daily_clicks
+            .entry((date_key, display_id))
+            .and_modify(|v| *v = v.saturating_add(delta as u64))
+            .or_insert(delta as u64);
+    }
+
+    fn cell_count(&self) -> usize {
+        self.total_cells.len() + self.daily_cells.len()
+    }
+}
+
+fn apply_heatmap_batch(
+    conn: &mut Connection,
+    kind: HeatmapKind,
+    pending: PendingHeatmap,
 ) -> Result<(), String> {
+    let PendingHeatmap {
+        total_cells,
+        daily_cells,
+        total_clicks,
+        daily_clicks,
+    } = pending;
     if total_cells.is_empty() && daily_cells.is_empty() && total_clicks.is_empty() && daily_clicks.is_empty() {
         return Ok(());
     }
+    let tables = heatmap_tables(kind);
 
     let tx = conn
         .transaction()
@@ -468,77 +930,81 @@ fn apply_heatmap_batch(
 
     {
         let mut stmt = tx
-            .prepare(
+            .prepare(&format!(
                 r#"
-INSERT INTO click_heatmap_total_cells(display_id, idx, count)
+INSERT INTO {}(display_id, idx, count)
 VALUES (?1, ?2, ?3)
 ON CONFLICT(display_id, idx) DO UPDATE SET count = count + excluded.count
 "#,
-            )
-            .map_err(|e| format!("Failed to prepare click_heatmap_total_cells upsert: {}", e))?;
+                tables.total_cells
+            ))
+            .map_err(|e| format!("Failed to prepare {} upsert: {}", tables.total_cells, e))?;
         for ((display_id, idx), delta) in total_cells {
             if delta == 0 {
                 continue;
             }
             stmt.execute(params![display_id.as_ref(), idx as i64, delta as i64])
-                .map_err(|e| format!("Failed to upsert click_heatmap_total_cells: {}", e))?;
+                .map_err(|e| format!("Failed to upsert {}: {}", tables.total_cells, e))?;
         }
     }
 
     {
         let mut stmt = tx
-            .prepare(
+            .prepare(&format!(
                 r#"
-INSERT INTO click_heatmap_daily_cells(date_key, display_id, idx, count)
+INSERT INTO {}(date_key, display_id, idx, count)
 VALUES (?1, ?2, ?3, ?4)
 ON CONFLICT(date_key, display_id, idx) DO UPDATE SET count = count + excluded.count
 "#,
-            )
-            .map_err(|e| format!("Failed to prepare click_heatmap_daily_cells upsert: {}", e))?;
+                tables.daily_cells
+            ))
+            .map_err(|e| format!("Failed to prepare {} upsert: {}", tables.daily_cells, e))?;
         for ((date_key, display_id, idx), delta) in daily_cells {
             if delta == 0 {
                 continue;
             }
             stmt.execute(params![date_key.as_ref(), display_id.as_ref(), idx as i64, delta as i64])
-                .map_err(|e| format!("Failed to upsert click_heatmap_daily_cells: {}", e))?;
+                .map_err(|e| format!("Failed to upsert {}: {}", tables.daily_cells, e))?;
         }
     }
 
     {
         let mut stmt = tx
-            .prepare(
+            .prepare(&format!(
                 r#"
-INSERT INTO click_heatmap_total_meta(display_id, total_clicks)
+INSERT INTO {}(display_id, total_clicks)
 VALUES (?1, ?2)
 ON CONFLICT(display_id) DO UPDATE SET total_clicks = total_clicks + excluded.total_clicks
 "#,
-            )
-            .map_err(|e| format!("Failed to prepare click_heatmap_total_meta upsert: {}", e))?;
+                tables.total_meta
+            ))
+            .map_err(|e| format!("Failed to prepare {} upsert: {}", tables.total_meta, e))?;
         for (display_id, delta) in total_clicks {
             if delta == 0 {
                 continue;
             }
             stmt.execute(params![display_id.as_ref(), delta as i64])
-                .map_err(|e| format!("Failed to upsert click_heatmap_total_meta: {}", e))?;
+                .map_err(|e| format!("Failed to upsert {}: {}", tables.total_meta, e))?;
         }
     }
 
     {
         let mut stmt = tx
-            .prepare(
+            .prepare(&format!(
                 r#"
-INSERT INTO click_heatmap_daily_meta(date_key, display_id, total_clicks)
+INSERT INTO {}(date_key, display_id, total_clicks)
 VALUES (?1, ?2, ?3)
 ON CONFLICT(date_key, display_id) DO UPDATE SET total_clicks = total_clicks + excluded.total_clicks
 "#,
-            )
-            .map_err(|e| format!("Failed to prepare click_heatmap_daily_meta upsert: {}", e))?;
+                tables.daily_meta
+            ))
+            .map_err(|e| format!("Failed to prepare {} upsert: {}", tables.daily_meta, e))?;
         for ((date_key, display_id), delta) in daily_clicks {
             if delta == 0 {
                 continue;
             }
             stmt.execute(params![date_key.as_ref(), display_id.as_ref(), delta as i64])
-                .map_err(|e| format!("Failed to upsert click_heatmap_daily_meta: {}", e))?;
+                .map_err(|e| format!("Failed to upsert {}: {}", tables.daily_meta, e))?;
         }
     }
 
@@ -646,55 +1112,882 @@ ON CONFLICT(date_key, display_id) DO UPDATE SET total_clicks = excluded.total_cl
     Ok(())
 }
 
-pub fn init(path: PathBuf) -> Result<(), String> {
-    if let Some(parent) = path.parent() {
-        std::fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create db dir {}: {}", parent.display(), e))?;
-    }
-
-    let (tx, rx) = mpsc::channel::<DbOp>();
-    let write_path = path.clone();
-
-    // Create schema eagerly to fail fast if the filesystem/db is broken.
-    {
-        let conn = open_write_conn(&write_path)?;
-        migrate(&conn)?;
-    }
-
-    std::thread::spawn(move || {
-        let mut conn = match open_write_conn(&write_path) {
-            Ok(c) => c,
-            Err(e) => {
-                eprintln!("{}", e);
-                return;
-            }
-        };
-
-        if let Err(e) = migrate(&conn) {
-            eprintln!("{}", e);
-            return;
-        }
-
-        if let Err(e) = migrate_daily_counters_v2(&mut conn) {
-            eprintln!("{}", e);
-        }
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportTableDelta {
+    pub table: String,
+    pub source_rows: u64,
+    /// Rows whose key doesn't exist locally yet.
+    pub new_rows: u64,
+    /// Rows whose counters are added onto an existing local row.
+    pub merged_rows: u64,
+    /// Sum of the table's counter column(s) in the import.
+    pub count_delta: u64,
+}
 
-        let mut pending_total_cells: HashMap<(Arc<str>, u32), u32> = HashMap::new();
-        let mut pending_daily_cells: HashMap<(Arc<str>, Arc<str>, u32), u32> = HashMap::new();
-        let mut pending_total_clicks: HashMap<Arc<str>, u64> = HashMap::new();
-        let mut pending_daily_clicks: HashMap<(Arc<str>, Arc<str>), u64> = HashMap::new();
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ImportMergeReport {
+    pub conflicting_days: Vec<String>,
+    pub tables: Vec<ImportTableDelta>,
+    /// Sum of imported `daily_stats.total`, including the live day that is merged in memory.
+    pub imported_total: u64,
+    /// Set by a dry run when the same source was merged before; a real import is refused then.
+    pub already_imported_at_ms: Option<i64>,
+}
 
-        let mut last_flush_ms = now_ms();
+/// Identifies an import source so merging it a second time can be refused.
+#[derive(Debug, Clone)]
+pub struct ImportSource {
+    /// Content fingerprint of the imported file(s).
+    pub id: String,
+    /// `exported_at_ms` from the archive manifest, when there is one.
+    pub exported_at_ms: Option<i64>,
+}
 
-        loop {
-            let op = match rx.recv_timeout(Duration::from_millis(120)) {
-                Ok(op) => Some(op),
-                Err(mpsc::RecvTimeoutError::Timeout) => None,
-                Err(mpsc::RecvTimeoutError::Disconnected) => break,
-            };
+struct MergeSpec {
+    table: &'static str,
+    key_columns: &'static [&'static str],
+    sum_columns: &'static [&'static str],
+    extra_columns: &'static [&'static str],
+    extra_updates: &'static str,
+    delta_expr: &'static str,
+    /// Rows for the live day are rewritten wholesale by `upsert_daily`, so imports must leave them
+    /// to the in-memory merge.
+    skip_live_day: bool,
+}
 
-            if let Some(op) = op {
-                match op {
+const MERGE_SPECS: &[MergeSpec] = &[
+    MergeSpec {
+        table: "daily_key_counts",
+        key_columns: &["date_key", "kind", "code"],
+        sum_columns: &["count"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.count",
+        skip_live_day: true,
+    },
+    MergeSpec {
+        table: "daily_shortcut_counts",
+        key_columns: &["date_key", "shortcut"],
+        sum_columns: &["count"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.count",
+        skip_live_day: true,
+    },
+    MergeSpec {
+        table: "daily_mouse_button_counts",
+        key_columns: &["date_key", "button"],
+        sum_columns: &["count"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.count",
+        skip_live_day: true,
+    },
+    MergeSpec {
+        table: "daily_hourly",
+        key_columns: &["date_key", "hour"],
+        sum_columns: &["total", "keyboard", "mouse_single", "scroll"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.total",
+        skip_live_day: true,
+    },
+    MergeSpec {
+        table: "daily_app_input",
+        key_columns: &["date_key", "app_id"],
+        sum_columns: &["keyboard", "mouse_single", "scroll", "scroll_ticks"],
+        extra_columns: &["name"],
+        extra_updates: ", name=COALESCE(name, excluded.name)",
+        delta_expr: "s.keyboard + s.mouse_single + s.scroll",
+        skip_live_day: true,
+    },
+    MergeSpec {
+        table: "app_meta",
+        key_columns: &["app_id"],
+        sum_columns: &[],
+        extra_columns: &["last_name", "updated_at_ms"],
+        extra_updates: "last_name=excluded.last_name, updated_at_ms=excluded.updated_at_ms WHERE excluded.updated_at_ms > app_meta.updated_at_ms",
+        delta_expr: "0",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "click_heatmap_total_cells",
+        key_columns: &["display_id", "idx"],
+        sum_columns: &["count"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.count",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "click_heatmap_total_meta",
+        key_columns: &["display_id"],
+        sum_columns: &["total_clicks"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.total_clicks",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "click_heatmap_daily_cells",
+        key_columns: &["date_key", "display_id", "idx"],
+        sum_columns: &["count"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.count",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "click_heatmap_daily_meta",
+        key_columns: &["date_key", "display_id"],
+        sum_columns: &["total_clicks"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.total_clicks",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "move_heatmap_total_cells",
+        key_columns: &["display_id", "idx"],
+        sum_columns: &["count"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.count",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "move_heatmap_total_meta",
+        key_columns: &["display_id"],
+        sum_columns: &["total_clicks"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.total_clicks",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "move_heatmap_daily_cells",
+        key_columns: &["date_key", "display_id", "idx"],
+        sum_columns: &["count"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.count",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "move_heatmap_daily_meta",
+        key_columns: &["date_key", "display_id"],
+        sum_columns: &["total_clicks"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.total_clicks",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "activity_timeline",
+        key_columns: &["date_key", "minute"],
+        sum_columns: &["keyboard", "mouse_single", "distance_px"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.keyboard + s.mouse_single",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "daily_typing_speed",
+        key_columns: &["date_key", "hour"],
+        sum_columns: &["keys", "active_ms"],
+        extra_columns: &["peak_kpm"],
+        extra_updates: ", peak_kpm=MAX(peak_kpm, excluded.peak_kpm)",
+        delta_expr: "s.keys",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "daily_corrections",
+        key_columns: &["date_key", "hour"],
+        sum_columns: &["typed_keys", "correction_keys", "bursts", "burst_deletes"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.typed_keys + s.correction_keys",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "daily_key_bigrams",
+        key_columns: &["date_key", "first_code", "second_code"],
+        sum_columns: &["count"],
+        extra_columns: &[],
+        extra_updates: "",
+        delta_expr: "s.count",
+        skip_live_day: false,
+    },
+    MergeSpec {
+        table: "sessions",
+        key_columns: &["start_ms"],
+        sum_columns: &[],
+        extra_columns: &["end_ms", "date_key", "keyboard", "mouse_single", "app_id", "app_name"],
+        extra_updates: "",
+        delta_expr: "s.keyboard + s.mouse_single",
+        skip_live_day: false,
+    },
+];
+
+impl MergeSpec {
+    fn filter(&self) -> &'static str {
+        if self.skip_live_day {
+            "WHERE (?1 IS NULL OR s.date_key <> ?1)"
+        } else {
+            "WHERE 1"
+        }
+    }
+
+    fn bind<'a>(&self, live_date: Option<&'a str>) -> ParamsFromIter<Option<Option<&'a str>>> {
+        params_from_iter(self.skip_live_day.then_some(live_date))
+    }
+
+    fn delta(&self, conn: &Connection, live_date: Option<&str>) -> Result<ImportTableDelta, String> {
+        let (source_rows, count_delta): (i64, i64) = conn
+            .query_row(
+                &format!(
+                    "SELECT COUNT(*), COALESCE(SUM({}), 0) FROM import.{} s {}",
+                    self.delta_expr,
+                    self.table,
+                    self.filter()
+                ),
+                self.bind(live_date),
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .map_err(|e| format!("Failed to inspect import.{}: {}", self.table, e))?;
+
+        let join = self
+            .key_columns
+            .iter()
+            .map(|c| format!("m.{c}=s.{c}"))
+            .collect::<Vec<_>>()
+            .join(" AND ");
+        let merged_rows: i64 = conn
+            .query_row(
+                &format!(
+                    "SELECT COUNT(*) FROM import.{t} s JOIN main.{t} m ON {join} {filter}",
+                    t = self.table,
+                    filter = self.filter()
+                ),
+                self.bind(live_date),
+                |row| row.get(0),
+            )
+            .map_err(|e| format!("Failed to compare import.{}: {}", self.table, e))?;
+
+        let source_rows = u64::try_from(source_rows).unwrap_or(0);
+        let merged_rows = u64::try_from(merged_rows).unwrap_or(0);
+        Ok(ImportTableDelta {
+            table: self.table.to_string(),
+            source_rows,
+            new_rows: source_rows.saturating_sub(merged_rows),
+            merged_rows,
+            count_delta: u64::try_from(count_delta).unwrap_or(0),
+        })
+    }
+
+    fn apply(&self, conn: &Connection, live_date: Option<&str>) -> Result<(), String> {
+        let columns = self
+            .key_columns
+            .iter()
+            .chain(self.sum_columns)
+            .chain(self.extra_columns)
+            .copied()
+            .collect::<Vec<_>>()
+            .join(", ");
+        let updates = self
+            .sum_columns
+            .iter()
+            .map(|c| format!("{c}={c}+excluded.{c}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        // Rows without counters (e.g. sessions) keep the local copy on conflict.
+        let on_conflict = if updates.is_empty() && self.extra_updates.is_empty() {
+            "DO NOTHING".to_string()
+        } else {
+            format!("DO UPDATE SET {}{}", updates, self.extra_updates)
+        };
+        let sql = format!(
+            "INSERT INTO main.{t}({columns}) SELECT {columns} FROM import.{t} s {filter} ON CONFLICT({keys}) {on_conflict}",
+            t = self.table,
+            filter = self.filter(),
+            keys = self.key_columns.join(", "),
+        );
+        conn.execute(&sql, self.bind(live_date))
+            .map_err(|e| format!("Failed to merge import.{}: {}", self.table, e))?;
+        Ok(())
+    }
+}
+
+fn merge_import_daily_stats(
+    conn: &Connection,
+    live_date: Option<&str>,
+    dry_run: bool,
+    report: &mut ImportMergeReport,
+) -> Result<(), String> {
+    // Collected up front: rows of main.daily_stats are rewritten below.
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT s.date_key, s.total, s.payload_json, m.payload_json
+FROM import.daily_stats s
+LEFT JOIN main.daily_stats m ON m.date_key = s.date_key
+ORDER BY s.date_key
+"#,
+        )
+        .map_err(|e| format!("Failed to prepare import.daily_stats scan: {}", e))?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to scan import.daily_stats: {}", e))?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read import.daily_stats row: {}", e))?;
+    drop(stmt);
+
+    let mut delta = ImportTableDelta {
+        table: "daily_stats".to_string(),
+        source_rows: 0,
+        new_rows: 0,
+        merged_rows: 0,
+        count_delta: 0,
+    };
+    for row in rows {
+        let (date_key, total, theirs_json, ours_json) = row;
+        let total = u64::try_from(total).unwrap_or(0);
+        report.imported_total = report.imported_total.saturating_add(total);
+        if live_date == Some(date_key.as_str()) {
+            continue;
+        }
+
+        delta.source_rows += 1;
+        delta.count_delta = delta.count_delta.saturating_add(total);
+        if ours_json.is_some() {
+            delta.merged_rows += 1;
+            report.conflicting_days.push(date_key.clone());
+        } else {
+            delta.new_rows += 1;
+        }
+        if dry_run {
+            continue;
+        }
+
+        let theirs = serde_json::from_str::<DailyStats>(&theirs_json)
+            .map_err(|e| format!("Failed to parse imported daily_stats {}: {}", date_key, e))?;
+        let merged = match ours_json {
+            Some(json) => {
+                let mut ours = serde_json::from_str::<DailyStats>(&json)
+                    .map_err(|e| format!("Failed to parse daily_stats {}: {}", date_key, e))?;
+                ours.merge_from(&theirs);
+                ours
+            }
+            None => theirs,
+        };
+        write_daily_stats_row(conn, &merged)?;
+    }
+    report.tables.push(delta);
+    Ok(())
+}
+
+fn merge_import(
+    conn: &mut Connection,
+    source: &Path,
+    live_date: Option<&str>,
+    import_source: Option<&ImportSource>,
+    dry_run: bool,
+) -> Result<ImportMergeReport, String> {
+    conn.execute(
+        "ATTACH DATABASE ?1 AS import",
+        params![source.to_string_lossy().as_ref()],
+    )
+    .map_err(|e| format!("Failed to attach import database: {}", e))?;
+
+    let res = (|| -> Result<ImportMergeReport, String> {
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
+        let mut report = ImportMergeReport::default();
+        if let Some(import_source) = import_source {
+            report.already_imported_at_ms = tx
+                .query_row(
+                    "SELECT imported_at_ms FROM import_log WHERE source_id=?1",
+                    params![import_source.id],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()
+                .map_err(|e| format!("Failed to read import_log: {}", e))?;
+            if let (Some(at), false) = (report.already_imported_at_ms, dry_run) {
+                let when = chrono::DateTime::from_timestamp_millis(at)
+                    .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string())
+                    .unwrap_or_else(|| at.to_string());
+                return Err(format!(
+                    "This data was already imported on {}; importing it again would double its counts",
+                    when
+                ));
+            }
+        }
+        merge_import_daily_stats(&tx, live_date, dry_run, &mut report)?;
+        for spec in MERGE_SPECS {
+            report.tables.push(spec.delta(&tx, live_date)?);
+            if !dry_run {
+                spec.apply(&tx, live_date)?;
+            }
+        }
+        if let (Some(import_source), false) = (import_source, dry_run) {
+            tx.execute(
+                "INSERT INTO import_log(source_id, exported_at_ms, imported_at_ms) VALUES (?1, ?2, ?3)",
+                params![
+                    import_source.id,
+                    import_source.exported_at_ms,
+                    chrono::Utc::now().timestamp_millis()
+                ],
+            )
+            .map_err(|e| format!("Failed to record import source: {}", e))?;
+        }
+        if dry_run {
+            tx.rollback()
+                .map_err(|e| format!("Failed to roll back dry-run import: {}", e))?;
+        } else {
+            tx.commit()
+                .map_err(|e| format!("Failed to commit import merge: {}", e))?;
+        }
+        Ok(report)
+    })();
+
+    let _ = conn.execute_batch("DETACH DATABASE import");
+    res
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimelineDelta {
+    pub keyboard: u64,
+    pub mouse_single: u64,
+    pub distance_px: u64,
+}
+
+impl TimelineDelta {
+    fn add(&mut self, other: &TimelineDelta) {
+        self.keyboard = self.keyboard.saturating_add(other.keyboard);
+        self.mouse_single = self.mouse_single.saturating_add(other.mouse_single);
+        self.distance_px = self.distance_px.saturating_add(other.distance_px);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.keyboard == 0 && self.mouse_single == 0 && self.distance_px == 0
+    }
+}
+
+fn apply_timeline_batch(
+    conn: &mut Connection,
+    pending: HashMap<(Arc<str>, u32), TimelineDelta>,
+) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
+    {
+        let mut stmt = tx
+            .prepare(
+                r#"
+INSERT INTO activity_timeline(date_key, minute, keyboard, mouse_single, distance_px)
+VALUES (?1, ?2, ?3, ?4, ?5)
+ON CONFLICT(date_key, minute) DO UPDATE SET
+  keyboard=keyboard+excluded.keyboard,
+  mouse_single=mouse_single+excluded.mouse_single,
+  distance_px=distance_px+excluded.distance_px
+"#,
+            )
+            .map_err(|e| format!("Failed to prepare activity_timeline upsert: {}", e))?;
+        for ((date_key, minute), d) in pending {
+            stmt.execute(params![
+                date_key.as_ref(),
+                minute,
+                i64::try_from(d.keyboard).unwrap_or(i64::MAX),
+                i64::try_from(d.mouse_single).unwrap_or(i64::MAX),
+                i64::try_from(d.distance_px).unwrap_or(i64::MAX),
+            ])
+            .map_err(|e| format!("Failed to upsert activity_timeline: {}", e))?;
+        }
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit activity_timeline batch: {}", e))
+}
+
+/// Typing keystrokes and the time spent typing them (idle gaps excluded) within one hour.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TypingSpeedDelta {
+    pub keys: u64,
+    pub active_ms: u64,
+    /// Highest rolling keys-per-minute seen; merged with `max`, not summed.
+    pub peak_kpm: f64,
+}
+
+impl TypingSpeedDelta {
+    fn add(&mut self, other: &TypingSpeedDelta) {
+        self.keys = self.keys.saturating_add(other.keys);
+        self.active_ms = self.active_ms.saturating_add(other.active_ms);
+        self.peak_kpm = self.peak_kpm.max(other.peak_kpm);
+    }
+}
+
+fn apply_typing_speed_batch(
+    conn: &mut Connection,
+    pending: HashMap<(Arc<str>, u32), TypingSpeedDelta>,
+) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
+    {
+        let mut stmt = tx
+            .prepare(
+                r#"
+INSERT INTO daily_typing_speed(date_key, hour, keys, active_ms, peak_kpm)
+VALUES (?1, ?2, ?3, ?4, ?5)
+ON CONFLICT(date_key, hour) DO UPDATE SET
+  keys=keys+excluded.keys,
+  active_ms=active_ms+excluded.active_ms,
+  peak_kpm=MAX(peak_kpm, excluded.peak_kpm)
+"#,
+            )
+            .map_err(|e| format!("Failed to prepare daily_typing_speed upsert: {}", e))?;
+        for ((date_key, hour), d) in pending {
+            stmt.execute(params![
+                date_key.as_ref(),
+                hour,
+                i64::try_from(d.keys).unwrap_or(i64::MAX),
+                i64::try_from(d.active_ms).unwrap_or(i64::MAX),
+                d.peak_kpm,
+            ])
+            .map_err(|e| format!("Failed to upsert daily_typing_speed: {}", e))?;
+        }
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit daily_typing_speed batch: {}", e))
+}
+
+/// Typed and correction keystrokes within one hour, plus bursts of typing and how many of them
+/// were deleted right away.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CorrectionDelta {
+    pub typed_keys: u64,
+    pub correction_keys: u64,
+    pub bursts: u64,
+    pub burst_deletes: u64,
+}
+
+impl CorrectionDelta {
+    fn add(&mut self, other: &CorrectionDelta) {
+        self.typed_keys = self.typed_keys.saturating_add(other.typed_keys);
+        self.correction_keys = self.correction_keys.saturating_add(other.correction_keys);
+        self.bursts = self.bursts.saturating_add(other.bursts);
+        self.burst_deletes = self.burst_deletes.saturating_add(other.burst_deletes);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.typed_keys == 0
+            && self.correction_keys == 0
+            && self.bursts == 0
+            && self.burst_deletes == 0
+    }
+}
+
+fn apply_corrections_batch(
+    conn: &mut Connection,
+    pending: HashMap<(Arc<str>, u32), CorrectionDelta>,
+) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
+    {
+        let mut stmt = tx
+            .prepare(
+                r#"
+INSERT INTO daily_corrections(date_key, hour, typed_keys, correction_keys, bursts, burst_deletes)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+ON CONFLICT(date_key, hour) DO UPDATE SET
+  typed_keys=typed_keys+excluded.typed_keys,
+  correction_keys=correction_keys+excluded.correction_keys,
+  bursts=bursts+excluded.bursts,
+  burst_deletes=burst_deletes+excluded.burst_deletes
+"#,
+            )
+            .map_err(|e| format!("Failed to prepare daily_corrections upsert: {}", e))?;
+        for ((date_key, hour), d) in pending {
+            stmt.execute(params![
+                date_key.as_ref(),
+                hour,
+                i64::try_from(d.typed_keys).unwrap_or(i64::MAX),
+                i64::try_from(d.correction_keys).unwrap_or(i64::MAX),
+                i64::try_from(d.bursts).unwrap_or(i64::MAX),
+                i64::try_from(d.burst_deletes).unwrap_or(i64::MAX),
+            ])
+            .map_err(|e| format!("Failed to upsert daily_corrections: {}", e))?;
+        }
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit daily_corrections batch: {}", e))
+}
+
+/// Pending bigram counts keyed by `(date_key, first_code, second_code)`.
+type BigramCounts = HashMap<(Arc<str>, Arc<str>, Arc<str>), u64>;
+
+fn apply_key_bigram_batch(
+    conn: &mut Connection,
+    pending: BigramCounts,
+) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
+    {
+        let mut stmt = tx
+            .prepare(
+                r#"
+INSERT INTO daily_key_bigrams(date_key, first_code, second_code, count)
+VALUES (?1, ?2, ?3, ?4)
+ON CONFLICT(date_key, first_code, second_code) DO UPDATE SET count=count+excluded.count
+"#,
+            )
+            .map_err(|e| format!("Failed to prepare daily_key_bigrams upsert: {}", e))?;
+        for ((date_key, first, second), count) in pending {
+            stmt.execute(params![
+                date_key.as_ref(),
+                first.as_ref(),
+                second.as_ref(),
+                i64::try_from(count).unwrap_or(i64::MAX),
+            ])
+            .map_err(|e| format!("Failed to upsert daily_key_bigrams: {}", e))?;
+        }
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit daily_key_bigrams batch: {}", e))
+}
+
+fn insert_session(conn: &Connection, record: &SessionRecord) -> Result<(), String> {
+    conn.execute(
+        r#"
+INSERT INTO sessions(start_ms, end_ms, date_key, keyboard, mouse_single, app_id, app_name)
+VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+ON CONFLICT(start_ms) DO UPDATE SET
+  end_ms=excluded.end_ms,
+  keyboard=excluded.keyboard,
+  mouse_single=excluded.mouse_single,
+  app_id=excluded.app_id,
+  app_name=excluded.app_name
+"#,
+        params![
+            i64::try_from(record.start_ms).unwrap_or(i64::MAX),
+            i64::try_from(record.end_ms).unwrap_or(i64::MAX),
+            record.date_key,
+            i64::try_from(record.keyboard).unwrap_or(i64::MAX),
+            i64::try_from(record.mouse_single).unwrap_or(i64::MAX),
+            record.app_id,
+            record.app_name,
+        ],
+    )
+    .map_err(|e| format!("Failed to insert session: {}", e))?;
+    Ok(())
+}
+
+fn map_session_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<SessionRecord> {
+    Ok(SessionRecord {
+        start_ms: u64::try_from(row.get::<_, i64>(0)?).unwrap_or(0),
+        end_ms: u64::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+        date_key: row.get(2)?,
+        keyboard: u64::try_from(row.get::<_, i64>(3)?).unwrap_or(0),
+        mouse_single: u64::try_from(row.get::<_, i64>(4)?).unwrap_or(0),
+        app_id: row.get(5)?,
+        app_name: row.get(6)?,
+        ongoing: false,
+    })
+}
+
+fn load_sessions_from(conn: &Connection, date_key: &str) -> Result<Vec<SessionRecord>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT start_ms, end_ms, date_key, keyboard, mouse_single, app_id, app_name
+FROM sessions
+WHERE date_key = ?1
+ORDER BY start_ms
+"#,
+        )
+        .map_err(|e| format!("Failed to prepare sessions query: {}", e))?;
+    let rows = stmt
+        .query_map(params![date_key], map_session_row)
+        .map_err(|e| format!("Failed to query sessions: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read session row: {}", e))
+}
+
+fn load_session_stats_from(
+    conn: &Connection,
+    start_key: Option<&str>,
+    end_key: Option<&str>,
+) -> Result<SessionStats, String> {
+    let (sessions, active_ms, average_session_ms, breaks, average_break_ms) = conn
+        .query_row(
+            r#"
+WITH s AS (
+  SELECT end_ms - start_ms AS dur,
+    start_ms - LAG(end_ms) OVER (PARTITION BY date_key ORDER BY start_ms) AS gap
+  FROM sessions
+  WHERE (?1 IS NULL OR date_key >= ?1) AND (?2 IS NULL OR date_key <= ?2)
+)
+SELECT COUNT(*), COALESCE(SUM(dur), 0), COALESCE(AVG(dur), 0), COUNT(gap), COALESCE(AVG(MAX(gap, 0)), 0)
+FROM s
+"#,
+            params![start_key, end_key],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, f64>(2)?,
+                    row.get::<_, i64>(3)?,
+                    row.get::<_, f64>(4)?,
+                ))
+            },
+        )
+        .map_err(|e| format!("Failed to query session stats: {}", e))?;
+
+    let longest = conn
+        .query_row(
+            r#"
+SELECT start_ms, end_ms, date_key, keyboard, mouse_single, app_id, app_name
+FROM sessions
+WHERE (?1 IS NULL OR date_key >= ?1) AND (?2 IS NULL OR date_key <= ?2)
+ORDER BY end_ms - start_ms DESC, start_ms
+LIMIT 1
+"#,
+            params![start_key, end_key],
+            map_session_row,
+        )
+        .optional()
+        .map_err(|e| format!("Failed to query longest session: {}", e))?;
+
+    Ok(SessionStats {
+        sessions: u64::try_from(sessions).unwrap_or(0),
+        active_ms: u64::try_from(active_ms).unwrap_or(0),
+        average_session_ms: average_session_ms.max(0.0).round() as u64,
+        longest,
+        breaks: u64::try_from(breaks).unwrap_or(0),
+        average_break_ms: average_break_ms.max(0.0).round() as u64,
+    })
+}
+
+enum PendingFileOp {
+    Backup(PathBuf),
+    Restore(PathBuf),
+}
+
+fn backup_db_to(conn: &Connection, dest: &Path) -> Result<(), String> {
+    let _ = std::fs::remove_file(dest);
+    conn.backup(rusqlite::DatabaseName::Main, dest, None)
+        .map_err(|e| format!("Failed to back up history db: {}", e))
+}
+
+fn restore_db_from(conn: &mut Connection, src: &Path) -> Result<(), String> {
+    // Refuse snapshots written by a newer app before they replace the live DB.
+    {
+        let snapshot = Connection::open_with_flags(src, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(|e| format!("Failed to open history db snapshot: {}", e))?;
+        let version = read_schema_version(&snapshot).unwrap_or(0);
+        if version > SCHEMA_VERSION {
+            return Err(format!(
+                "History db snapshot schema version {} is newer than supported version {}",
+                version, SCHEMA_VERSION
+            ));
+        }
+    }
+    conn.restore(
+        rusqlite::DatabaseName::Main,
+        src,
+        None::<fn(rusqlite::backup::Progress)>,
+    )
+    .map_err(|e| format!("Failed to restore history db: {}", e))?;
+    migrate(conn)
+}
+
+/// Opens (creating if needed) a standalone database at the current schema, e.g. for staging an
+/// import before it is merged.
+pub fn open_staging_db(path: &Path) -> Result<Connection, String> {
+    let mut conn =
+        Connection::open(path).map_err(|e| format!("Failed to open sqlite db: {}", e))?;
+    migrate(&mut conn)?;
+    Ok(conn)
+}
+
+/// Deletes all per-day history. The import log goes too: once its rows are gone, importing the
+/// same data again no longer double counts.
+fn clear_daily(conn: &mut Connection) -> Result<(), String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
+    let _ = tx.execute("DELETE FROM daily_key_counts", []);
+    let _ = tx.execute("DELETE FROM daily_shortcut_counts", []);
+    let _ = tx.execute("DELETE FROM daily_mouse_button_counts", []);
+    let _ = tx.execute("DELETE FROM daily_hourly", []);
+    let _ = tx.execute("DELETE FROM daily_app_input", []);
+    let _ = tx.execute("DELETE FROM app_meta", []);
+    let _ = tx.execute("DELETE FROM daily_stats", []);
+    let _ = tx.execute("DELETE FROM activity_timeline", []);
+    let _ = tx.execute("DELETE FROM sessions", []);
+    let _ = tx.execute("DELETE FROM daily_typing_speed", []);
+    let _ = tx.execute("DELETE FROM daily_corrections", []);
+    let _ = tx.execute("DELETE FROM daily_key_bigrams", []);
+    let _ = tx.execute("DELETE FROM import_log", []);
+    tx.commit()
+        .map_err(|e| format!("Failed to commit sqlite transaction: {}", e))
+}
+
+pub fn init(path: PathBuf) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create db dir {}: {}", parent.display(), e))?;
+    }
+
+    let (tx, rx) = mpsc::channel::<DbOp>();
+    let write_path = path.clone();
+
+    // Migrate eagerly to fail fast if the filesystem/db is broken or from a newer app version.
+    {
+        let mut conn = open_write_conn(&write_path)?;
+        migrate(&mut conn)?;
+    }
+
+    std::thread::spawn(move || {
+        let mut conn = match open_write_conn(&write_path) {
+            Ok(c) => c,
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
+
+        let mut pending_heatmaps: HashMap<HeatmapKind, PendingHeatmap> = HashMap::new();
+
+        let mut last_flush_ms = now_ms();
+        let mut pending_timeline: HashMap<(Arc<str>, u32), TimelineDelta> = HashMap::new();
+        let mut pending_typing: HashMap<(Arc<str>, u32), TypingSpeedDelta> = HashMap::new();
+        let mut pending_corrections: HashMap<(Arc<str>, u32), CorrectionDelta> = HashMap::new();
+        let mut pending_bigrams: BigramCounts = HashMap::new();
+        let mut last_timeline_flush_ms = now_ms();
+        let mut force_flush = false;
+        let mut pending_reply: Option<(PendingFileOp, Sender<Result<(), String>>)> = None;
+        let mut retention: Option<RetentionPolicy> = None;
+        let mut next_prune_ms = i64::MAX;
+
+        loop {
+            let op = match rx.recv_timeout(Duration::from_millis(120)) {
+                Ok(op) => Some(op),
+                Err(mpsc::RecvTimeoutError::Timeout) => None,
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            };
+
+            if let Some(op) = op {
+                match op {
                     DbOp::BulkUpsertDaily(days) => {
                         let tx = match conn.transaction() {
                             Ok(tx) => tx,
@@ -713,26 +2006,12 @@ pub fn init(path: PathBuf) -> Result<(), String> {
                         }
                     }
                     DbOp::ClearDaily => {
-                        let res = (|| -> Result<(), String> {
-                            let tx = conn
-                                .transaction()
-                                .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
-                            let _ = tx.execute("DELETE FROM daily_key_counts", []);
-                            let _ = tx.execute("DELETE FROM daily_shortcut_counts", []);
-                            let _ = tx.execute("DELETE FROM daily_mouse_button_counts", []);
-                            let _ = tx.execute("DELETE FROM daily_hourly", []);
-                            let _ = tx.execute("DELETE FROM daily_app_input", []);
-                            let _ = tx.execute("DELETE FROM app_meta", []);
-                            let _ = tx.execute("DELETE FROM daily_stats", []);
-                            tx.commit()
-                                .map_err(|e| format!("Failed to commit sqlite transaction: {}", e))?;
-                            Ok(())
-                        })();
-                        if let Err(e) = res {
+                        if let Err(e) = clear_daily(&mut conn) {
                             eprintln!("{}", e);
                         }
                     }
                     DbOp::HeatmapDelta {
+                        kind,
                         date_key,
                         display_id,
                         idx,
@@ -741,177 +2020,1025 @@ pub fn init(path: PathBuf) -> Result<(), String> {
                         if delta == 0 {
                             continue;
                         }
-                        pending_total_cells
-                            .entry((Arc::clone(&display_id), idx))
-                            .and_modify(|v| *v = v.saturating_add(delta))
-                            .or_insert(delta);
-                        pending_daily_cells
-                            .entry((Arc::clone(&date_key), Arc::clone(&display_id), idx))
-                            .and_modify(|v| *v = v.saturating_add(delta))
-                            .or_insert(delta);
-
-                        pending_total_clicks
-                            .entry(Arc::clone(&display_id))
-                            .and_modify(|v| *v = v.saturating_add(delta as u64))
-                            .or_insert(delta as u64);
-                        pending_daily_clicks
-                            .entry((date_key, display_id))
-                            .and_modify(|v| *v = v.saturating_add(delta as u64))
-                            .or_insert(delta as u64);
+                        pending_heatmaps
+                            .entry(kind)
+                            .or_default()
+                            .add(date_key, display_id, idx, delta);
                     }
                     DbOp::MigrateLegacyHeatmap { state, reply } => {
                         let res = migrate_legacy_heatmap(&mut conn, &state);
                         let _ = reply.send(res);
                     }
                     DbOp::ClearHeatmap {
+                        kind,
                         display_id,
                         date_key,
                         reply,
                     } => {
                         let res = (|| -> Result<(), String> {
+                            let tables = heatmap_tables(kind);
                             let tx = conn
                                 .transaction()
                                 .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
 
+                            let clear = |table: &str, filter: &str, args: &[&str]| {
+                                tx.execute(
+                                    &format!("DELETE FROM {}{}", table, filter),
+                                    params_from_iter(args.iter()),
+                                )
+                                .map(|_| ())
+                                .map_err(|e| format!("Failed to clear {}: {}", table, e))
+                            };
                             match (display_id.as_deref(), date_key.as_deref()) {
                                 (Some(display_id), Some(date_key)) => {
-                                    tx.execute(
-                                        "DELETE FROM click_heatmap_daily_cells WHERE date_key=?1 AND display_id=?2",
-                                        params![date_key, display_id],
-                                    )
-                                    .map_err(|e| format!("Failed to clear click_heatmap_daily_cells: {}", e))?;
-                                    tx.execute(
-                                        "DELETE FROM click_heatmap_daily_meta WHERE date_key=?1 AND display_id=?2",
-                                        params![date_key, display_id],
-                                    )
-                                    .map_err(|e| format!("Failed to clear click_heatmap_daily_meta: {}", e))?;
+                                    let filter = " WHERE date_key=?1 AND display_id=?2";
+                                    clear(tables.daily_cells, filter, &[date_key, display_id])?;
+                                    clear(tables.daily_meta, filter, &[date_key, display_id])?;
                                 }
                                 (None, Some(date_key)) => {
-                                    tx.execute(
-                                        "DELETE FROM click_heatmap_daily_cells WHERE date_key=?1",
-                                        params![date_key],
-                                    )
-                                    .map_err(|e| format!("Failed to clear click_heatmap_daily_cells: {}", e))?;
-                                    tx.execute(
-                                        "DELETE FROM click_heatmap_daily_meta WHERE date_key=?1",
-                                        params![date_key],
-                                    )
-                                    .map_err(|e| format!("Failed to clear click_heatmap_daily_meta: {}", e))?;
+                                    clear(tables.daily_cells, " WHERE date_key=?1", &[date_key])?;
+                                    clear(tables.daily_meta, " WHERE date_key=?1", &[date_key])?;
                                 }
                                 (Some(display_id), None) => {
-                                    tx.execute(
-                                        "DELETE FROM click_heatmap_total_cells WHERE display_id=?1",
-                                        params![display_id],
-                                    )
-                                    .map_err(|e| format!("Failed to clear click_heatmap_total_cells: {}", e))?;
-                                    tx.execute(
-                                        "DELETE FROM click_heatmap_total_meta WHERE display_id=?1",
-                                        params![display_id],
-                                    )
-                                    .map_err(|e| format!("Failed to clear click_heatmap_total_meta: {}", e))?;
+                                    clear(tables.total_cells, " WHERE display_id=?1", &[display_id])?;
+                                    clear(tables.total_meta, " WHERE display_id=?1", &[display_id])?;
                                 }
                                 (None, None) => {
-                                    tx.execute("DELETE FROM click_heatmap_total_cells", [])
-                                        .map_err(|e| format!("Failed to clear click_heatmap_total_cells: {}", e))?;
-                                    tx.execute("DELETE FROM click_heatmap_total_meta", [])
-                                        .map_err(|e| format!("Failed to clear click_heatmap_total_meta: {}", e))?;
-                                    tx.execute("DELETE FROM click_heatmap_daily_cells", [])
-                                        .map_err(|e| format!("Failed to clear click_heatmap_daily_cells: {}", e))?;
-                                    tx.execute("DELETE FROM click_heatmap_daily_meta", [])
-                                        .map_err(|e| format!("Failed to clear click_heatmap_daily_meta: {}", e))?;
+                                    clear(tables.total_cells, "", &[])?;
+                                    clear(tables.total_meta, "", &[])?;
+                                    clear(tables.daily_cells, "", &[])?;
+                                    clear(tables.daily_meta, "", &[])?;
                                 }
                             }
 
-                            tx.commit()
-                                .map_err(|e| format!("Failed to commit click heatmap clear: {}", e))?;
-                            Ok(())
-                        })();
-                        let _ = reply.send(res);
-                    }
-                    DbOp::Vacuum => {
-                        let _ = conn.execute("VACUUM", []);
-                    }
-                }
+                            tx.commit()
+                                .map_err(|e| format!("Failed to commit heatmap clear: {}", e))?;
+                            Ok(())
+                        })();
+                        let _ = reply.send(res);
+                    }
+                    DbOp::MergeImport {
+                        source,
+                        live_date,
+                        import_source,
+                        dry_run,
+                        reply,
+                    } => {
+                        let res = merge_import(
+                            &mut conn,
+                            &source,
+                            live_date.as_deref(),
+                            import_source.as_ref(),
+                            dry_run,
+                        );
+                        let _ = reply.send(res);
+                    }
+                    DbOp::TimelineDelta {
+                        date_key,
+                        minute,
+                        delta,
+                    } => {
+                        pending_timeline.entry((date_key, minute)).or_default().add(&delta);
+                    }
+                    DbOp::TypingSpeedDelta {
+                        date_key,
+                        hour,
+                        delta,
+                    } => {
+                        pending_typing.entry((date_key, hour)).or_default().add(&delta);
+                    }
+                    DbOp::CorrectionDelta {
+                        date_key,
+                        hour,
+                        delta,
+                    } => {
+                        pending_corrections.entry((date_key, hour)).or_default().add(&delta);
+                    }
+                    DbOp::KeyBigram {
+                        date_key,
+                        first,
+                        second,
+                    } => {
+                        let count = pending_bigrams.entry((date_key, first, second)).or_insert(0);
+                        *count = count.saturating_add(1);
+                    }
+                    DbOp::SetRetention(policy) => {
+                        retention = Some(policy);
+                        next_prune_ms = now_ms();
+                    }
+                    DbOp::Prune { reply } => {
+                        let res = match retention.as_ref() {
+                            Some(policy) => {
+                                prune_expired(&mut conn, policy, chrono::Local::now().date_naive())
+                            }
+                            None => Err("history retention policy not set".to_string()),
+                        };
+                        next_prune_ms = now_ms().saturating_add(PRUNE_INTERVAL_MS);
+                        match reply {
+                            Some(reply) => {
+                                let _ = reply.send(res);
+                            }
+                            None => {
+                                if let Err(e) = res {
+                                    eprintln!("{}", e);
+                                }
+                            }
+                        }
+                    }
+                    DbOp::InsertSession { record, reply } => {
+                        let res = insert_session(&conn, &record);
+                        match reply {
+                            Some(reply) => {
+                                let _ = reply.send(res);
+                            }
+                            None => {
+                                if let Err(e) = res {
+                                    eprintln!("{}", e);
+                                }
+                            }
+                        }
+                    }
+                    DbOp::Backup { dest, reply } => {
+                        // Snapshots must include clicks still buffered in memory.
+                        force_flush = true;
+                        pending_reply = Some((PendingFileOp::Backup(dest), reply));
+                    }
+                    DbOp::Restore { src, reply } => {
+                        force_flush = true;
+                        pending_reply = Some((PendingFileOp::Restore(src), reply));
+                    }
+                    DbOp::Vacuum { full, reply } => {
+                        let res = vacuum(&conn, full);
+                        match reply {
+                            Some(reply) => {
+                                let _ = reply.send(res);
+                            }
+                            None => {
+                                if let Err(e) = res {
+                                    eprintln!("{}", e);
+                                }
+                            }
+                        }
+                    }
+                    DbOp::ScrubKeyDetails { level, reply } => {
+                        let _ = reply.send(scrub_key_details(&mut conn, level));
+                    }
+                }
+            }
+
+            let should_flush = !pending_heatmaps.is_empty()
+                && (force_flush
+                    || now_ms().saturating_sub(last_flush_ms) >= 650
+                    || pending_heatmaps.values().map(PendingHeatmap::cell_count).sum::<usize>() >= 1200);
+            if should_flush {
+                for (kind, pending) in std::mem::take(&mut pending_heatmaps) {
+                    if let Err(e) = apply_heatmap_batch(&mut conn, kind, pending) {
+                        eprintln!("{}", e);
+                    }
+                }
+                last_flush_ms = now_ms();
+            }
+            if (!pending_timeline.is_empty()
+                || !pending_typing.is_empty()
+                || !pending_corrections.is_empty()
+                || !pending_bigrams.is_empty())
+                && (force_flush || now_ms().saturating_sub(last_timeline_flush_ms) >= 650)
+            {
+                if let Err(e) = apply_timeline_batch(&mut conn, std::mem::take(&mut pending_timeline)) {
+                    eprintln!("{}", e);
+                }
+                if !pending_typing.is_empty() {
+                    if let Err(e) =
+                        apply_typing_speed_batch(&mut conn, std::mem::take(&mut pending_typing))
+                    {
+                        eprintln!("{}", e);
+                    }
+                }
+                if !pending_corrections.is_empty() {
+                    if let Err(e) = apply_corrections_batch(
+                        &mut conn,
+                        std::mem::take(&mut pending_corrections),
+                    ) {
+                        eprintln!("{}", e);
+                    }
+                }
+                if !pending_bigrams.is_empty() {
+                    if let Err(e) =
+                        apply_key_bigram_batch(&mut conn, std::mem::take(&mut pending_bigrams))
+                    {
+                        eprintln!("{}", e);
+                    }
+                }
+                last_timeline_flush_ms = now_ms();
+            }
+            force_flush = false;
+
+            if now_ms() >= next_prune_ms {
+                if let Some(policy) = retention.as_ref() {
+                    if let Err(e) =
+                        prune_expired(&mut conn, policy, chrono::Local::now().date_naive())
+                    {
+                        eprintln!("{}", e);
+                    }
+                }
+                next_prune_ms = now_ms().saturating_add(PRUNE_INTERVAL_MS);
+            }
+
+            if let Some((op, reply)) = pending_reply.take() {
+                let res = match op {
+                    PendingFileOp::Backup(dest) => backup_db_to(&conn, &dest),
+                    PendingFileOp::Restore(src) => restore_db_from(&mut conn, &src),
+                };
+                let _ = reply.send(res);
+            }
+        }
+    });
+
+    *CTX.lock() = Some(DbContext { path, tx });
+    Ok(())
+}
+
+fn with_ctx<T>(f: impl FnOnce(&DbContext) -> T) -> Option<T> {
+    CTX.lock().as_ref().map(f)
+}
+
+pub fn enqueue_bulk_upsert_daily(days: Vec<DailyStats>) {
+    if days.is_empty() {
+        return;
+    }
+    let _ = with_ctx(|ctx| ctx.tx.send(DbOp::BulkUpsertDaily(days)));
+}
+
+pub fn clear_daily_stats() {
+    let _ = with_ctx(|ctx| ctx.tx.send(DbOp::ClearDaily));
+    let _ = with_ctx(|ctx| {
+        ctx.tx.send(DbOp::Vacuum {
+            full: true,
+            reply: None,
+        })
+    });
+}
+
+pub fn record_heatmap_cell(kind: HeatmapKind, display_id: Arc<str>, idx: usize) -> bool {
+    let trimmed = display_id.as_ref().trim();
+    if trimmed.is_empty() {
+        return false;
+    }
+    let idx = u32::try_from(idx).unwrap_or(u32::MAX);
+    let date_key = crate::core::date_key::today_key_arc();
+    let display_id = if trimmed == display_id.as_ref() {
+        display_id
+    } else {
+        Arc::<str>::from(trimmed.to_string())
+    };
+
+    with_ctx(|ctx| {
+        ctx.tx.send(DbOp::HeatmapDelta {
+            kind,
+            date_key,
+            display_id,
+            idx,
+            delta: 1,
+        })
+    })
+    .and_then(|res| res.ok())
+    .is_some()
+}
+
+/// Adds activity to the current timeline bucket. `bucket_minutes` should divide 60; anything else
+/// falls back to one-minute buckets.
+pub fn record_timeline(delta: TimelineDelta, bucket_minutes: u32) {
+    if delta.is_empty() {
+        return;
+    }
+    let bucket_minutes = if (1..=60).contains(&bucket_minutes) && 60 % bucket_minutes == 0 {
+        bucket_minutes
+    } else {
+        1
+    };
+    let now = chrono::Local::now();
+    let minute_of_day = now.hour() * 60 + now.minute();
+    let minute = minute_of_day - minute_of_day % bucket_minutes;
+    let date_key: Arc<str> = Arc::from(now.date_naive().to_string());
+
+    with_ctx(|ctx| {
+        let _ = ctx.tx.send(DbOp::TimelineDelta {
+            date_key,
+            minute,
+            delta,
+        });
+    });
+}
+
+const PRUNE_INTERVAL_MS: i64 = 6 * 60 * 60 * 1000;
+
+/// Days to keep per table; `0` keeps rows forever.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    pub daily_stats_days: u32,
+    pub key_counts_days: u32,
+    pub mouse_button_counts_days: u32,
+    pub hourly_days: u32,
+    pub app_input_days: u32,
+    pub click_heatmap_days: u32,
+    pub timeline_days: u32,
+    pub sessions_days: u32,
+}
+
+impl RetentionPolicy {
+    pub fn from_settings(settings: &Settings) -> Self {
+        let r = &settings.history_retention;
+        Self {
+            daily_stats_days: r.daily_stats_days,
+            key_counts_days: r.key_counts_days,
+            mouse_button_counts_days: r.mouse_button_counts_days,
+            hourly_days: r.hourly_days,
+            app_input_days: r.app_input_days,
+            click_heatmap_days: r.click_heatmap_days,
+            timeline_days: settings.timeline_retention_days,
+            sessions_days: r.sessions_days,
+        }
+    }
+
+    /// Date-keyed tables and how many days of each to keep.
+    fn rules(&self) -> [(&'static str, u32); 15] {
+        [
+            ("daily_stats", self.daily_stats_days),
+            ("daily_key_counts", self.key_counts_days),
+            ("daily_shortcut_counts", self.key_counts_days),
+            ("daily_key_bigrams", self.key_counts_days),
+            ("daily_mouse_button_counts", self.mouse_button_counts_days),
+            ("daily_hourly", self.hourly_days),
+            ("daily_typing_speed", self.hourly_days),
+            ("daily_corrections", self.hourly_days),
+            ("daily_app_input", self.app_input_days),
+            ("click_heatmap_daily_cells", self.click_heatmap_days),
+            ("click_heatmap_daily_meta", self.click_heatmap_days),
+            ("move_heatmap_daily_cells", self.click_heatmap_days),
+            ("move_heatmap_daily_meta", self.click_heatmap_days),
+            ("activity_timeline", self.timeline_days),
+            ("sessions", self.sessions_days),
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryTableRows {
+    pub table: String,
+    pub rows: u64,
+}
+
+/// Deletes rows older than each table's retention window, then hands freed pages back to the OS
+/// when the database uses incremental auto-vacuum. Returns the rows deleted per table.
+fn prune_expired(
+    conn: &mut Connection,
+    policy: &RetentionPolicy,
+    today: chrono::NaiveDate,
+) -> Result<Vec<HistoryTableRows>, String> {
+    let tx = conn
+        .transaction()
+        .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
+    let mut deleted = Vec::new();
+    for (table, days) in policy.rules() {
+        if days == 0 {
+            continue;
+        }
+        let keep_from = today - chrono::Duration::days(i64::from(days) - 1);
+        let rows = tx
+            .execute(
+                &format!("DELETE FROM {} WHERE date_key < ?1", table),
+                params![keep_from.to_string()],
+            )
+            .map_err(|e| format!("Failed to prune {}: {}", table, e))?;
+        if rows > 0 {
+            deleted.push(HistoryTableRows {
+                table: table.to_string(),
+                rows: rows as u64,
+            });
+        }
+    }
+    tx.commit()
+        .map_err(|e| format!("Failed to commit history pruning: {}", e))?;
+
+    if !deleted.is_empty() && auto_vacuum_mode(conn)? == 2 {
+        vacuum(conn, false)?;
+    }
+    Ok(deleted)
+}
+
+fn auto_vacuum_mode(conn: &Connection) -> Result<i64, String> {
+    conn.query_row("PRAGMA auto_vacuum", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read auto_vacuum: {}", e))
+}
+
+/// `full` rebuilds the whole file (and switches old databases to incremental auto-vacuum);
+/// otherwise only the free pages are released.
+fn vacuum(conn: &Connection, full: bool) -> Result<(), String> {
+    if full {
+        conn.execute_batch("PRAGMA auto_vacuum=INCREMENTAL; VACUUM;")
+            .map_err(|e| format!("Failed to vacuum history db: {}", e))?;
+    } else {
+        // The pragma returns a row per freed page, so step through it rather than `execute`.
+        let mut stmt = conn
+            .prepare("PRAGMA incremental_vacuum")
+            .map_err(|e| format!("Failed to prepare incremental_vacuum: {}", e))?;
+        let mut rows = stmt
+            .query([])
+            .map_err(|e| format!("Failed to run incremental_vacuum: {}", e))?;
+        while rows
+            .next()
+            .map_err(|e| format!("Failed to run incremental_vacuum: {}", e))?
+            .is_some()
+        {}
+    }
+    conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+        .map_err(|e| format!("Failed to checkpoint history db: {}", e))
+}
+
+/// Rewrites one key-detail table with `reduce` applied to its code column, merging rows that
+/// collapse into the same category. Returns how many rows changed.
+fn reduce_detail_rows(
+    tx: &Connection,
+    table: &str,
+    kind_col: Option<&str>,
+    code_col: &str,
+    reduce: fn(&str) -> String,
+) -> Result<u64, String> {
+    let mut rows: std::collections::BTreeMap<(String, i64, String), i64> = Default::default();
+    let mut changed = 0u64;
+    {
+        let mut stmt = tx
+            .prepare(&format!(
+                "SELECT date_key, {}, {}, count FROM {}",
+                kind_col.unwrap_or("0"),
+                code_col,
+                table
+            ))
+            .map_err(|e| format!("Failed to prepare {} scrub query: {}", table, e))?;
+        let existing = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })
+            .map_err(|e| format!("Failed to read {}: {}", table, e))?;
+        for row in existing {
+            let (date_key, kind, code, count) =
+                row.map_err(|e| format!("Failed to read {} row: {}", table, e))?;
+            let reduced = reduce(&code);
+            if reduced != code {
+                changed += 1;
             }
+            let entry = rows.entry((date_key, kind, reduced)).or_insert(0);
+            *entry = entry.saturating_add(count);
+        }
+    }
+    if changed == 0 {
+        return Ok(0);
+    }
 
-            let should_flush = !pending_total_cells.is_empty()
-                && (now_ms().saturating_sub(last_flush_ms) >= 650
-                    || pending_total_cells.len() + pending_daily_cells.len() >= 1200);
-            if should_flush {
-                let total_cells = std::mem::take(&mut pending_total_cells);
-                let daily_cells = std::mem::take(&mut pending_daily_cells);
-                let total_clicks = std::mem::take(&mut pending_total_clicks);
-                let daily_clicks = std::mem::take(&mut pending_daily_clicks);
-                if let Err(e) = apply_heatmap_batch(&mut conn, total_cells, daily_cells, total_clicks, daily_clicks) {
-                    eprintln!("{}", e);
+    tx.execute(&format!("DELETE FROM {}", table), [])
+        .map_err(|e| format!("Failed to clear {}: {}", table, e))?;
+    let insert = match kind_col {
+        Some(kind_col) => format!(
+            "INSERT INTO {}(date_key, {}, {}, count) VALUES (?1, ?2, ?3, ?4)",
+            table, kind_col, code_col
+        ),
+        None => format!("INSERT INTO {}(date_key, {}, count) VALUES (?1, ?2, ?3)", table, code_col),
+    };
+    let mut stmt = tx
+        .prepare(&insert)
+        .map_err(|e| format!("Failed to prepare {} insert: {}", table, e))?;
+    for ((date_key, kind, code), count) in rows {
+        let res = match kind_col {
+            Some(_) => stmt.execute(params![date_key, kind, code, count]),
+            None => stmt.execute(params![date_key, code, count]),
+        };
+        res.map_err(|e| format!("Failed to insert {}: {}", table, e))?;
+    }
+    Ok(changed)
+}
+
+/// Brings stored key and shortcut counts down to `level`. Deleted content is overwritten and the
+/// WAL truncated, so the details don't linger in free pages.
+fn scrub_key_details(
+    conn: &mut Connection,
+    level: KeyPrivacyLevel,
+) -> Result<Vec<HistoryTableRows>, String> {
+    if level == KeyPrivacyLevel::Full {
+        return Ok(Vec::new());
+    }
+    let secure_delete: i64 = conn
+        .query_row("PRAGMA secure_delete", [], |row| row.get(0))
+        .map_err(|e| format!("Failed to read secure_delete: {}", e))?;
+    conn.query_row("PRAGMA secure_delete=ON", [], |_| Ok(()))
+        .map_err(|e| format!("Failed to enable secure_delete: {}", e))?;
+
+    let res = (|| -> Result<Vec<HistoryTableRows>, String> {
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start sqlite transaction: {}", e))?;
+        let mut scrubbed = Vec::new();
+        for (table, kind_col, code_col) in [
+            ("daily_key_counts", Some("kind"), "code"),
+            ("daily_shortcut_counts", None, "shortcut"),
+        ] {
+            let rows = match level {
+                KeyPrivacyLevel::Full => 0,
+                KeyPrivacyLevel::Categories => {
+                    let reduce: fn(&str) -> String = if kind_col.is_some() {
+                        |code| crate::core::key_privacy::reduced_code(code).to_string()
+                    } else {
+                        crate::core::key_privacy::reduced_shortcut
+                    };
+                    reduce_detail_rows(&tx, table, kind_col, code_col, reduce)?
                 }
-                last_flush_ms = now_ms();
+                KeyPrivacyLevel::TotalsOnly => tx
+                    .execute(&format!("DELETE FROM {}", table), [])
+                    .map_err(|e| format!("Failed to clear {}: {}", table, e))?
+                    as u64,
+            };
+            if rows > 0 {
+                scrubbed.push(HistoryTableRows {
+                    table: table.to_string(),
+                    rows,
+                });
+            }
+        }
+        // Bigrams are only kept at `Full`; correction counts are Backspace/Delete counts under
+        // another name.
+        let mut cleared = vec!["daily_key_bigrams"];
+        if level == KeyPrivacyLevel::TotalsOnly {
+            cleared.push("daily_corrections");
+        }
+        for table in cleared {
+            let rows = tx
+                .execute(&format!("DELETE FROM {}", table), [])
+                .map_err(|e| format!("Failed to clear {}: {}", table, e))?
+                as u64;
+            if rows > 0 {
+                scrubbed.push(HistoryTableRows {
+                    table: table.to_string(),
+                    rows,
+                });
             }
         }
+        tx.commit()
+            .map_err(|e| format!("Failed to commit key detail scrub: {}", e))?;
+        Ok(scrubbed)
+    })();
+
+    let restore = conn
+        .query_row(&format!("PRAGMA secure_delete={}", secure_delete), [], |_| Ok(()))
+        .map_err(|e| format!("Failed to restore secure_delete: {}", e));
+    let scrubbed = res?;
+    restore?;
+    vacuum(conn, false)?;
+    Ok(scrubbed)
+}
+
+/// Installs the retention policy; the DB worker prunes right away and then every few hours.
+pub fn set_retention(policy: RetentionPolicy) {
+    with_ctx(|ctx| {
+        let _ = ctx.tx.send(DbOp::SetRetention(policy));
     });
+}
 
-    *CTX.lock() = Some(DbContext { path, tx });
-    Ok(())
+/// Prunes expired rows now, in the background.
+pub fn enqueue_prune() {
+    with_ctx(|ctx| {
+        let _ = ctx.tx.send(DbOp::Prune { reply: None });
+    });
 }
 
-fn with_ctx<T>(f: impl FnOnce(&DbContext) -> T) -> Option<T> {
-    CTX.lock().as_ref().map(f)
+pub fn prune_now() -> Result<Vec<HistoryTableRows>, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+    let (reply_tx, reply_rx) = mpsc::channel();
+    ctx.tx
+        .send(DbOp::Prune {
+            reply: Some(reply_tx),
+        })
+        .map_err(|_| "history db worker not available".to_string())?;
+    reply_rx
+        .recv()
+        .map_err(|_| "history db prune failed: worker disconnected".to_string())?
 }
 
-pub fn enqueue_bulk_upsert_daily(days: Vec<DailyStats>) {
-    if days.is_empty() {
+pub fn vacuum_now(full: bool) -> Result<(), String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+    let (reply_tx, reply_rx) = mpsc::channel();
+    ctx.tx
+        .send(DbOp::Vacuum {
+            full,
+            reply: Some(reply_tx),
+        })
+        .map_err(|_| "history db worker not available".to_string())?;
+    reply_rx
+        .recv()
+        .map_err(|_| "history db vacuum failed: worker disconnected".to_string())?
+}
+
+/// Scrubs a history database that isn't the live one, such as a backup's copy.
+pub fn scrub_key_details_in(
+    path: &Path,
+    level: KeyPrivacyLevel,
+) -> Result<Vec<HistoryTableRows>, String> {
+    let mut conn = open_staging_db(path)?;
+    scrub_key_details(&mut conn, level)
+}
+
+/// Scrubs stored key details down to `level` on the DB worker and waits for the result.
+pub fn scrub_key_details_now(level: KeyPrivacyLevel) -> Result<Vec<HistoryTableRows>, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+    let (reply_tx, reply_rx) = mpsc::channel();
+    ctx.tx
+        .send(DbOp::ScrubKeyDetails {
+            level,
+            reply: reply_tx,
+        })
+        .map_err(|_| "history db worker not available".to_string())?;
+    reply_rx
+        .recv()
+        .map_err(|_| "history db scrub failed: worker disconnected".to_string())?
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryTableStats {
+    pub table: String,
+    pub rows: u64,
+    /// Bytes used by the table and its indexes; `None` if `dbstat` is unavailable.
+    pub bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryDbStats {
+    pub path: String,
+    pub file_bytes: u64,
+    pub wal_bytes: u64,
+    pub page_size: u64,
+    pub page_count: u64,
+    pub freelist_pages: u64,
+    pub incremental_vacuum: bool,
+    pub tables: Vec<HistoryTableStats>,
+}
+
+fn table_sizes(conn: &Connection) -> Option<HashMap<String, u64>> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT s.tbl_name, SUM(d.pgsize)
+FROM dbstat d
+JOIN sqlite_schema s ON s.name = d.name
+GROUP BY s.tbl_name
+"#,
+        )
+        .ok()?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+        .ok()?;
+    rows.map(|r| r.map(|(name, bytes)| (name, u64::try_from(bytes).unwrap_or(0))))
+        .collect::<Result<HashMap<_, _>, _>>()
+        .ok()
+}
+
+fn load_db_stats_from(conn: &Connection) -> Result<Vec<HistoryTableStats>, String> {
+    let sizes = table_sizes(conn);
+    EXPORT_TABLES
+        .iter()
+        .map(|table| {
+            let rows: i64 = conn
+                .query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| row.get(0))
+                .map_err(|e| format!("Failed to count {}: {}", table, e))?;
+            Ok(HistoryTableStats {
+                table: table.to_string(),
+                rows: u64::try_from(rows).unwrap_or(0),
+                bytes: sizes
+                    .as_ref()
+                    .map(|sizes| sizes.get(*table).copied().unwrap_or(0)),
+            })
+        })
+        .collect()
+}
+
+/// Row counts and on-disk footprint of the history database.
+pub fn db_stats() -> Result<HistoryDbStats, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let conn = open_read_conn(&ctx.path)?;
+    let pragma = |name: &str| -> Result<i64, String> {
+        conn.query_row(&format!("PRAGMA {}", name), [], |row| row.get(0))
+            .map_err(|e| format!("Failed to read {}: {}", name, e))
+    };
+    let page_size = pragma("page_size")?;
+    let page_count = pragma("page_count")?;
+    let freelist_pages = pragma("freelist_count")?;
+    let incremental_vacuum = auto_vacuum_mode(&conn)? == 2;
+    let tables = load_db_stats_from(&conn)?;
+
+    let file_len = |p: &Path| std::fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    let mut wal_path = ctx.path.clone().into_os_string();
+    wal_path.push("-wal");
+
+    Ok(HistoryDbStats {
+        path: ctx.path.to_string_lossy().to_string(),
+        file_bytes: file_len(&ctx.path),
+        wal_bytes: file_len(Path::new(&wal_path)),
+        page_size: u64::try_from(page_size).unwrap_or(0),
+        page_count: u64::try_from(page_count).unwrap_or(0),
+        freelist_pages: u64::try_from(freelist_pages).unwrap_or(0),
+        incremental_vacuum,
+        tables,
+    })
+}
+
+fn load_timeline_from(conn: &Connection, date_key: &str) -> Result<Vec<TimelineBucket>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT minute, keyboard, mouse_single, distance_px
+FROM activity_timeline
+WHERE date_key = ?1
+ORDER BY minute
+"#,
+        )
+        .map_err(|e| format!("Failed to prepare activity_timeline query: {}", e))?;
+    let rows = stmt
+        .query_map(params![date_key], |row| {
+            Ok(TimelineBucket {
+                minute: row.get::<_, u32>(0)?,
+                keyboard: u64::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+                mouse_single: u64::try_from(row.get::<_, i64>(2)?).unwrap_or(0),
+                distance_px: u64::try_from(row.get::<_, i64>(3)?).unwrap_or(0),
+            })
+        })
+        .map_err(|e| format!("Failed to query activity_timeline: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read activity_timeline row: {}", e))
+}
+
+fn load_typing_speed_from(conn: &Connection, date_key: &str) -> Result<TypingSpeedDay, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT hour, keys, active_ms, peak_kpm
+FROM daily_typing_speed
+WHERE date_key = ?1
+ORDER BY hour
+"#,
+        )
+        .map_err(|e| format!("Failed to prepare daily_typing_speed query: {}", e))?;
+    let rows = stmt
+        .query_map(params![date_key], |row| {
+            Ok(TypingSpeedHour::new(
+                row.get::<_, u32>(0)?,
+                u64::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+                u64::try_from(row.get::<_, i64>(2)?).unwrap_or(0),
+                row.get::<_, f64>(3)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to query daily_typing_speed: {}", e))?;
+    let hourly = rows
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read daily_typing_speed row: {}", e))?;
+    Ok(TypingSpeedDay::from_hours(date_key.to_string(), hourly))
+}
+
+/// Loads a day's typing speed (sparse: hours without typing are omitted).
+pub fn load_typing_speed(date_key: &str) -> Result<TypingSpeedDay, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let conn = open_read_conn(&ctx.path)?;
+    load_typing_speed_from(&conn, date_key)
+}
+
+/// Adds typing time and keystrokes to `hour` of `date_key`.
+pub fn record_typing_speed(date_key: Arc<str>, hour: u32, delta: TypingSpeedDelta) {
+    if delta.keys == 0 && delta.active_ms == 0 {
         return;
     }
-    let _ = with_ctx(|ctx| ctx.tx.send(DbOp::BulkUpsertDaily(days)));
+    with_ctx(|ctx| {
+        let _ = ctx.tx.send(DbOp::TypingSpeedDelta {
+            date_key,
+            hour,
+            delta,
+        });
+    });
 }
 
-pub fn clear_daily_stats() {
-    let _ = with_ctx(|ctx| ctx.tx.send(DbOp::ClearDaily));
-    let _ = with_ctx(|ctx| ctx.tx.send(DbOp::Vacuum));
+fn load_corrections_from(conn: &Connection, date_key: &str) -> Result<Vec<TypingAccuracyHour>, String> {
+    let mut stmt = conn
+        .prepare(
+            r#"
+SELECT hour, typed_keys, correction_keys, bursts, burst_deletes
+FROM daily_corrections
+WHERE date_key = ?1
+ORDER BY hour
+"#,
+        )
+        .map_err(|e| format!("Failed to prepare daily_corrections query: {}", e))?;
+    let rows = stmt
+        .query_map(params![date_key], |row| {
+            Ok(TypingAccuracyHour::new(
+                row.get::<_, u32>(0)?,
+                u64::try_from(row.get::<_, i64>(1)?).unwrap_or(0),
+                u64::try_from(row.get::<_, i64>(2)?).unwrap_or(0),
+                u64::try_from(row.get::<_, i64>(3)?).unwrap_or(0),
+                u64::try_from(row.get::<_, i64>(4)?).unwrap_or(0),
+            ))
+        })
+        .map_err(|e| format!("Failed to query daily_corrections: {}", e))?;
+    rows.collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("Failed to read daily_corrections row: {}", e))
 }
 
-pub fn record_click_heatmap_cell(display_id: Arc<str>, idx: usize) -> bool {
-    let trimmed = display_id.as_ref().trim();
-    if trimmed.is_empty() {
-        return false;
-    }
-    let idx = u32::try_from(idx).unwrap_or(u32::MAX);
-    let date_key = crate::core::date_key::today_key_arc();
-    let display_id = if trimmed == display_id.as_ref() {
-        display_id
-    } else {
-        Arc::<str>::from(trimmed.to_string())
-    };
+/// Loads a day's hourly correction counts and the day's stored stats, from one snapshot.
+pub fn load_corrections(
+    date_key: &str,
+) -> Result<(Vec<TypingAccuracyHour>, Option<DailyStats>), String> {
+    with_read_snapshot(|conn, _| {
+        Ok((
+            load_corrections_from(conn, date_key)?,
+            load_day_from(conn, date_key)?,
+        ))
+    })
+}
 
+/// Counts one `first` -> `second` key sequence on `date_key`.
+pub fn record_key_bigram(date_key: Arc<str>, first: Arc<str>, second: Arc<str>) {
     with_ctx(|ctx| {
-        ctx.tx.send(DbOp::HeatmapDelta {
+        let _ = ctx.tx.send(DbOp::KeyBigram {
+            date_key,
+            first,
+            second,
+        });
+    });
+}
+
+/// Adds typed and correction keystrokes to `hour` of `date_key`.
+pub fn record_corrections(date_key: Arc<str>, hour: u32, delta: CorrectionDelta) {
+    if delta.is_empty() {
+        return;
+    }
+    with_ctx(|ctx| {
+        let _ = ctx.tx.send(DbOp::CorrectionDelta {
             date_key,
+            hour,
+            delta,
+        });
+    });
+}
+
+/// Loads a day's timeline buckets (sparse: buckets without activity are omitted).
+pub fn load_timeline(date_key: &str) -> Result<Vec<TimelineBucket>, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let conn = open_read_conn(&ctx.path)?;
+    load_timeline_from(&conn, date_key)
+}
+
+/// Persists a finished session. With `wait`, blocks until it is written (used on quit).
+pub fn record_session(record: SessionRecord, wait: bool) -> Result<(), String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    if !wait {
+        return ctx
+            .tx
+            .send(DbOp::InsertSession {
+                record: Box::new(record),
+                reply: None,
+            })
+            .map_err(|_| "history db worker not available".to_string());
+    }
+
+    let (reply_tx, reply_rx) = mpsc::channel::<Result<(), String>>();
+    ctx.tx
+        .send(DbOp::InsertSession {
+            record: Box::new(record),
+            reply: Some(reply_tx),
+        })
+        .map_err(|_| "history db worker not available".to_string())?;
+    reply_rx
+        .recv()
+        .map_err(|_| "history db session write failed: worker disconnected".to_string())?
+}
+
+pub fn load_sessions(date_key: &str) -> Result<Vec<SessionRecord>, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let conn = open_read_conn(&ctx.path)?;
+    load_sessions_from(&conn, date_key)
+}
+
+/// Statistics over finished sessions whose start date falls in `[start_key, end_key]`.
+pub fn load_session_stats(start_key: Option<&str>, end_key: Option<&str>) -> Result<SessionStats, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let conn = open_read_conn(&ctx.path)?;
+    load_session_stats_from(&conn, start_key, end_key)
+}
+
+pub fn enqueue_migrate_click_heatmap_legacy(
+    state: ClickHeatmapState,
+) -> Result<mpsc::Receiver<Result<(), String>>, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let (reply_tx, reply_rx) = mpsc::channel::<Result<(), String>>();
+    ctx.tx
+        .send(DbOp::MigrateLegacyHeatmap { state, reply: reply_tx })
+        .map_err(|_| "history db worker not available".to_string())?;
+
+    Ok(reply_rx)
+}
+
+pub fn clear_heatmap(
+    kind: HeatmapKind,
+    display_id: Option<String>,
+    date_key: Option<String>,
+) -> Result<(), String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let (reply_tx, reply_rx) = mpsc::channel::<Result<(), String>>();
+    ctx.tx
+        .send(DbOp::ClearHeatmap {
+            kind,
             display_id,
-            idx,
-            delta: 1,
+            date_key,
+            reply: reply_tx,
         })
-    })
-    .and_then(|res| res.ok())
-    .is_some()
+        .map_err(|_| "history db worker not available".to_string())?;
+
+    reply_rx
+        .recv()
+        .map_err(|_| "history db clear failed: worker disconnected".to_string())?
 }
 
-pub fn enqueue_migrate_click_heatmap_legacy(
-    state: ClickHeatmapState,
-) -> Result<mpsc::Receiver<Result<(), String>>, String> {
+/// Merges a staged import database (see `open_staging_db`) into the history DB. Rows dated
+/// `live_date` are left out because the live day is still owned by `MeritStats::today`. A source
+/// recorded by an earlier import is refused.
+pub fn merge_import_db(
+    source: PathBuf,
+    live_date: Option<String>,
+    import_source: Option<ImportSource>,
+    dry_run: bool,
+) -> Result<ImportMergeReport, String> {
     let ctx = CTX
         .lock()
         .clone()
         .ok_or_else(|| "history db not initialized".to_string())?;
 
-    let (reply_tx, reply_rx) = mpsc::channel::<Result<(), String>>();
+    let (reply_tx, reply_rx) = mpsc::channel::<Result<ImportMergeReport, String>>();
     ctx.tx
-        .send(DbOp::MigrateLegacyHeatmap { state, reply: reply_tx })
+        .send(DbOp::MergeImport {
+            source,
+            live_date,
+            import_source,
+            dry_run,
+            reply: reply_tx,
+        })
         .map_err(|_| "history db worker not available".to_string())?;
 
-    Ok(reply_rx)
+    reply_rx
+        .recv()
+        .map_err(|_| "history db import failed: worker disconnected".to_string())?
 }
 
-pub fn clear_click_heatmap(display_id: Option<String>, date_key: Option<String>) -> Result<(), String> {
+fn request_file_op(op: impl FnOnce(Sender<Result<(), String>>) -> DbOp) -> Result<(), String> {
     let ctx = CTX
         .lock()
         .clone()
@@ -919,16 +3046,22 @@ pub fn clear_click_heatmap(display_id: Option<String>, date_key: Option<String>)
 
     let (reply_tx, reply_rx) = mpsc::channel::<Result<(), String>>();
     ctx.tx
-        .send(DbOp::ClearHeatmap {
-            display_id,
-            date_key,
-            reply: reply_tx,
-        })
+        .send(op(reply_tx))
         .map_err(|_| "history db worker not available".to_string())?;
 
     reply_rx
         .recv()
-        .map_err(|_| "history db clear failed: worker disconnected".to_string())?
+        .map_err(|_| "history db operation failed: worker disconnected".to_string())?
+}
+
+/// Writes a consistent copy of the history DB to `dest` using SQLite's online backup API.
+pub fn backup_to(dest: PathBuf) -> Result<(), String> {
+    request_file_op(|reply| DbOp::Backup { dest, reply })
+}
+
+/// Replaces the live history DB with the snapshot at `src`.
+pub fn restore_from(src: PathBuf) -> Result<(), String> {
+    request_file_op(|reply| DbOp::Restore { src, reply })
 }
 
 pub fn load_recent_days(limit: usize) -> Result<Vec<DailyStats>, String> {
@@ -961,6 +3094,29 @@ LIMIT ?1
         out.push((date_key, day));
     }
 
+    hydrate_days(&conn, out)
+}
+
+/// Loads a single fully hydrated day from `conn` (e.g. an import staging database).
+pub fn load_day_from(conn: &Connection, date_key: &str) -> Result<Option<DailyStats>, String> {
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT payload_json FROM daily_stats WHERE date_key=?1",
+            params![date_key],
+            |row| row.get::<_, String>(0),
+        )
+        .optional()
+        .map_err(|e| format!("Failed to query daily_stats: {}", e))?;
+    let Some(json) = json else {
+        return Ok(None);
+    };
+    let day = serde_json::from_str::<DailyStats>(&json)
+        .map_err(|e| format!("Failed to parse daily_stats json: {}", e))?;
+    Ok(hydrate_days(conn, vec![(date_key.to_string(), day)])?.pop())
+}
+
+/// Fills the normalized per-day counters back into `DailyStats` payloads.
+fn hydrate_days(conn: &Connection, out: Vec<(String, DailyStats)>) -> Result<Vec<DailyStats>, String> {
     if out.is_empty() {
         return Ok(Vec::new());
     }
@@ -1078,7 +3234,7 @@ LIMIT ?1
     {
         let mut stmt = conn
             .prepare(
-                "SELECT date_key, hour, total, keyboard, mouse_single FROM daily_hourly WHERE date_key BETWEEN ?1 AND ?2",
+                "SELECT date_key, hour, total, keyboard, mouse_single, scroll FROM daily_hourly WHERE date_key BETWEEN ?1 AND ?2",
             )
             .map_err(|e| format!("Failed to prepare daily_hourly hydration query: {}", e))?;
         let rows = stmt
@@ -1089,11 +3245,12 @@ LIMIT ?1
                     row.get::<_, i64>(2)?,
                     row.get::<_, i64>(3)?,
                     row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
                 ))
             })
             .map_err(|e| format!("Failed to hydrate daily_hourly: {}", e))?;
         for row in rows {
-            let (date_key, hour, total, keyboard, mouse_single) =
+            let (date_key, hour, total, keyboard, mouse_single, scroll) =
                 row.map_err(|e| format!("Failed to read daily_hourly row: {}", e))?;
             let hour_usize = usize::try_from(hour).unwrap_or(0);
             if hour_usize >= 24 {
@@ -1106,6 +3263,7 @@ LIMIT ?1
             b.total = u64::try_from(total).unwrap_or(u64::MAX);
             b.keyboard = u64::try_from(keyboard).unwrap_or(u64::MAX);
             b.mouse_single = u64::try_from(mouse_single).unwrap_or(u64::MAX);
+            b.scroll = u64::try_from(scroll).unwrap_or(u64::MAX);
         }
     }
 
@@ -1114,7 +3272,7 @@ LIMIT ?1
     {
         let mut stmt = conn
             .prepare(
-                "SELECT date_key, app_id, name, keyboard, mouse_single FROM daily_app_input WHERE date_key BETWEEN ?1 AND ?2",
+                "SELECT date_key, app_id, name, keyboard, mouse_single, scroll, scroll_ticks FROM daily_app_input WHERE date_key BETWEEN ?1 AND ?2",
             )
             .map_err(|e| format!("Failed to prepare daily_app_input hydration query: {}", e))?;
         let rows = stmt
@@ -1125,15 +3283,19 @@ LIMIT ?1
                     row.get::<_, Option<String>>(2)?,
                     row.get::<_, i64>(3)?,
                     row.get::<_, i64>(4)?,
+                    row.get::<_, i64>(5)?,
+                    row.get::<_, i64>(6)?,
                 ))
             })
             .map_err(|e| format!("Failed to hydrate daily_app_input: {}", e))?;
         for row in rows {
-            let (date_key, app_id, name, keyboard, mouse_single) =
+            let (date_key, app_id, name, keyboard, mouse_single, scroll, scroll_ticks) =
                 row.map_err(|e| format!("Failed to read daily_app_input row: {}", e))?;
             let keyboard_u64 = u64::try_from(keyboard).unwrap_or(u64::MAX);
             let mouse_u64 = u64::try_from(mouse_single).unwrap_or(u64::MAX);
-            if keyboard_u64 == 0 && mouse_u64 == 0 {
+            let scroll_u64 = u64::try_from(scroll).unwrap_or(u64::MAX);
+            let scroll_ticks_u64 = u64::try_from(scroll_ticks).unwrap_or(u64::MAX);
+            if keyboard_u64 == 0 && mouse_u64 == 0 && scroll_u64 == 0 && scroll_ticks_u64 == 0 {
                 continue;
             }
             app_input_counts
@@ -1143,9 +3305,13 @@ LIMIT ?1
                     Arc::from(app_id),
                     crate::models::merit::AppInputStats {
                         name: name.map(Arc::from),
-                        total: keyboard_u64.saturating_add(mouse_u64),
+                        total: keyboard_u64
+                            .saturating_add(mouse_u64)
+                            .saturating_add(scroll_u64),
                         keyboard: keyboard_u64,
                         mouse_single: mouse_u64,
+                        scroll: scroll_u64,
+                        scroll_ticks: scroll_ticks_u64,
                     },
                 );
         }
@@ -1214,6 +3380,191 @@ LIMIT ?1
     Ok(out)
 }
 
+fn bucket_sql(granularity: StatisticsGranularity) -> &'static str {
+    match granularity {
+        StatisticsGranularity::Day => "date_key",
+        StatisticsGranularity::Week => {
+            "date(date_key, '-' || ((CAST(strftime('%w', date_key) AS INTEGER) + 6) % 7) || ' days')"
+        }
+        StatisticsGranularity::Month => "strftime('%Y-%m-01', date_key)",
+        StatisticsGranularity::Year => "strftime('%Y-01-01', date_key)",
+    }
+}
+
+const RANGE_FILTER_SQL: &str =
+    "(?1 IS NULL OR date_key >= ?1) AND (?2 IS NULL OR date_key <= ?2) AND (?3 IS NULL OR date_key <> ?3)";
+
+fn load_range_buckets_from(
+    conn: &Connection,
+    start_key: Option<&str>,
+    end_key: Option<&str>,
+    exclude_key: Option<&str>,
+    granularity: StatisticsGranularity,
+) -> Result<Vec<StatisticsBucket>, String> {
+    fn to_u64(v: i64) -> u64 {
+        u64::try_from(v).unwrap_or(0)
+    }
+
+    let bucket = bucket_sql(granularity);
+    let mut buckets: std::collections::BTreeMap<String, StatisticsBucket> =
+        std::collections::BTreeMap::new();
+
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+SELECT {bucket} AS bucket, COUNT(*), SUM(total), SUM(keyboard), SUM(mouse_single),
+  MIN(json_extract(payload_json, '$.first_event_at_ms')),
+  MAX(json_extract(payload_json, '$.last_event_at_ms')),
+  SUM(COALESCE(json_extract(payload_json, '$.mouse_move_distance_px'), 0)),
+  SUM(COALESCE(json_extract(payload_json, '$.scroll'), 0)),
+  SUM(COALESCE(json_extract(payload_json, '$.scroll_ticks'), 0)),
+  SUM(COALESCE(json_extract(payload_json, '$.scroll_distance_vertical'), 0)),
+  SUM(COALESCE(json_extract(payload_json, '$.scroll_distance_horizontal'), 0)),
+  SUM(COALESCE(json_extract(payload_json, '$.double_clicks'), 0)),
+  SUM(COALESCE(json_extract(payload_json, '$.drags'), 0)),
+  SUM(COALESCE(json_extract(payload_json, '$.drag_distance_px'), 0))
+FROM daily_stats
+WHERE {RANGE_FILTER_SQL}
+GROUP BY bucket
+ORDER BY bucket
+"#
+        ))
+        .map_err(|e| format!("Failed to prepare range bucket query: {}", e))?;
+    let rows = stmt
+        .query_map(params![start_key, end_key, exclude_key], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, Option<i64>>(5)?,
+                row.get::<_, Option<i64>>(6)?,
+                row.get::<_, i64>(7)?,
+                [
+                    row.get::<_, i64>(8)?,
+                    row.get::<_, i64>(9)?,
+                    row.get::<_, i64>(10)?,
+                    row.get::<_, i64>(11)?,
+                    row.get::<_, i64>(12)?,
+                    row.get::<_, i64>(13)?,
+                    row.get::<_, i64>(14)?,
+                ],
+            ))
+        })
+        .map_err(|e| format!("Failed to query range buckets: {}", e))?;
+    for row in rows {
+        let (key, days, total, keyboard, mouse_single, first, last, distance, activity) =
+            row.map_err(|e| format!("Failed to read range bucket row: {}", e))?;
+        let date = chrono::NaiveDate::parse_from_str(&key, "%Y-%m-%d")
+            .map_err(|e| format!("Invalid bucket date {}: {}", key, e))?;
+        let mut b = StatisticsBucket::new(granularity, date);
+        b.active_days = u32::try_from(days).unwrap_or(u32::MAX);
+        b.total = to_u64(total);
+        b.keyboard = to_u64(keyboard);
+        b.mouse_single = to_u64(mouse_single);
+        b.first_event_at_ms = first.map(to_u64);
+        b.last_event_at_ms = last.map(to_u64);
+        b.mouse_move_distance_px = to_u64(distance);
+        let [scroll, scroll_ticks, scroll_vertical, scroll_horizontal, double_clicks, drags, drag_distance] =
+            activity.map(to_u64);
+        b.scroll = scroll;
+        b.scroll_ticks = scroll_ticks;
+        b.scroll_distance_vertical = scroll_vertical;
+        b.scroll_distance_horizontal = scroll_horizontal;
+        b.double_clicks = double_clicks;
+        b.drags = drags;
+        b.drag_distance_px = drag_distance;
+        buckets.insert(key, b);
+    }
+    drop(stmt);
+
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+SELECT {bucket} AS bucket, d.key, SUM(d.value)
+FROM daily_stats, json_each(daily_stats.payload_json, '$.mouse_move_distance_px_by_display') AS d
+WHERE {RANGE_FILTER_SQL}
+GROUP BY bucket, d.key
+"#
+        ))
+        .map_err(|e| format!("Failed to prepare range distance query: {}", e))?;
+    let rows = stmt
+        .query_map(params![start_key, end_key, exclude_key], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to query range distance: {}", e))?;
+    for row in rows {
+        let (key, display, px) = row.map_err(|e| format!("Failed to read range distance row: {}", e))?;
+        if let Some(b) = buckets.get_mut(&key) {
+            b.mouse_move_distance_px_by_display.insert(display, to_u64(px));
+        }
+    }
+    drop(stmt);
+
+    let mut stmt = conn
+        .prepare(&format!(
+            r#"
+SELECT {bucket} AS bucket, hour, SUM(total), SUM(keyboard), SUM(mouse_single), SUM(scroll)
+FROM daily_hourly
+WHERE {RANGE_FILTER_SQL}
+GROUP BY bucket, hour
+"#
+        ))
+        .map_err(|e| format!("Failed to prepare range hourly query: {}", e))?;
+    let rows = stmt
+        .query_map(params![start_key, end_key, exclude_key], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, i64>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, i64>(3)?,
+                row.get::<_, i64>(4)?,
+                row.get::<_, i64>(5)?,
+            ))
+        })
+        .map_err(|e| format!("Failed to query range hourly: {}", e))?;
+    for row in rows {
+        let (key, hour, total, keyboard, mouse_single, scroll) =
+            row.map_err(|e| format!("Failed to read range hourly row: {}", e))?;
+        let Some(slot) = buckets
+            .get_mut(&key)
+            .and_then(|b| b.hourly.get_mut(usize::try_from(hour).unwrap_or(usize::MAX)))
+        else {
+            continue;
+        };
+        *slot = crate::models::merit::HourlyStats {
+            total: to_u64(total),
+            keyboard: to_u64(keyboard),
+            mouse_single: to_u64(mouse_single),
+            scroll: to_u64(scroll),
+        };
+    }
+
+    Ok(buckets.into_values().collect())
+}
+
+/// Rolls archived days in `[start_key, end_key]` up into buckets, oldest first. `exclude_key`
+/// skips a day the caller supplies itself (the live day).
+pub fn load_range_buckets(
+    start_key: Option<&str>,
+    end_key: Option<&str>,
+    exclude_key: Option<&str>,
+    granularity: StatisticsGranularity,
+) -> Result<Vec<StatisticsBucket>, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let conn = open_read_conn(&ctx.path)?;
+    load_range_buckets_from(&conn, start_key, end_key, exclude_key, granularity)
+}
+
 fn load_aggregate_key_counts(
     conn: &Connection,
     kind: i64,
@@ -1260,6 +3611,57 @@ fn load_aggregate_key_counts(
     Ok(out)
 }
 
+fn load_aggregate_key_bigrams(
+    conn: &Connection,
+    start_key: Option<&str>,
+    end_key: Option<&str>,
+) -> Result<Vec<KeyBigramCount>, String> {
+    fn map_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<(String, String, i64)> {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, i64>(2)?,
+        ))
+    }
+
+    let mut stmt = conn
+        .prepare(match (start_key, end_key) {
+            (Some(_), Some(_)) => "SELECT first_code, second_code, SUM(count) AS n FROM daily_key_bigrams WHERE date_key BETWEEN ?1 AND ?2 GROUP BY first_code, second_code ORDER BY n DESC",
+            (Some(_), None) => "SELECT first_code, second_code, SUM(count) AS n FROM daily_key_bigrams WHERE date_key >= ?1 GROUP BY first_code, second_code ORDER BY n DESC",
+            (None, Some(_)) => "SELECT first_code, second_code, SUM(count) AS n FROM daily_key_bigrams WHERE date_key <= ?1 GROUP BY first_code, second_code ORDER BY n DESC",
+            (None, None) => "SELECT first_code, second_code, SUM(count) AS n FROM daily_key_bigrams GROUP BY first_code, second_code ORDER BY n DESC",
+        })
+        .map_err(|e| format!("Failed to prepare key bigram aggregate query: {}", e))?;
+
+    let rows = match (start_key, end_key) {
+        (Some(a), Some(b)) => stmt
+            .query_map(params![a, b], map_row)
+            .map_err(|e| format!("Failed to query key bigram aggregate: {}", e))?,
+        (Some(a), None) | (None, Some(a)) => stmt
+            .query_map(params![a], map_row)
+            .map_err(|e| format!("Failed to query key bigram aggregate: {}", e))?,
+        (None, None) => stmt
+            .query_map([], map_row)
+            .map_err(|e| format!("Failed to query key bigram aggregate: {}", e))?,
+    };
+
+    let mut out = Vec::new();
+    for row in rows {
+        let (first, second, count) =
+            row.map_err(|e| format!("Failed to read key bigram aggregate row: {}", e))?;
+        let count_u64 = u64::try_from(count).unwrap_or(u64::MAX);
+        if count_u64 == 0 {
+            continue;
+        }
+        out.push(KeyBigramCount {
+            first: crate::core::key_codes::intern(&first),
+            second: crate::core::key_codes::intern(&second),
+            count: count_u64,
+        });
+    }
+    Ok(out)
+}
+
 fn load_aggregate_simple_counts(
     conn: &Connection,
     table: &str,
@@ -1317,22 +3719,23 @@ fn load_aggregate_hourly(
     start_key: Option<&str>,
     end_key: Option<&str>,
 ) -> Result<Vec<crate::models::merit::HourlyStats>, String> {
-    fn map_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<(i64, i64, i64, i64)> {
+    fn map_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<(i64, i64, i64, i64, i64)> {
         Ok((
             row.get::<_, i64>(0)?,
             row.get::<_, i64>(1)?,
             row.get::<_, i64>(2)?,
             row.get::<_, i64>(3)?,
+            row.get::<_, i64>(4)?,
         ))
     }
 
     let mut out = vec![crate::models::merit::HourlyStats::default(); 24];
     let mut stmt = conn
         .prepare(match (start_key, end_key) {
-            (Some(_), Some(_)) => "SELECT hour, SUM(total), SUM(keyboard), SUM(mouse_single) FROM daily_hourly WHERE date_key BETWEEN ?1 AND ?2 GROUP BY hour ORDER BY hour",
-            (Some(_), None) => "SELECT hour, SUM(total), SUM(keyboard), SUM(mouse_single) FROM daily_hourly WHERE date_key >= ?1 GROUP BY hour ORDER BY hour",
-            (None, Some(_)) => "SELECT hour, SUM(total), SUM(keyboard), SUM(mouse_single) FROM daily_hourly WHERE date_key <= ?1 GROUP BY hour ORDER BY hour",
-            (None, None) => "SELECT hour, SUM(total), SUM(keyboard), SUM(mouse_single) FROM daily_hourly GROUP BY hour ORDER BY hour",
+            (Some(_), Some(_)) => "SELECT hour, SUM(total), SUM(keyboard), SUM(mouse_single), SUM(scroll) FROM daily_hourly WHERE date_key BETWEEN ?1 AND ?2 GROUP BY hour ORDER BY hour",
+            (Some(_), None) => "SELECT hour, SUM(total), SUM(keyboard), SUM(mouse_single), SUM(scroll) FROM daily_hourly WHERE date_key >= ?1 GROUP BY hour ORDER BY hour",
+            (None, Some(_)) => "SELECT hour, SUM(total), SUM(keyboard), SUM(mouse_single), SUM(scroll) FROM daily_hourly WHERE date_key <= ?1 GROUP BY hour ORDER BY hour",
+            (None, None) => "SELECT hour, SUM(total), SUM(keyboard), SUM(mouse_single), SUM(scroll) FROM daily_hourly GROUP BY hour ORDER BY hour",
         })
         .map_err(|e| format!("Failed to prepare hourly aggregate query: {}", e))?;
     let rows = match (start_key, end_key) {
@@ -1348,7 +3751,7 @@ fn load_aggregate_hourly(
     };
 
     for row in rows {
-        let (hour, total, keyboard, mouse_single) =
+        let (hour, total, keyboard, mouse_single, scroll) =
             row.map_err(|e| format!("Failed to read hourly aggregate row: {}", e))?;
         let idx = usize::try_from(hour).unwrap_or(0);
         if idx >= 24 {
@@ -1358,6 +3761,7 @@ fn load_aggregate_hourly(
             total: u64::try_from(total).unwrap_or(u64::MAX),
             keyboard: u64::try_from(keyboard).unwrap_or(u64::MAX),
             mouse_single: u64::try_from(mouse_single).unwrap_or(u64::MAX),
+            scroll: u64::try_from(scroll).unwrap_or(u64::MAX),
         };
     }
 
@@ -1369,40 +3773,47 @@ fn load_aggregate_app_input(
     start_key: Option<&str>,
     end_key: Option<&str>,
 ) -> Result<HashMap<Arc<str>, crate::models::merit::AppInputStats>, String> {
-    fn map_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<(String, Option<String>, i64, i64)> {
+    type AppRow = (String, Option<String>, i64, i64, i64, i64);
+    fn map_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<AppRow> {
         Ok((
             row.get::<_, String>(0)?,
             row.get::<_, Option<String>>(1)?,
             row.get::<_, i64>(2)?,
             row.get::<_, i64>(3)?,
+            row.get::<_, i64>(4)?,
+            row.get::<_, i64>(5)?,
         ))
     }
 
     let mut stmt = conn
         .prepare(match (start_key, end_key) {
             (Some(_), Some(_)) => r#"
-SELECT a.app_id, COALESCE(MAX(a.name), m.last_name) AS name, SUM(a.keyboard), SUM(a.mouse_single)
+SELECT a.app_id, COALESCE(MAX(a.name), m.last_name) AS name, SUM(a.keyboard), SUM(a.mouse_single),
+  SUM(a.scroll), SUM(a.scroll_ticks)
 FROM daily_app_input a
 LEFT JOIN app_meta m ON m.app_id = a.app_id
 WHERE a.date_key BETWEEN ?1 AND ?2
 GROUP BY a.app_id
 "#,
             (Some(_), None) => r#"
-SELECT a.app_id, COALESCE(MAX(a.name), m.last_name) AS name, SUM(a.keyboard), SUM(a.mouse_single)
+SELECT a.app_id, COALESCE(MAX(a.name), m.last_name) AS name, SUM(a.keyboard), SUM(a.mouse_single),
+  SUM(a.scroll), SUM(a.scroll_ticks)
 FROM daily_app_input a
 LEFT JOIN app_meta m ON m.app_id = a.app_id
 WHERE a.date_key >= ?1
 GROUP BY a.app_id
 "#,
             (None, Some(_)) => r#"
-SELECT a.app_id, COALESCE(MAX(a.name), m.last_name) AS name, SUM(a.keyboard), SUM(a.mouse_single)
+SELECT a.app_id, COALESCE(MAX(a.name), m.last_name) AS name, SUM(a.keyboard), SUM(a.mouse_single),
+  SUM(a.scroll), SUM(a.scroll_ticks)
 FROM daily_app_input a
 LEFT JOIN app_meta m ON m.app_id = a.app_id
 WHERE a.date_key <= ?1
 GROUP BY a.app_id
 "#,
             (None, None) => r#"
-SELECT a.app_id, COALESCE(MAX(a.name), m.last_name) AS name, SUM(a.keyboard), SUM(a.mouse_single)
+SELECT a.app_id, COALESCE(MAX(a.name), m.last_name) AS name, SUM(a.keyboard), SUM(a.mouse_single),
+  SUM(a.scroll), SUM(a.scroll_ticks)
 FROM daily_app_input a
 LEFT JOIN app_meta m ON m.app_id = a.app_id
 GROUP BY a.app_id
@@ -1424,20 +3835,26 @@ GROUP BY a.app_id
 
     let mut out: HashMap<Arc<str>, crate::models::merit::AppInputStats> = HashMap::new();
     for row in rows {
-        let (app_id, name, keyboard, mouse_single) =
+        let (app_id, name, keyboard, mouse_single, scroll, scroll_ticks) =
             row.map_err(|e| format!("Failed to read app input aggregate row: {}", e))?;
         let keyboard_u64 = u64::try_from(keyboard).unwrap_or(u64::MAX);
         let mouse_u64 = u64::try_from(mouse_single).unwrap_or(u64::MAX);
-        if keyboard_u64 == 0 && mouse_u64 == 0 {
+        let scroll_u64 = u64::try_from(scroll).unwrap_or(u64::MAX);
+        let scroll_ticks_u64 = u64::try_from(scroll_ticks).unwrap_or(u64::MAX);
+        if keyboard_u64 == 0 && mouse_u64 == 0 && scroll_u64 == 0 && scroll_ticks_u64 == 0 {
             continue;
         }
         out.insert(
             Arc::<str>::from(app_id),
             crate::models::merit::AppInputStats {
                 name: name.map(Arc::<str>::from),
-                total: keyboard_u64.saturating_add(mouse_u64),
+                total: keyboard_u64
+                    .saturating_add(mouse_u64)
+                    .saturating_add(scroll_u64),
                 keyboard: keyboard_u64,
                 mouse_single: mouse_u64,
+                scroll: scroll_u64,
+                scroll_ticks: scroll_ticks_u64,
             },
         );
     }
@@ -1477,6 +3894,63 @@ pub fn load_statistics_aggregates(
     })
 }
 
+/// Bigram counts over a date range, most frequent first.
+pub fn load_key_bigrams(
+    start_key: Option<&str>,
+    end_key: Option<&str>,
+) -> Result<Vec<KeyBigramCount>, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let conn = open_read_conn(&ctx.path)?;
+    load_aggregate_key_bigrams(&conn, start_key, end_key)
+}
+
+/// Tables included in full data exports and merged by imports.
+pub const EXPORT_TABLES: &[&str] = &[
+    "daily_stats",
+    "daily_key_counts",
+    "daily_shortcut_counts",
+    "daily_mouse_button_counts",
+    "daily_hourly",
+    "daily_app_input",
+    "app_meta",
+    "click_heatmap_total_cells",
+    "click_heatmap_total_meta",
+    "click_heatmap_daily_cells",
+    "click_heatmap_daily_meta",
+    "move_heatmap_total_cells",
+    "move_heatmap_total_meta",
+    "move_heatmap_daily_cells",
+    "move_heatmap_daily_meta",
+    "activity_timeline",
+    "sessions",
+    "daily_typing_speed",
+    "daily_corrections",
+    "daily_key_bigrams",
+];
+
+/// Runs `f` against a read-only connection inside a single read transaction, so every query sees
+/// the same snapshot even while the DB worker keeps writing.
+pub fn with_read_snapshot<T>(
+    f: impl FnOnce(&Connection, i64) -> Result<T, String>,
+) -> Result<T, String> {
+    let ctx = CTX
+        .lock()
+        .clone()
+        .ok_or_else(|| "history db not initialized".to_string())?;
+
+    let conn = open_read_conn(&ctx.path)?;
+    conn.execute_batch("BEGIN")
+        .map_err(|e| format!("Failed to start read snapshot: {}", e))?;
+    let schema_version = read_schema_version(&conn)?;
+    let res = f(&conn, schema_version);
+    let _ = conn.execute_batch("COMMIT");
+    res
+}
+
 pub fn load_total_merit_all_time() -> Result<u64, String> {
     let ctx = CTX
         .lock()
@@ -1492,7 +3966,8 @@ pub fn load_total_merit_all_time() -> Result<u64, String> {
     Ok(u64::try_from(sum).unwrap_or(u64::MAX))
 }
 
-pub fn load_click_heatmap_base(
+pub fn load_heatmap_base(
+    kind: HeatmapKind,
     display_id: &str,
     date_key: Option<&str>,
 ) -> Result<(Vec<(u32, u32)>, u64), String> {
@@ -1507,11 +3982,15 @@ pub fn load_click_heatmap_base(
     }
 
     let conn = open_read_conn(&ctx.path)?;
+    let tables = heatmap_tables(kind);
 
     let total_clicks: u64 = match date_key {
         Some(date_key) => conn
             .query_row(
-                "SELECT total_clicks FROM click_heatmap_daily_meta WHERE date_key=?1 AND display_id=?2",
+                &format!(
+                    "SELECT total_clicks FROM {} WHERE date_key=?1 AND display_id=?2",
+                    tables.daily_meta
+                ),
                 params![date_key, display_id],
                 |row| row.get::<_, i64>(0),
             )
@@ -1520,7 +3999,7 @@ pub fn load_click_heatmap_base(
             .unwrap_or(0),
         None => conn
             .query_row(
-                "SELECT total_clicks FROM click_heatmap_total_meta WHERE display_id=?1",
+                &format!("SELECT total_clicks FROM {} WHERE display_id=?1", tables.total_meta),
                 params![display_id],
                 |row| row.get::<_, i64>(0),
             )
@@ -1529,53 +4008,507 @@ pub fn load_click_heatmap_base(
             .unwrap_or(0),
     };
 
+    let (table, sql) = match date_key {
+        Some(_) => (
+            tables.daily_cells,
+            format!(
+                "SELECT idx, count FROM {} WHERE date_key=?2 AND display_id=?1",
+                tables.daily_cells
+            ),
+        ),
+        None => (
+            tables.total_cells,
+            format!("SELECT idx, count FROM {} WHERE display_id=?1", tables.total_cells),
+        ),
+    };
+    let mut stmt = conn
+        .prepare(&sql)
+        .map_err(|e| format!("Failed to prepare {} query: {}", table, e))?;
+    let map_row = |row: &rusqlite::Row<'_>| -> rusqlite::Result<(i64, i64)> {
+        Ok((row.get(0)?, row.get(1)?))
+    };
+    let rows = match date_key {
+        Some(date_key) => stmt.query_map(params![display_id, date_key], map_row),
+        None => stmt.query_map(params![display_id], map_row),
+    }
+    .map_err(|e| format!("Failed to query {}: {}", table, e))?;
+
     let mut out: Vec<(u32, u32)> = Vec::new();
-    match date_key {
-        Some(date_key) => {
-            let mut stmt = conn
-                .prepare(
-                    "SELECT idx, count FROM click_heatmap_daily_cells WHERE date_key=?1 AND display_id=?2",
-                )
-                .map_err(|e| format!("Failed to prepare click_heatmap_daily_cells query: {}", e))?;
-            let rows = stmt
-                .query_map(params![date_key, display_id], |row| {
-                    let idx: i64 = row.get(0)?;
-                    let count: i64 = row.get(1)?;
-                    Ok((idx, count))
-                })
-                .map_err(|e| format!("Failed to query click_heatmap_daily_cells: {}", e))?;
-            for row in rows {
-                let (idx, count) = row.map_err(|e| format!("Failed to read heatmap row: {}", e))?;
-                let idx = u32::try_from(idx).unwrap_or(0);
-                let count_u32 = u32::try_from(count).unwrap_or(u32::MAX);
-                if count_u32 == 0 {
-                    continue;
-                }
-                out.push((idx, count_u32));
-            }
-        }
-        None => {
-            let mut stmt = conn
-                .prepare("SELECT idx, count FROM click_heatmap_total_cells WHERE display_id=?1")
-                .map_err(|e| format!("Failed to prepare click_heatmap_total_cells query: {}", e))?;
-            let rows = stmt
-                .query_map(params![display_id], |row| {
-                    let idx: i64 = row.get(0)?;
-                    let count: i64 = row.get(1)?;
-                    Ok((idx, count))
-                })
-                .map_err(|e| format!("Failed to query click_heatmap_total_cells: {}", e))?;
-            for row in rows {
-                let (idx, count) = row.map_err(|e| format!("Failed to read heatmap row: {}", e))?;
-                let idx = u32::try_from(idx).unwrap_or(0);
-                let count_u32 = u32::try_from(count).unwrap_or(u32::MAX);
-                if count_u32 == 0 {
-                    continue;
-                }
-                out.push((idx, count_u32));
-            }
+    for row in rows {
+        let (idx, count) = row.map_err(|e| format!("Failed to read heatmap row: {}", e))?;
+        let idx = u32::try_from(idx).unwrap_or(0);
+        let count_u32 = u32::try_from(count).unwrap_or(u32::MAX);
+        if count_u32 == 0 {
+            continue;
         }
+        out.push((idx, count_u32));
     }
 
     Ok((out, total_clicks))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table_exists(conn: &Connection, name: &str) -> bool {
+        conn.query_row(
+            "SELECT 1 FROM sqlite_master WHERE type='table' AND name=?1",
+            params![name],
+            |_| Ok(()),
+        )
+        .optional()
+        .unwrap()
+        .is_some()
+    }
+
+    fn count_rows(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get::<_, i64>(0)).unwrap()
+    }
+
+    /// Layout written by the earliest SQLite builds: heavy counters still inline in `payload_json`.
+    fn fixture_inline_payload() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            r#"
+CREATE TABLE schema_meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);
+CREATE TABLE daily_stats (
+  date_key TEXT PRIMARY KEY,
+  total INTEGER NOT NULL,
+  keyboard INTEGER NOT NULL,
+  mouse_single INTEGER NOT NULL,
+  payload_json TEXT NOT NULL,
+  updated_at_ms INTEGER NOT NULL
+);
+"#,
+        )
+        .unwrap();
+        let payload = serde_json::json!({
+            "date": "2024-03-01",
+            "total": 5,
+            "keyboard": 4,
+            "mouse_single": 1,
+            "key_counts": { "KeyA": 3, "KeyB": 1 },
+            "shortcut_counts": { "Ctrl+KeyC": 1 },
+            "mouse_button_counts": { "MouseLeft": 1 },
+            "hourly": [{}, {}, {}, {}, {}, {}, {}, {}, {}, { "total": 5, "keyboard": 4, "mouse_single": 1 }],
+            "app_input_counts": { "/usr/bin/editor": { "name": "Editor", "keyboard": 4, "mouse_single": 1 } }
+        });
+        conn.execute(
+            "INSERT INTO daily_stats VALUES ('2024-03-01', 5, 4, 1, ?1, 0)",
+            params![payload.to_string()],
+        )
+        .unwrap();
+        conn
+    }
+
+    /// Layout written after counters were normalized but before `schema_version` existed.
+    fn fixture_normalized_unversioned() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "CREATE TABLE schema_meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);",
+        )
+        .unwrap();
+        migrate_v1_base_schema(&conn).unwrap();
+        conn.execute_batch(
+            r#"
+INSERT INTO schema_meta VALUES ('daily_counters_normalized_v2', '1');
+INSERT INTO schema_meta VALUES ('legacy_click_heatmap_migrated_v1', '1');
+INSERT INTO daily_stats VALUES ('2024-03-02', 2, 2, 0, '{"date":"2024-03-02","total":2,"keyboard":2}', 0);
+INSERT INTO daily_key_counts VALUES ('2024-03-02', 0, 'KeyZ', 2);
+"#,
+        )
+        .unwrap();
+        conn
+    }
+
+    #[test]
+    fn migrations_are_ordered_and_end_at_schema_version() {
+        let mut prev = 0;
+        for &(version, _) in MIGRATIONS {
+            assert!(version > prev, "migration versions must be strictly increasing");
+            prev = version;
+        }
+        assert_eq!(prev, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn fresh_database_migrates_to_latest() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(read_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        for table in [
+            "daily_stats",
+            "daily_key_counts",
+            "daily_shortcut_counts",
+            "daily_mouse_button_counts",
+            "daily_hourly",
+            "daily_app_input",
+            "app_meta",
+            "click_heatmap_total_cells",
+            "click_heatmap_daily_cells",
+            "click_heatmap_total_meta",
+            "click_heatmap_daily_meta",
+            "move_heatmap_total_cells",
+            "move_heatmap_daily_meta",
+            "import_log",
+        ] {
+            assert!(table_exists(&conn, table), "missing table {}", table);
+        }
+    }
+
+    #[test]
+    fn inline_payload_layout_is_normalized() {
+        let mut conn = fixture_inline_payload();
+        migrate(&mut conn).unwrap();
+        assert_eq!(read_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+
+        assert_eq!(
+            count_rows(
+                &conn,
+                "SELECT count FROM daily_key_counts WHERE date_key='2024-03-01' AND kind=0 AND code='KeyA'"
+            ),
+            3
+        );
+        assert_eq!(
+            count_rows(&conn, "SELECT count FROM daily_shortcut_counts WHERE shortcut='Ctrl+KeyC'"),
+            1
+        );
+        assert_eq!(
+            count_rows(&conn, "SELECT keyboard FROM daily_app_input WHERE app_id='/usr/bin/editor'"),
+            4
+        );
+        assert_eq!(
+            count_rows(&conn, "SELECT total FROM daily_hourly WHERE date_key='2024-03-01' AND hour=9"),
+            5
+        );
+
+        let payload: String = conn
+            .query_row("SELECT payload_json FROM daily_stats WHERE date_key='2024-03-01'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        let day: DailyStats = serde_json::from_str(&payload).unwrap();
+        assert!(day.key_counts.is_empty());
+        assert!(day.app_input_counts.is_empty());
+        assert_eq!(day.total, 5);
+    }
+
+    #[test]
+    fn normalized_unversioned_layout_keeps_existing_rows() {
+        let mut conn = fixture_normalized_unversioned();
+        migrate(&mut conn).unwrap();
+        assert_eq!(read_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        // The payload has no key counts; re-normalizing would have wiped this row.
+        assert_eq!(
+            count_rows(&conn, "SELECT count FROM daily_key_counts WHERE code='KeyZ'"),
+            2
+        );
+    }
+
+    #[test]
+    fn migrate_is_idempotent() {
+        let mut conn = fixture_inline_payload();
+        migrate(&mut conn).unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(read_schema_version(&conn).unwrap(), SCHEMA_VERSION);
+        assert_eq!(count_rows(&conn, "SELECT COUNT(*) FROM daily_key_counts WHERE kind=0"), 2);
+    }
+
+    #[test]
+    fn newer_schema_version_is_refused() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        write_schema_version(&conn, SCHEMA_VERSION + 1).unwrap();
+        let err = migrate(&mut conn).unwrap_err();
+        assert!(err.contains("newer"), "{}", err);
+    }
+
+    fn merge_fixture_day(date: &str, key_a: u64) -> DailyStats {
+        let date = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d").unwrap();
+        let mut day = DailyStats::new(date);
+        day.add_merit(crate::models::InputSource::Keyboard, key_a, 1_000);
+        day.add_key_counts(&HashMap::from([(Arc::<str>::from("KeyA"), key_a)]));
+        day
+    }
+
+    fn merge_fixture(dir: &Path, name: &str, days: &[DailyStats]) -> (PathBuf, Connection) {
+        let path = dir.join(name);
+        let conn = open_staging_db(&path).unwrap();
+        for day in days {
+            upsert_daily(&conn, day).unwrap();
+        }
+        (path, conn)
+    }
+
+    fn merge_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("cyber-zen-{}-{}", name, now_ms()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn merge_import_sums_counters_and_skips_live_day() {
+        let dir = merge_dir("merge-import");
+        let (_, mut local) = merge_fixture(
+            &dir,
+            "local.sqlite3",
+            &[merge_fixture_day("2024-03-01", 3)],
+        );
+        let (import_path, import) = merge_fixture(
+            &dir,
+            "import.sqlite3",
+            &[
+                merge_fixture_day("2024-03-01", 2),
+                merge_fixture_day("2024-03-02", 4),
+                merge_fixture_day("2024-03-03", 7),
+            ],
+        );
+        drop(import);
+
+        let report =
+            merge_import(&mut local, &import_path, Some("2024-03-03"), None, false).unwrap();
+        assert_eq!(report.conflicting_days, ["2024-03-01"]);
+        assert_eq!(report.imported_total, 13);
+        let daily = report.tables.iter().find(|t| t.table == "daily_stats").unwrap();
+        assert_eq!((daily.new_rows, daily.merged_rows, daily.count_delta), (1, 1, 6));
+
+        assert_eq!(count_rows(&local, "SELECT total FROM daily_stats WHERE date_key='2024-03-01'"), 5);
+        assert_eq!(
+            count_rows(&local, "SELECT count FROM daily_key_counts WHERE date_key='2024-03-01' AND kind=0"),
+            5
+        );
+        assert_eq!(count_rows(&local, "SELECT COUNT(*) FROM daily_stats WHERE date_key='2024-03-03'"), 0);
+        assert_eq!(
+            count_rows(&local, "SELECT COUNT(*) FROM daily_key_counts WHERE date_key='2024-03-03'"),
+            0
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn merge_import_dry_run_leaves_db_untouched() {
+        let dir = merge_dir("merge-import-dry");
+        let (_, mut local) = merge_fixture(
+            &dir,
+            "local.sqlite3",
+            &[merge_fixture_day("2024-03-01", 3)],
+        );
+        let (import_path, import) = merge_fixture(
+            &dir,
+            "import.sqlite3",
+            &[merge_fixture_day("2024-03-01", 2)],
+        );
+        drop(import);
+
+        let report = merge_import(&mut local, &import_path, None, None, true).unwrap();
+        assert_eq!(report.conflicting_days, ["2024-03-01"]);
+        let keys = report.tables.iter().find(|t| t.table == "daily_key_counts").unwrap();
+        assert_eq!((keys.merged_rows, keys.count_delta), (2, 4));
+        assert_eq!(count_rows(&local, "SELECT total FROM daily_stats WHERE date_key='2024-03-01'"), 3);
+        assert_eq!(
+            count_rows(&local, "SELECT count FROM daily_key_counts WHERE date_key='2024-03-01' AND kind=0"),
+            3
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn repeat_import_of_a_source_is_refused() {
+        let dir = merge_dir("merge-import-repeat");
+        let (_, mut local) = merge_fixture(&dir, "local.sqlite3", &[]);
+        let (import_path, import) = merge_fixture(
+            &dir,
+            "import.sqlite3",
+            &[merge_fixture_day("2024-03-01", 2)],
+        );
+        drop(import);
+        let source = ImportSource {
+            id: "fnv1a64:0123".to_string(),
+            exported_at_ms: Some(1_700_000_000_000),
+        };
+
+        let report = merge_import(&mut local, &import_path, None, Some(&source), false).unwrap();
+        assert_eq!(report.already_imported_at_ms, None);
+        let report = merge_import(&mut local, &import_path, None, Some(&source), true).unwrap();
+        assert!(report.already_imported_at_ms.is_some());
+        let err = merge_import(&mut local, &import_path, None, Some(&source), false).unwrap_err();
+        assert!(err.contains("already imported"), "{}", err);
+        assert_eq!(count_rows(&local, "SELECT total FROM daily_stats WHERE date_key='2024-03-01'"), 2);
+
+        // Clearing history wipes what the import brought in, so the same data may come back.
+        clear_daily(&mut local).unwrap();
+        let report = merge_import(&mut local, &import_path, None, Some(&source), false).unwrap();
+        assert_eq!(report.already_imported_at_ms, None);
+        assert_eq!(count_rows(&local, "SELECT total FROM daily_stats WHERE date_key='2024-03-01'"), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn range_buckets_roll_up_by_week_and_skip_excluded_day() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        // 2024-02-29 is a Thursday; 2024-03-04 starts the next ISO week.
+        for (date, n) in [("2024-02-26", 1), ("2024-02-29", 2), ("2024-03-04", 4), ("2024-03-05", 8)] {
+            let mut day = merge_fixture_day(date, n);
+            day.hourly = vec![crate::models::merit::HourlyStats::default(); 24];
+            day.hourly[9] = crate::models::merit::HourlyStats {
+                total: n,
+                keyboard: n,
+                ..Default::default()
+            };
+            day.add_mouse_move_distance_px(10);
+            day.mouse_move_distance_px_by_display.insert("d1".to_string(), 10);
+            upsert_daily(&conn, &day).unwrap();
+        }
+
+        let buckets = load_range_buckets_from(
+            &conn,
+            Some("2024-02-27"),
+            None,
+            Some("2024-03-05"),
+            StatisticsGranularity::Week,
+        )
+        .unwrap();
+        let summary: Vec<_> = buckets
+            .iter()
+            .map(|b| (b.start.to_string(), b.end.to_string(), b.active_days, b.total))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("2024-02-26".to_string(), "2024-03-03".to_string(), 1, 2),
+                ("2024-03-04".to_string(), "2024-03-10".to_string(), 1, 4),
+            ]
+        );
+        assert_eq!(buckets[0].mouse_move_distance_px, 10);
+        assert_eq!(buckets[0].mouse_move_distance_px_by_display.get("d1"), Some(&10));
+        assert_eq!(buckets[1].hourly[9].keyboard, 4);
+    }
+
+    #[test]
+    fn timeline_batches_accumulate_per_bucket() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        let key: Arc<str> = Arc::from("2024-03-01");
+        let delta = TimelineDelta {
+            keyboard: 3,
+            mouse_single: 1,
+            distance_px: 40,
+        };
+        for _ in 0..2 {
+            let batch = HashMap::from([((Arc::clone(&key), 545), delta), ((Arc::clone(&key), 540), delta)]);
+            apply_timeline_batch(&mut conn, batch).unwrap();
+        }
+
+        let buckets = load_timeline_from(&conn, "2024-03-01").unwrap();
+        let rows: Vec<_> = buckets
+            .iter()
+            .map(|b| (b.minute, b.keyboard, b.mouse_single, b.distance_px))
+            .collect();
+        assert_eq!(rows, [(540, 6, 2, 80), (545, 6, 2, 80)]);
+        assert!(load_timeline_from(&conn, "2024-03-02").unwrap().is_empty());
+    }
+
+    #[test]
+    fn session_stats_measure_breaks_within_a_day() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        let session = |start_ms: u64, end_ms: u64, date_key: &str| SessionRecord {
+            start_ms,
+            end_ms,
+            date_key: date_key.to_string(),
+            keyboard: 10,
+            ..Default::default()
+        };
+        for record in [
+            session(1_000, 61_000, "2024-03-01"),
+            session(361_000, 481_000, "2024-03-01"),
+            session(9_000_000, 9_030_000, "2024-03-02"),
+        ] {
+            insert_session(&conn, &record).unwrap();
+        }
+
+        assert_eq!(load_sessions_from(&conn, "2024-03-01").unwrap().len(), 2);
+        let stats = load_session_stats_from(&conn, None, None).unwrap();
+        assert_eq!(stats.sessions, 3);
+        assert_eq!(stats.active_ms, 210_000);
+        assert_eq!(stats.longest.map(|s| s.start_ms), Some(361_000));
+        // The day boundary is not a break.
+        assert_eq!((stats.breaks, stats.average_break_ms), (1, 300_000));
+    }
+
+    #[test]
+    fn prune_respects_per_table_retention() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        for date_key in ["2024-01-01", "2024-03-01"] {
+            let delta = TimelineDelta {
+                keyboard: 1,
+                ..Default::default()
+            };
+            apply_timeline_batch(&mut conn, HashMap::from([((Arc::from(date_key), 0), delta)]))
+                .unwrap();
+            conn.execute(
+                "INSERT INTO click_heatmap_daily_cells(date_key, display_id, idx, count) VALUES (?1, 'd', 0, 1)",
+                params![date_key],
+            )
+            .unwrap();
+        }
+
+        let policy = RetentionPolicy {
+            click_heatmap_days: 10,
+            ..Default::default()
+        };
+        let today = chrono::NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        let deleted = prune_expired(&mut conn, &policy, today).unwrap();
+        let deleted: Vec<_> = deleted.iter().map(|d| (d.table.as_str(), d.rows)).collect();
+        assert_eq!(deleted, [("click_heatmap_daily_cells", 1)]);
+
+        let rows: HashMap<_, _> = load_db_stats_from(&conn)
+            .unwrap()
+            .into_iter()
+            .map(|t| (t.table, t.rows))
+            .collect();
+        assert_eq!(rows["click_heatmap_daily_cells"], 1);
+        assert_eq!(rows["activity_timeline"], 2);
+    }
+
+    #[test]
+    fn scrub_reduces_key_details_to_categories_then_totals() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        let mut day = merge_fixture_day("2024-03-01", 3);
+        day.add_key_counts(&HashMap::from([
+            (Arc::<str>::from("KeyB"), 2),
+            (Arc::<str>::from("Digit1"), 1),
+        ]));
+        day.add_shortcut_counts(&HashMap::from([(Arc::<str>::from("Ctrl+KeyC"), 1)]));
+        upsert_daily(&conn, &day).unwrap();
+
+        let scrubbed = scrub_key_details(&mut conn, KeyPrivacyLevel::Categories).unwrap();
+        let scrubbed: Vec<_> = scrubbed.iter().map(|d| (d.table.as_str(), d.rows)).collect();
+        assert_eq!(scrubbed, [("daily_key_counts", 6), ("daily_shortcut_counts", 1)]);
+        let letters: i64 = conn
+            .query_row(
+                "SELECT count FROM daily_key_counts WHERE kind=0 AND code='cat:letter'",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap();
+        assert_eq!(letters, 5);
+        assert_eq!(count_rows(&conn, "SELECT COUNT(*) FROM daily_key_counts WHERE kind=0"), 2);
+        assert_eq!(
+            count_rows(&conn, "SELECT COUNT(*) FROM daily_shortcut_counts WHERE shortcut='Ctrl+cat:letter'"),
+            1
+        );
+        assert!(scrub_key_details(&mut conn, KeyPrivacyLevel::Categories)
+            .unwrap()
+            .is_empty());
+
+        scrub_key_details(&mut conn, KeyPrivacyLevel::TotalsOnly).unwrap();
+        assert_eq!(count_rows(&conn, "SELECT COUNT(*) FROM daily_key_counts"), 0);
+        assert_eq!(count_rows(&conn, "SELECT COUNT(*) FROM daily_shortcut_counts"), 0);
+        assert_eq!(count_rows(&conn, "SELECT COUNT(*) FROM daily_stats"), 1);
+    }
+}
@@ -9,16 +9,20 @@ use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
-#[cfg(target_os = "macos")]
+#[cfg(any(target_os = "macos", target_os = "linux"))]
 use std::sync::mpsc;
 use std::thread;
 use tauri::{AppHandle, Emitter};
 
 use crate::core::key_codes;
+use crate::core::key_bigrams;
 use crate::core::keyboard_piano;
 use crate::core::click_heatmap;
 use crate::core::main_window_bounds;
 use crate::core::mouse_distance;
+use crate::core::mouse_gestures;
+use crate::core::mouse_scroll;
+use crate::core::typing_accuracy;
 use crate::core::merit_batcher::enqueue_merit_trigger;
 use crate::core::MeritStorage;
 use crate::core::{intern, perf};
@@ -62,6 +66,22 @@ impl ModState {
     fn meta(&self) -> bool {
         self.meta_left || self.meta_right
     }
+
+    /// Tracks modifiers by canonical code; Caps Lock toggles on press.
+    fn apply(&mut self, code: &str, pressed: bool) {
+        match code {
+            "ShiftLeft" => self.shift_left = pressed,
+            "ShiftRight" => self.shift_right = pressed,
+            "ControlLeft" => self.ctrl_left = pressed,
+            "ControlRight" => self.ctrl_right = pressed,
+            "AltLeft" => self.alt_left = pressed,
+            "AltRight" => self.alt_right = pressed,
+            "MetaLeft" => self.meta_left = pressed,
+            "MetaRight" => self.meta_right = pressed,
+            "CapsLock" if pressed => self.caps_lock = !self.caps_lock,
+            _ => {}
+        }
+    }
 }
 
 #[cfg(not(target_os = "macos"))]
@@ -153,6 +173,8 @@ fn shortcut_id(meta: bool, ctrl: bool, alt: bool, shift: bool, code: &str) -> Ar
 #[serde(rename_all = "snake_case")]
 pub enum InputListenerErrorCode {
     PermissionRequired,
+    /// Linux: `/dev/input` is not readable; the user must join the `input` group.
+    InputGroupRequired,
     ListenFailed,
 }
 
@@ -173,6 +195,22 @@ fn should_suppress_mouse_press() -> bool {
     now_ms() < SUPPRESS_MOUSE_UNTIL_MS.load(Ordering::SeqCst)
 }
 
+#[cfg(target_os = "macos")]
+fn macos_button_code(button: crate::core::macos_event_tap::RawMouseButton) -> Arc<str> {
+    use crate::core::macos_event_tap::RawMouseButton;
+    match button {
+        RawMouseButton::Left => key_codes::intern("MouseLeft"),
+        RawMouseButton::Right => key_codes::intern("MouseRight"),
+        RawMouseButton::Other(number) => key_codes::from_macos_mouse_button(number),
+    }
+}
+
+fn mouse_button_counted(code: &str) -> bool {
+    let storage = MeritStorage::instance();
+    let storage = storage.read();
+    storage.mouse_button_counted(code)
+}
+
 fn should_ignore_global_mouse_click(
     app_handle: &AppHandle,
     space: click_heatmap::CoordinateSpace,
@@ -196,6 +234,165 @@ fn should_ignore_global_mouse_click(
     main_window_bounds::contains_point(space, x, y)
 }
 
+fn report_error(app_handle: &AppHandle, err: InputListenerError) {
+    *LAST_ERROR.write() = Some(err.clone());
+    let _ = app_handle.emit("input-listener-error", err);
+    THREAD_STARTED.store(false, Ordering::SeqCst);
+}
+
+/// Counts a key press from a backend that reports raw key transitions (rdev, evdev).
+#[cfg(not(target_os = "macos"))]
+fn handle_key_press(app_handle: &AppHandle, code: Arc<str>, repeat: bool) {
+    perf::inc_input_key();
+
+    let snapshot = {
+        let mut state = MOD_STATE.lock();
+        if !repeat {
+            state.apply(code.as_ref(), true);
+        }
+        *state
+    };
+    keyboard_piano::emit_key(app_handle, code.as_ref());
+
+    let shift_down = snapshot.shift();
+    let is_shifted = effective_shifted(code.as_ref(), shift_down, snapshot.caps_lock);
+    let shortcut = if (snapshot.ctrl() || snapshot.alt() || snapshot.meta())
+        && !is_modifier_code(code.as_ref())
+    {
+        Some(shortcut_id(
+            snapshot.meta(),
+            snapshot.ctrl(),
+            snapshot.alt(),
+            shift_down,
+            code.as_ref(),
+        ))
+    } else {
+        None
+    };
+
+    let app = active_app::current_or_unknown();
+    typing_accuracy::record_key(code.as_ref(), shortcut.is_some(), &app);
+    if !repeat {
+        key_bigrams::record_key(&code, shortcut.is_some(), &app);
+    }
+    enqueue_merit_trigger(
+        app_handle.clone(),
+        InputOrigin::Global,
+        InputSource::Keyboard,
+        1u64,
+        Some(code),
+        Some(is_shifted),
+        shortcut,
+        Some(app),
+    );
+}
+
+#[cfg(not(target_os = "macos"))]
+fn handle_key_release(code: &str) {
+    MOD_STATE.lock().apply(code, false);
+}
+
+/// Counts a mouse press; `pos` is the physical cursor position when the backend knows it.
+#[cfg(not(target_os = "macos"))]
+fn handle_mouse_press(app_handle: &AppHandle, code: Arc<str>, pos: Option<(f64, f64)>) {
+    perf::inc_input_mouse_click();
+    mouse_gestures::record_press(&code);
+    if let Some((x, y)) = pos {
+        click_heatmap::record_global_click(
+            app_handle,
+            click_heatmap::CoordinateSpace::Physical,
+            x,
+            y,
+        );
+    }
+
+    if should_suppress_mouse_press() || !mouse_button_counted(&code) {
+        return;
+    }
+    if let Some((x, y)) = pos {
+        if should_ignore_global_mouse_click(
+            app_handle,
+            click_heatmap::CoordinateSpace::Physical,
+            x,
+            y,
+        ) {
+            return;
+        }
+    }
+
+    enqueue_merit_trigger(
+        app_handle.clone(),
+        InputOrigin::Global,
+        InputSource::MouseSingle,
+        1u64,
+        Some(code),
+        None,
+        None,
+        Some(active_app::current_or_unknown()),
+    );
+}
+
+/// Reads `/dev/input` directly; used on Wayland, where rdev's X11 hook sees no native clients.
+///
+/// Raw devices carry no cursor position, so clicks skip the heatmap and the in-window check.
+#[cfg(target_os = "linux")]
+fn run_evdev(app_handle: AppHandle) {
+    use crate::core::evdev_input::{self, RawInputEvent};
+
+    let (tx, rx) = mpsc::channel::<RawInputEvent>();
+    let worker_handle = app_handle.clone();
+
+    thread::spawn(move || {
+        let _self_app = active_app::AppContext::for_self(&worker_handle);
+        for raw in rx {
+            if !IS_ENABLED.load(Ordering::SeqCst) {
+                continue;
+            }
+
+            match raw {
+                RawInputEvent::KeyDown { code, repeat } => {
+                    let code = perf::time(perf::TimerKind::KeyCodeMap, || {
+                        key_codes::from_evdev_keycode_arc(code)
+                    });
+                    handle_key_press(&worker_handle, code, repeat);
+                }
+                RawInputEvent::KeyUp { code } => {
+                    if let Some(code) = key_codes::from_evdev_keycode(code) {
+                        handle_key_release(code);
+                    }
+                }
+                RawInputEvent::MouseDown { button } => {
+                    handle_mouse_press(&worker_handle, key_codes::from_evdev_button(button), None);
+                }
+                RawInputEvent::MouseUp { button } => {
+                    mouse_gestures::record_release(&key_codes::from_evdev_button(button));
+                }
+                RawInputEvent::MouseMove { dx, dy } => {
+                    perf::inc_input_mouse_move();
+                    mouse_distance::record_relative_move(f64::from(dx), f64::from(dy));
+                }
+                RawInputEvent::Scroll { dx, dy } => {
+                    mouse_scroll::record_scroll(&worker_handle, f64::from(dx), f64::from(dy));
+                }
+            }
+        }
+    });
+
+    if let Err(e) = evdev_input::run(tx) {
+        let err = match e {
+            evdev_input::RunError::PermissionDenied(message) => InputListenerError {
+                code: InputListenerErrorCode::InputGroupRequired,
+                message,
+            },
+            evdev_input::RunError::Failed(message) => InputListenerError {
+                code: InputListenerErrorCode::ListenFailed,
+                message,
+            },
+        };
+        report_error(&app_handle, err);
+    }
+}
+
 pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
     if THREAD_STARTED.swap(true, Ordering::SeqCst) {
         return Ok(());
@@ -209,14 +406,13 @@ pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
                     code: InputListenerErrorCode::PermissionRequired,
                     message: "需要开启 macOS「输入监控」权限：系统设置 → 隐私与安全性 → 输入监控。授权后可在设置页点击“启动”重新初始化。若已授权仍无效，请先移除本应用再重新添加并重启应用。".to_string(),
                 };
-                *LAST_ERROR.write() = Some(err.clone());
-                let _ = app_handle.emit("input-listener-error", err);
-                THREAD_STARTED.store(false, Ordering::SeqCst);
+                report_error(&app_handle, err);
                 return;
             }
         }
 
         mouse_distance::init(app_handle.clone());
+        mouse_scroll::init(app_handle.clone());
 
         #[cfg(target_os = "macos")]
         {
@@ -265,6 +461,11 @@ pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
                                 (None, None)
                             };
 
+                            let app = active_app::current_or_unknown();
+                            if let Some(code) = code.as_ref() {
+                                typing_accuracy::record_key(code, shortcut.is_some(), &app);
+                                key_bigrams::record_key(code, shortcut.is_some(), &app);
+                            }
                             enqueue_merit_trigger(
                                 worker_handle.clone(),
                                 InputOrigin::Global,
@@ -273,7 +474,7 @@ pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
                                 code,
                                 is_shifted,
                                 shortcut,
-                                Some(active_app::current_or_unknown()),
+                                Some(app),
                             );
                             continue;
                         }
@@ -288,7 +489,10 @@ pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
                                 y,
                             );
 
-                            if should_suppress_mouse_press() {
+                            let code = macos_button_code(button);
+                            mouse_gestures::record_press(&code);
+
+                            if should_suppress_mouse_press() || !mouse_button_counted(&code) {
                                 continue;
                             }
                             if should_ignore_global_mouse_click(
@@ -300,17 +504,11 @@ pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
                                 continue;
                             }
 
-                            let code: Option<Arc<str>> = match button {
-                                crate::core::macos_event_tap::RawMouseButton::Left => {
-                                    Some(key_codes::intern("MouseLeft"))
-                                }
-                                crate::core::macos_event_tap::RawMouseButton::Right => {
-                                    Some(key_codes::intern("MouseRight"))
-                                }
-                                crate::core::macos_event_tap::RawMouseButton::Other => None,
-                            };
-
-                            (InputSource::MouseSingle, 1u64, code)
+                            (InputSource::MouseSingle, 1u64, Some(code))
+                        }
+                        crate::core::macos_event_tap::RawInputEvent::MouseUp { button } => {
+                            mouse_gestures::record_release(&macos_button_code(button));
+                            continue;
                         }
                         crate::core::macos_event_tap::RawInputEvent::MouseMove { x, y } => {
                             perf::inc_input_mouse_move();
@@ -321,6 +519,10 @@ pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
                             );
                             continue;
                         }
+                        crate::core::macos_event_tap::RawInputEvent::Scroll { dx, dy } => {
+                            mouse_scroll::record_scroll(&worker_handle, dx, dy);
+                            continue;
+                        }
                     };
 
                     enqueue_merit_trigger(
@@ -337,18 +539,24 @@ pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
             });
 
             if let Err(e) = crate::core::macos_event_tap::run(tx) {
-                let err = InputListenerError {
-                    code: InputListenerErrorCode::ListenFailed,
-                    message: e,
-                };
-                *LAST_ERROR.write() = Some(err.clone());
-                let _ = app_handle.emit("input-listener-error", err);
-                THREAD_STARTED.store(false, Ordering::SeqCst);
+                report_error(
+                    &app_handle,
+                    InputListenerError {
+                        code: InputListenerErrorCode::ListenFailed,
+                        message: e,
+                    },
+                );
             }
         }
 
         #[cfg(not(target_os = "macos"))]
         {
+            #[cfg(target_os = "linux")]
+            if crate::core::evdev_input::is_wayland_session() {
+                run_evdev(app_handle);
+                return;
+            }
+
             let callback_handle = app_handle.clone();
             let _self_app = active_app::AppContext::for_self(&callback_handle);
             let callback = move |event: Event| {
@@ -356,101 +564,23 @@ pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
                     return;
                 }
 
-                let (source, count, detail_code, is_shifted, shortcut) = match event.event_type {
+                match event.event_type {
                     EventType::KeyPress(key) => {
-                        perf::inc_input_key();
-
-                        let (snapshot, code) = {
-                            let mut state = MOD_STATE.lock();
-                            match key {
-                                rdev::Key::ShiftLeft => state.shift_left = true,
-                                rdev::Key::ShiftRight => state.shift_right = true,
-                                rdev::Key::ControlLeft => state.ctrl_left = true,
-                                rdev::Key::ControlRight => state.ctrl_right = true,
-                                rdev::Key::Alt => state.alt_left = true,
-                                rdev::Key::AltGr => state.alt_right = true,
-                                rdev::Key::MetaLeft => state.meta_left = true,
-                                rdev::Key::MetaRight => state.meta_right = true,
-                                rdev::Key::CapsLock => state.caps_lock = !state.caps_lock,
-                                _ => {}
-                            }
-                            let code = perf::time(perf::TimerKind::KeyCodeMap, || key_codes::from_rdev_key(key));
-                            (*state, code)
-                        };
-                        keyboard_piano::emit_key(&callback_handle, code.as_ref());
-
-                        let (is_shifted, shortcut) = {
-                            let shift_down = snapshot.shift();
-                            let is_shifted = effective_shifted(code.as_ref(), shift_down, snapshot.caps_lock);
-                            let shortcut = if (snapshot.ctrl() || snapshot.alt() || snapshot.meta())
-                                && !is_modifier_code(code.as_ref())
-                            {
-                                Some(shortcut_id(
-                                    snapshot.meta(),
-                                    snapshot.ctrl(),
-                                    snapshot.alt(),
-                                    shift_down,
-                                    code.as_ref(),
-                                ))
-                            } else {
-                                None
-                            };
-                            (Some(is_shifted), shortcut)
-                        };
-
-                        (InputSource::Keyboard, 1u64, Some(code), is_shifted, shortcut)
+                        let code = perf::time(perf::TimerKind::KeyCodeMap, || key_codes::from_rdev_key(key));
+                        handle_key_press(&callback_handle, code, false);
                     }
                     EventType::KeyRelease(key) => {
-                        let mut state = MOD_STATE.lock();
-                        match key {
-                            rdev::Key::ShiftLeft => state.shift_left = false,
-                            rdev::Key::ShiftRight => state.shift_right = false,
-                            rdev::Key::ControlLeft => state.ctrl_left = false,
-                            rdev::Key::ControlRight => state.ctrl_right = false,
-                            rdev::Key::Alt => state.alt_left = false,
-                            rdev::Key::AltGr => state.alt_right = false,
-                            rdev::Key::MetaLeft => state.meta_left = false,
-                            rdev::Key::MetaRight => state.meta_right = false,
-                            _ => {}
-                        }
-                        return;
+                        handle_key_release(key_codes::from_rdev_key(key).as_ref());
                     }
                     EventType::ButtonPress(button) => {
-                        perf::inc_input_mouse_click();
                         let pos = {
                             let st = MOUSE_STATE.lock();
                             st.has_position.then_some((st.x, st.y))
                         };
-                        if let Some((x, y)) = pos {
-                            click_heatmap::record_global_click(
-                                &callback_handle,
-                                click_heatmap::CoordinateSpace::Physical,
-                                x,
-                                y,
-                            );
-                        }
-
-                        if should_suppress_mouse_press() {
-                            return;
-                        }
-                        if let Some((x, y)) = pos {
-                            if should_ignore_global_mouse_click(
-                                &callback_handle,
-                                click_heatmap::CoordinateSpace::Physical,
-                                x,
-                                y,
-                            ) {
-                                return;
-                            }
-                        }
-
-                        let code = match button {
-                            rdev::Button::Left => Some(key_codes::intern("MouseLeft")),
-                            rdev::Button::Right => Some(key_codes::intern("MouseRight")),
-                            _ => None,
-                        };
-
-                        (InputSource::MouseSingle, 1u64, code, None, None)
+                        handle_mouse_press(&callback_handle, key_codes::from_rdev_button(button), pos);
+                    }
+                    EventType::ButtonRelease(button) => {
+                        mouse_gestures::record_release(&key_codes::from_rdev_button(button));
                     }
                     EventType::MouseMove { x, y } => {
                         perf::inc_input_mouse_move();
@@ -463,31 +593,34 @@ pub fn init_input_listener(app_handle: AppHandle) -> Result<(), String> {
                         st.x = x;
                         st.y = y;
                         st.has_position = true;
-                        return;
                     }
-                    _ => return,
-                };
-
-                enqueue_merit_trigger(
-                    callback_handle.clone(),
-                    InputOrigin::Global,
-                    source,
-                    count,
-                    detail_code,
-                    is_shifted,
-                    shortcut,
-                    Some(active_app::current_or_unknown()),
-                );
+                    EventType::Wheel { delta_x, delta_y } => {
+                        // rdev reports whole notches (Windows drops high-resolution remainders).
+                        mouse_scroll::record_scroll(
+                            &callback_handle,
+                            delta_x as f64,
+                            delta_y as f64,
+                        );
+                    }
+                    _ => {}
+                }
             };
 
             if let Err(e) = listen(callback) {
-                let err = InputListenerError {
-                    code: InputListenerErrorCode::ListenFailed,
-                    message: format!("{:?}", e),
-                };
-                *LAST_ERROR.write() = Some(err.clone());
-                let _ = app_handle.emit("input-listener-error", err);
-                THREAD_STARTED.store(false, Ordering::SeqCst);
+                // No usable X display (e.g. a Wayland session without the usual env vars).
+                #[cfg(target_os = "linux")]
+                {
+                    eprintln!("rdev listen failed ({:?}); falling back to evdev", e);
+                    run_evdev(app_handle);
+                }
+                #[cfg(not(target_os = "linux"))]
+                report_error(
+                    &app_handle,
+                    InputListenerError {
+                        code: InputListenerErrorCode::ListenFailed,
+                        message: format!("{:?}", e),
+                    },
+                );
             }
         }
     });
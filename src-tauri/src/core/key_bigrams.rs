@@ -0,0 +1,149 @@
+//! Consecutive-key (bigram) counts for layout analysis. Fed per key press from `input_listener`.
+//!
+//! Bigrams come close to recording the typed text, so they're only kept at
+//! `KeyPrivacyLevel::Full` and never for apps whose details are stripped.
+
+use crate::core::active_app::AppContext;
+use crate::core::finger_map::finger_for;
+use crate::core::history_db;
+use crate::core::key_privacy;
+use crate::core::MeritStorage;
+use crate::models::{KeyBigramCount, KeyBigramStats, KeyPrivacyLevel};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[derive(Default)]
+struct Tracker {
+    last: Option<(Arc<str>, u64)>,
+}
+
+impl Tracker {
+    /// Returns the previous key if it was pressed within `timeout_ms`.
+    fn record(&mut self, code: &Arc<str>, now_ms: u64, timeout_ms: u64) -> Option<Arc<str>> {
+        self.last
+            .replace((Arc::clone(code), now_ms))
+            .filter(|(_, at)| now_ms.saturating_sub(*at) <= timeout_ms)
+            .map(|(first, _)| first)
+    }
+}
+
+static TRACKER: Lazy<Mutex<Tracker>> = Lazy::new(|| Mutex::new(Tracker::default()));
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Records a global key press. Modifiers are skipped so capitals don't split a pair; shortcuts
+/// and keys that may not be stored break the chain.
+pub fn record_key(code: &Arc<str>, has_shortcut: bool, app: &AppContext) {
+    if key_privacy::category(code) == "cat:modifier" {
+        return;
+    }
+    let timeout_ms = {
+        let storage = MeritStorage::instance();
+        let storage = storage.read();
+        (storage.key_details_allowed(app) && storage.key_privacy_level() == KeyPrivacyLevel::Full)
+            .then(|| u64::from(storage.key_bigram_timeout_ms()))
+    };
+
+    let mut tracker = TRACKER.lock();
+    match timeout_ms {
+        Some(timeout_ms) if !has_shortcut => {
+            if let Some(first) = tracker.record(code, now_ms(), timeout_ms) {
+                history_db::record_key_bigram(
+                    crate::core::date_key::today_key_arc(),
+                    first,
+                    Arc::clone(code),
+                );
+            }
+        }
+        _ => tracker.last = None,
+    }
+}
+
+/// Same-finger/same-hand breakdown of `bigrams` (sorted most frequent first), keeping the top
+/// `limit` pairs.
+pub fn stats(mut bigrams: Vec<KeyBigramCount>, limit: usize) -> KeyBigramStats {
+    let mut out = KeyBigramStats::default();
+    for bigram in &bigrams {
+        let count = bigram.count;
+        out.total = out.total.saturating_add(count);
+        let same_key = bigram.first == bigram.second;
+        if same_key {
+            out.same_key = out.same_key.saturating_add(count);
+        }
+        match (finger_for(&bigram.first), finger_for(&bigram.second)) {
+            (Some((first_hand, first_finger)), Some((second_hand, second_finger))) => {
+                if first_hand != second_hand {
+                    out.hand_alternations = out.hand_alternations.saturating_add(count);
+                    continue;
+                }
+                out.same_hand = out.same_hand.saturating_add(count);
+                if first_finger == second_finger && !same_key {
+                    out.same_finger = out.same_finger.saturating_add(count);
+                }
+            }
+            _ => out.unmapped = out.unmapped.saturating_add(count),
+        }
+    }
+    bigrams.truncate(limit);
+    out.top = bigrams;
+    out
+}
+
+pub fn load_stats(
+    start_key: Option<&str>,
+    end_key: Option<&str>,
+    limit: usize,
+) -> Result<KeyBigramStats, String> {
+    Ok(stats(
+        history_db::load_key_bigrams(start_key, end_key)?,
+        limit,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bigram(first: &str, second: &str, count: u64) -> KeyBigramCount {
+        KeyBigramCount {
+            first: Arc::from(first),
+            second: Arc::from(second),
+            count,
+        }
+    }
+
+    #[test]
+    fn pairs_keys_within_timeout_and_classifies_fingers() {
+        let a: Arc<str> = Arc::from("KeyA");
+        let b: Arc<str> = Arc::from("KeyB");
+        let mut tracker = Tracker::default();
+        assert_eq!(tracker.record(&a, 1_000, 500), None);
+        assert_eq!(tracker.record(&b, 1_400, 500).as_deref(), Some("KeyA"));
+        assert_eq!(tracker.record(&a, 2_000, 500), None);
+
+        let stats = stats(
+            vec![
+                bigram("KeyE", "KeyD", 8),  // left middle twice
+                bigram("KeyT", "KeyH", 6),  // alternation
+                bigram("KeyL", "KeyL", 4),  // repeat
+                bigram("KeyA", "KeyS", 3),  // same hand, different fingers
+                bigram("Space", "KeyA", 2), // thumb key
+            ],
+            2,
+        );
+        assert_eq!(stats.total, 23);
+        assert_eq!(stats.same_finger, 8);
+        assert_eq!(stats.same_key, 4);
+        assert_eq!(stats.same_hand, 15);
+        assert_eq!(stats.hand_alternations, 6);
+        assert_eq!(stats.unmapped, 2);
+        assert_eq!(stats.top.len(), 2);
+    }
+}
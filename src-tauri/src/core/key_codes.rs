@@ -82,6 +82,12 @@ static CANONICAL_ARCS: Lazy<HashMap<&'static str, Arc<str>>> = Lazy::new(|| {
         "MetaLeft",
         "MetaRight",
         "Minus",
+        "MouseBack",
+        "MouseExtra",
+        "MouseForward",
+        "MouseLeft",
+        "MouseMiddle",
+        "MouseRight",
         "NumLock",
         "Numpad0",
         "Numpad1",
@@ -129,6 +135,24 @@ pub fn intern(code: &str) -> Arc<str> {
         .unwrap_or_else(|| Arc::<str>::from(code))
 }
 
+/// Buttons past the middle one that count as "side" buttons in settings.
+pub fn is_side_mouse_button(code: &str) -> bool {
+    matches!(code, "MouseBack" | "MouseForward" | "MouseExtra")
+}
+
+#[cfg(not(target_os = "macos"))]
+pub fn from_rdev_button(button: rdev::Button) -> Arc<str> {
+    intern(match button {
+        rdev::Button::Left => "MouseLeft",
+        rdev::Button::Right => "MouseRight",
+        rdev::Button::Middle => "MouseMiddle",
+        // Windows reports XBUTTON1/XBUTTON2 as 1/2; X11 numbers them 8/9.
+        rdev::Button::Unknown(1 | 8) => "MouseBack",
+        rdev::Button::Unknown(2 | 9) => "MouseForward",
+        rdev::Button::Unknown(_) => "MouseExtra",
+    })
+}
+
 #[cfg(not(target_os = "macos"))]
 pub fn from_rdev_key(key: rdev::Key) -> Arc<str> {
     use rdev::Key;
@@ -251,6 +275,158 @@ pub fn from_rdev_key(key: rdev::Key) -> Arc<str> {
     Arc::<str>::from(format!("{:?}", key))
 }
 
+#[cfg(target_os = "linux")]
+pub fn from_evdev_keycode(code: u16) -> Option<&'static str> {
+    // Source for keycodes: linux/input-event-codes.h (KEY_* constants).
+    Some(match code {
+        1 => "Escape",
+        2 => "Digit1",
+        3 => "Digit2",
+        4 => "Digit3",
+        5 => "Digit4",
+        6 => "Digit5",
+        7 => "Digit6",
+        8 => "Digit7",
+        9 => "Digit8",
+        10 => "Digit9",
+        11 => "Digit0",
+        12 => "Minus",
+        13 => "Equal",
+        14 => "Backspace",
+        15 => "Tab",
+        16 => "KeyQ",
+        17 => "KeyW",
+        18 => "KeyE",
+        19 => "KeyR",
+        20 => "KeyT",
+        21 => "KeyY",
+        22 => "KeyU",
+        23 => "KeyI",
+        24 => "KeyO",
+        25 => "KeyP",
+        26 => "BracketLeft",
+        27 => "BracketRight",
+        28 => "Enter",
+        29 => "ControlLeft",
+        30 => "KeyA",
+        31 => "KeyS",
+        32 => "KeyD",
+        33 => "KeyF",
+        34 => "KeyG",
+        35 => "KeyH",
+        36 => "KeyJ",
+        37 => "KeyK",
+        38 => "KeyL",
+        39 => "Semicolon",
+        40 => "Quote",
+        41 => "Backquote",
+        42 => "ShiftLeft",
+        43 => "Backslash",
+        44 => "KeyZ",
+        45 => "KeyX",
+        46 => "KeyC",
+        47 => "KeyV",
+        48 => "KeyB",
+        49 => "KeyN",
+        50 => "KeyM",
+        51 => "Comma",
+        52 => "Period",
+        53 => "Slash",
+        54 => "ShiftRight",
+        55 => "NumpadMultiply",
+        56 => "AltLeft",
+        57 => "Space",
+        58 => "CapsLock",
+        59 => "F1",
+        60 => "F2",
+        61 => "F3",
+        62 => "F4",
+        63 => "F5",
+        64 => "F6",
+        65 => "F7",
+        66 => "F8",
+        67 => "F9",
+        68 => "F10",
+        69 => "NumLock",
+        70 => "ScrollLock",
+        71 => "Numpad7",
+        72 => "Numpad8",
+        73 => "Numpad9",
+        74 => "NumpadSubtract",
+        75 => "Numpad4",
+        76 => "Numpad5",
+        77 => "Numpad6",
+        78 => "NumpadAdd",
+        79 => "Numpad1",
+        80 => "Numpad2",
+        81 => "Numpad3",
+        82 => "Numpad0",
+        83 => "NumpadDecimal",
+        // KEY_102ND; rdev reports it as IntlBackslash, which is counted as Backslash.
+        86 => "Backslash",
+        87 => "F11",
+        88 => "F12",
+        96 => "NumpadEnter",
+        97 => "ControlRight",
+        98 => "NumpadDivide",
+        99 => "PrintScreen",
+        100 => "AltRight",
+        102 => "Home",
+        103 => "ArrowUp",
+        104 => "PageUp",
+        105 => "ArrowLeft",
+        106 => "ArrowRight",
+        107 => "End",
+        108 => "ArrowDown",
+        109 => "PageDown",
+        110 => "Insert",
+        111 => "Delete",
+        113 => "AudioVolumeMute",
+        114 => "AudioVolumeDown",
+        115 => "AudioVolumeUp",
+        117 => "NumpadEqual",
+        119 => "Pause",
+        125 => "MetaLeft",
+        126 => "MetaRight",
+        // KEY_COMPOSE is the Menu key on PC keyboards.
+        127 => "ContextMenu",
+        163 => "MediaTrackNext",
+        // KEY_PLAYPAUSE, KEY_PLAYCD, KEY_PAUSECD
+        164 | 200 | 201 => "MediaPlayPause",
+        165 => "MediaTrackPrevious",
+        166 => "MediaStop",
+        183 => "F13",
+        184 => "F14",
+        185 => "F15",
+        186 => "F16",
+        464 => "Fn",
+        _ => return None,
+    })
+}
+
+#[cfg(target_os = "linux")]
+pub fn from_evdev_keycode_arc(code: u16) -> Arc<str> {
+    match from_evdev_keycode(code) {
+        Some(code) => intern(code),
+        None => Arc::<str>::from(format!("Unknown({})", code)),
+    }
+}
+
+/// Maps an evdev `BTN_*` mouse code (0x110..=0x117).
+#[cfg(target_os = "linux")]
+pub fn from_evdev_button(code: u16) -> Arc<str> {
+    intern(match code {
+        0x110 => "MouseLeft",
+        0x111 => "MouseRight",
+        0x112 => "MouseMiddle",
+        // BTN_SIDE / BTN_BACK
+        0x113 | 0x116 => "MouseBack",
+        // BTN_EXTRA / BTN_FORWARD
+        0x114 | 0x115 => "MouseForward",
+        _ => "MouseExtra",
+    })
+}
+
 #[cfg(target_os = "macos")]
 pub fn from_macos_virtual_keycode(keycode: u16) -> Option<&'static str> {
     // Canonical codes largely follow the web `KeyboardEvent.code` naming.
@@ -387,6 +563,19 @@ pub fn from_macos_virtual_keycode_arc(keycode: u16) -> Option<Arc<str>> {
     Some(intern(normalized))
 }
 
+/// Maps `kCGMouseEventButtonNumber` (0 = left, 1 = right, 2 = middle).
+#[cfg(target_os = "macos")]
+pub fn from_macos_mouse_button(number: i64) -> Arc<str> {
+    intern(match number {
+        0 => "MouseLeft",
+        1 => "MouseRight",
+        2 => "MouseMiddle",
+        3 => "MouseBack",
+        4 => "MouseForward",
+        _ => "MouseExtra",
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -408,6 +597,31 @@ mod tests {
         assert!(Arc::ptr_eq(&code, &canonical));
     }
 
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn from_rdev_button_maps_side_buttons() {
+        assert!(Arc::ptr_eq(&from_rdev_button(rdev::Button::Middle), &intern("MouseMiddle")));
+        assert_eq!(from_rdev_button(rdev::Button::Unknown(1)).as_ref(), "MouseBack");
+        assert_eq!(from_rdev_button(rdev::Button::Unknown(9)).as_ref(), "MouseForward");
+        assert!(is_side_mouse_button(&from_rdev_button(rdev::Button::Unknown(12))));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn evdev_keycode_mapping_matches_rdev_names() {
+        assert!(Arc::ptr_eq(&from_evdev_keycode_arc(30), &intern("KeyA")));
+        assert_eq!(from_evdev_keycode(100), Some("AltRight"));
+        assert_eq!(from_evdev_keycode(96), Some("NumpadEnter"));
+        assert_eq!(from_evdev_keycode(127), Some("ContextMenu"));
+        assert_eq!(from_evdev_keycode(114), Some("AudioVolumeDown"));
+        assert_eq!(from_evdev_keycode(201), Some("MediaPlayPause"));
+        assert_eq!(from_evdev_keycode(165), Some("MediaTrackPrevious"));
+        assert_eq!(from_evdev_keycode_arc(240).as_ref(), "Unknown(240)");
+        assert!(Arc::ptr_eq(&from_evdev_button(0x112), &intern("MouseMiddle")));
+        assert_eq!(from_evdev_button(0x113).as_ref(), "MouseBack");
+        assert_eq!(from_evdev_button(0x115).as_ref(), "MouseForward");
+    }
+
     #[cfg(target_os = "macos")]
     #[test]
     fn macos_keycode_mapping_is_normalized_and_interned() {
@@ -0,0 +1,137 @@
+//! Reduces key-level detail according to `Settings::key_privacy_level`.
+
+use crate::core::history_db::{self, HistoryTableRows};
+use crate::core::intern;
+use crate::core::MeritStorage;
+use crate::models::{DailyStats, KeyPrivacyLevel};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const CATEGORY_PREFIX: &str = "cat:";
+
+/// Coarse bucket for a canonical key code, e.g. `cat:letter` for `KeyA`.
+pub fn category(code: &str) -> &'static str {
+    match code {
+        "ShiftLeft" | "ShiftRight" | "ControlLeft" | "ControlRight" | "AltLeft" | "AltRight"
+        | "MetaLeft" | "MetaRight" | "CapsLock" | "Fn" => "cat:modifier",
+        "Space" | "Tab" | "Enter" => "cat:whitespace",
+        "Backspace" | "Delete" => "cat:deletion",
+        "Insert" => "cat:editing",
+        "ArrowUp" | "ArrowDown" | "ArrowLeft" | "ArrowRight" | "Home" | "End" | "PageUp"
+        | "PageDown" => "cat:navigation",
+        "Minus" | "Equal" | "BracketLeft" | "BracketRight" | "Backslash" | "Semicolon"
+        | "Quote" | "Backquote" | "Comma" | "Period" | "Slash" => "cat:punctuation",
+        "Escape" | "PrintScreen" | "ScrollLock" | "Pause" => "cat:function",
+        _ if code.len() == 4 && code.starts_with("Key") => "cat:letter",
+        _ if code.starts_with("Digit") => "cat:digit",
+        _ if code.starts_with("Numpad") || code == "NumLock" => "cat:numpad",
+        _ if code.len() > 1
+            && code.starts_with('F')
+            && code[1..].bytes().all(|b| b.is_ascii_digit()) =>
+        {
+            "cat:function"
+        }
+        _ => "cat:other",
+    }
+}
+
+/// Like [`category`], but codes that are already categories pass through.
+pub fn reduced_code(code: &str) -> &str {
+    if code.starts_with(CATEGORY_PREFIX) {
+        code
+    } else {
+        category(code)
+    }
+}
+
+/// Shortcut ids keep their modifiers (`Ctrl+Shift+KeyZ` becomes `Ctrl+Shift+cat:letter`).
+pub fn reduced_shortcut(shortcut: &str) -> String {
+    match shortcut.rsplit_once('+') {
+        Some((mods, code)) => format!("{}+{}", mods, reduced_code(code)),
+        None => reduced_code(shortcut).to_string(),
+    }
+}
+
+/// The key code to store at `level`, or `None` if it must not be stored at all.
+pub fn reduce_code(level: KeyPrivacyLevel, code: &Arc<str>) -> Option<Arc<str>> {
+    match level {
+        KeyPrivacyLevel::Full => Some(Arc::clone(code)),
+        KeyPrivacyLevel::Categories => Some(intern::intern_str(reduced_code(code))),
+        KeyPrivacyLevel::TotalsOnly => None,
+    }
+}
+
+/// The shortcut id to store at `level`, or `None` if it must not be stored at all.
+pub fn reduce_shortcut(level: KeyPrivacyLevel, shortcut: &Arc<str>) -> Option<Arc<str>> {
+    match level {
+        KeyPrivacyLevel::Full => Some(Arc::clone(shortcut)),
+        KeyPrivacyLevel::Categories => Some(intern::intern_str(&reduced_shortcut(shortcut))),
+        KeyPrivacyLevel::TotalsOnly => None,
+    }
+}
+
+fn reduce_counts(
+    counts: &mut HashMap<Arc<str>, u64>,
+    level: KeyPrivacyLevel,
+    reduce: fn(KeyPrivacyLevel, &Arc<str>) -> Option<Arc<str>>,
+) {
+    if level == KeyPrivacyLevel::Full || counts.is_empty() {
+        return;
+    }
+    let mut out: HashMap<Arc<str>, u64> = HashMap::new();
+    for (code, count) in counts.drain() {
+        if let Some(code) = reduce(level, &code) {
+            let entry = out.entry(code).or_insert(0);
+            *entry = entry.saturating_add(count);
+        }
+    }
+    *counts = out;
+}
+
+/// Brings a day's key and shortcut counters down to `level`; totals are untouched.
+pub fn reduce_day(day: &mut DailyStats, level: KeyPrivacyLevel) {
+    reduce_counts(&mut day.key_counts, level, reduce_code);
+    reduce_counts(&mut day.key_counts_unshifted, level, reduce_code);
+    reduce_counts(&mut day.key_counts_shifted, level, reduce_code);
+    reduce_counts(&mut day.shortcut_counts, level, reduce_shortcut);
+}
+
+/// Brings everything already recorded down to `level`: the in-memory days first, so a later save
+/// can't write the details back, then the history database. Returns the rows rewritten per table.
+pub fn scrub_stored(level: KeyPrivacyLevel) -> Result<Vec<HistoryTableRows>, String> {
+    if level == KeyPrivacyLevel::Full {
+        return Ok(Vec::new());
+    }
+    MeritStorage::instance().write().scrub_key_details(level);
+    crate::core::persistence::request_save();
+    history_db::scrub_key_details_now(level)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reduces_codes_and_shortcuts_by_level() {
+        assert_eq!(category("KeyQ"), "cat:letter");
+        assert_eq!(category("F11"), "cat:function");
+        assert_eq!(category("Fn"), "cat:modifier");
+        assert_eq!(category("Delete"), "cat:deletion");
+        assert_eq!(category("Insert"), "cat:editing");
+        assert_eq!(reduced_code("cat:digit"), "cat:digit");
+
+        let shortcut: Arc<str> = Arc::from("Ctrl+Shift+KeyZ");
+        let reduced = reduce_shortcut(KeyPrivacyLevel::Categories, &shortcut).unwrap();
+        assert_eq!(reduced.as_ref(), "Ctrl+Shift+cat:letter");
+        assert!(reduce_shortcut(KeyPrivacyLevel::TotalsOnly, &shortcut).is_none());
+
+        let mut counts: HashMap<Arc<str>, u64> = HashMap::new();
+        counts.insert(Arc::from("KeyA"), 3);
+        counts.insert(Arc::from("KeyB"), 2);
+        counts.insert(Arc::from("Digit1"), 1);
+        reduce_counts(&mut counts, KeyPrivacyLevel::Categories, reduce_code);
+        assert_eq!(counts.get("cat:letter"), Some(&5));
+        assert_eq!(counts.get("cat:digit"), Some(&1));
+        assert_eq!(counts.len(), 2);
+    }
+}
@@ -0,0 +1,405 @@
+//! Finger load, hand balance, row usage and finger travel for logical layouts, computed from
+//! recorded key counts.
+//!
+//! Counts are recorded by physical key. They're turned back into characters through the layout the
+//! user actually types on, then placed on the keys each candidate layout uses for them, so every
+//! layout is scored on the same text. Geometry follows `Settings::keyboard_layout`; all supported
+//! boards share the ANSI main block except HHKB, which moves Backspace and Backslash.
+
+use crate::core::finger_map::{finger_for, Finger, Hand};
+use crate::models::StatisticsAggregates;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Standard key pitch.
+const KEY_UNIT_MM: f64 = 19.05;
+
+/// Character keys of the main block by row, matched index-wise by `LogicalLayout::rows`.
+const POSITIONS: [&[&str]; 4] = [
+    &[
+        "Backquote",
+        "Digit1",
+        "Digit2",
+        "Digit3",
+        "Digit4",
+        "Digit5",
+        "Digit6",
+        "Digit7",
+        "Digit8",
+        "Digit9",
+        "Digit0",
+        "Minus",
+        "Equal",
+    ],
+    &[
+        "KeyQ",
+        "KeyW",
+        "KeyE",
+        "KeyR",
+        "KeyT",
+        "KeyY",
+        "KeyU",
+        "KeyI",
+        "KeyO",
+        "KeyP",
+        "BracketLeft",
+        "BracketRight",
+        "Backslash",
+    ],
+    &[
+        "KeyA",
+        "KeyS",
+        "KeyD",
+        "KeyF",
+        "KeyG",
+        "KeyH",
+        "KeyJ",
+        "KeyK",
+        "KeyL",
+        "Semicolon",
+        "Quote",
+    ],
+    &[
+        "KeyZ", "KeyX", "KeyC", "KeyV", "KeyB", "KeyN", "KeyM", "Comma", "Period", "Slash",
+    ],
+];
+
+/// Left edge of each row's first character key, in key units.
+const ROW_OFFSETS: [f64; 4] = [0.0, 1.5, 1.75, 2.25];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LogicalLayout {
+    #[default]
+    Qwerty,
+    Dvorak,
+    Colemak,
+    Workman,
+}
+
+impl LogicalLayout {
+    pub const ALL: [LogicalLayout; 4] = [Self::Qwerty, Self::Dvorak, Self::Colemak, Self::Workman];
+
+    /// Unshifted characters on the keys listed in `POSITIONS`.
+    fn rows(self) -> [&'static str; 4] {
+        match self {
+            Self::Qwerty => [
+                "`1234567890-=",
+                "qwertyuiop[]\\",
+                "asdfghjkl;'",
+                "zxcvbnm,./",
+            ],
+            Self::Dvorak => [
+                "`1234567890[]",
+                "',.pyfgcrl/=\\",
+                "aoeuidhtns-",
+                ";qjkxbmwvz",
+            ],
+            Self::Colemak => [
+                "`1234567890-=",
+                "qwfpgjluy;[]\\",
+                "arstdhneio'",
+                "zxcvbkm,./",
+            ],
+            Self::Workman => [
+                "`1234567890-=",
+                "qdrwbjfup;[]\\",
+                "ashtgyneoi'",
+                "zxmcvkl,./",
+            ],
+        }
+    }
+
+    fn char_at(self, (row, idx): (usize, usize)) -> Option<char> {
+        self.rows()[row].chars().nth(idx)
+    }
+
+    fn position_of(self, ch: char) -> Option<(usize, usize)> {
+        self.rows()
+            .iter()
+            .enumerate()
+            .find_map(|(row, chars)| chars.chars().position(|c| c == ch).map(|idx| (row, idx)))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Row {
+    Number,
+    Top,
+    Home,
+    Bottom,
+}
+
+const ROWS: [Row; 4] = [Row::Number, Row::Top, Row::Home, Row::Bottom];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Board {
+    Ansi,
+    Hhkb,
+}
+
+impl Board {
+    fn from_layout_id(id: &str) -> Self {
+        if id == "hhkb" {
+            Self::Hhkb
+        } else {
+            Self::Ansi
+        }
+    }
+
+    /// Row and key centre (x, y) in key units.
+    fn locate(self, code: &str) -> Option<(Row, f64, f64)> {
+        match (self, code) {
+            (Self::Hhkb, "Backspace") => return Some((Row::Top, 14.25, 1.0)),
+            (Self::Hhkb, "Backslash") => return Some((Row::Number, 13.5, 0.0)),
+            _ => {}
+        }
+        if let Some((row, idx)) = position(code) {
+            return Some((ROWS[row], ROW_OFFSETS[row] + idx as f64 + 0.5, row as f64));
+        }
+        Some(match code {
+            "Backspace" => (Row::Number, 14.0, 0.0),
+            "Tab" => (Row::Top, 0.75, 1.0),
+            "CapsLock" => (Row::Home, 0.875, 2.0),
+            "Enter" => (Row::Home, 13.875, 2.0),
+            "ShiftLeft" => (Row::Bottom, 1.125, 3.0),
+            "ShiftRight" => (Row::Bottom, 13.625, 3.0),
+            _ => return None,
+        })
+    }
+}
+
+fn position(code: &str) -> Option<(usize, usize)> {
+    POSITIONS
+        .iter()
+        .enumerate()
+        .find_map(|(row, codes)| codes.iter().position(|c| *c == code).map(|idx| (row, idx)))
+}
+
+fn home_key(hand: Hand, finger: Finger) -> &'static str {
+    match (hand, finger) {
+        (Hand::Left, Finger::Pinky) => "KeyA",
+        (Hand::Left, Finger::Ring) => "KeyS",
+        (Hand::Left, Finger::Middle) => "KeyD",
+        (Hand::Left, Finger::Index) => "KeyF",
+        (Hand::Right, Finger::Index) => "KeyJ",
+        (Hand::Right, Finger::Middle) => "KeyK",
+        (Hand::Right, Finger::Ring) => "KeyL",
+        (Hand::Right, Finger::Pinky) => "Semicolon",
+    }
+}
+
+const FINGERS: [(Hand, Finger); 8] = [
+    (Hand::Left, Finger::Pinky),
+    (Hand::Left, Finger::Ring),
+    (Hand::Left, Finger::Middle),
+    (Hand::Left, Finger::Index),
+    (Hand::Right, Finger::Index),
+    (Hand::Right, Finger::Middle),
+    (Hand::Right, Finger::Ring),
+    (Hand::Right, Finger::Pinky),
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FingerLoad {
+    pub hand: Hand,
+    pub finger: Finger,
+    pub keys: u64,
+    /// Share of the keys typed by the eight fingers.
+    pub share: f64,
+    pub travel_mm: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RowUsage {
+    pub row: Row,
+    pub keys: u64,
+    pub share: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LayoutAnalysis {
+    pub layout: LogicalLayout,
+    /// Keys typed by the eight fingers; the base for every share below.
+    pub finger_keys: u64,
+    /// Space presses, left to the thumbs.
+    pub thumb_keys: u64,
+    /// Keys off the main block (navigation, numpad, ...) and category-only counts.
+    pub unmapped_keys: u64,
+    pub fingers: Vec<FingerLoad>,
+    pub left_hand_keys: u64,
+    pub right_hand_keys: u64,
+    pub left_hand_share: f64,
+    pub rows: Vec<RowUsage>,
+    /// Finger travel from the home row and back for every press.
+    pub travel_mm: f64,
+}
+
+fn share(part: u64, total: u64) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 / total as f64
+    }
+}
+
+/// Moves counts typed on `source` to the keys `target` uses for the same characters.
+fn remap(
+    counts: &HashMap<Arc<str>, u64>,
+    source: LogicalLayout,
+    target: LogicalLayout,
+) -> HashMap<String, u64> {
+    let mut out: HashMap<String, u64> = HashMap::new();
+    for (code, count) in counts {
+        let moved = position(code)
+            .and_then(|pos| source.char_at(pos))
+            .and_then(|ch| target.position_of(ch))
+            .map(|(row, idx)| POSITIONS[row][idx]);
+        let entry = out
+            .entry(moved.unwrap_or(code.as_ref()).to_string())
+            .or_insert(0);
+        *entry = entry.saturating_add(*count);
+    }
+    out
+}
+
+fn analyze_counts(
+    counts: &HashMap<String, u64>,
+    board: Board,
+    layout: LogicalLayout,
+) -> LayoutAnalysis {
+    let mut finger_keys = HashMap::<(Hand, Finger), u64>::new();
+    let mut finger_travel = HashMap::<(Hand, Finger), f64>::new();
+    let mut row_keys = [0u64; 4];
+    let mut thumb_keys = 0u64;
+    let mut unmapped_keys = 0u64;
+
+    for (code, count) in counts {
+        let count = *count;
+        if code == "Space" {
+            thumb_keys = thumb_keys.saturating_add(count);
+            continue;
+        }
+        let (Some((hand, finger)), Some((row, x, y))) = (finger_for(code), board.locate(code))
+        else {
+            unmapped_keys = unmapped_keys.saturating_add(count);
+            continue;
+        };
+        let keys = finger_keys.entry((hand, finger)).or_insert(0);
+        *keys = keys.saturating_add(count);
+        if let Some(idx) = ROWS.iter().position(|r| *r == row) {
+            row_keys[idx] = row_keys[idx].saturating_add(count);
+        }
+        if let Some((_, home_x, home_y)) = board.locate(home_key(hand, finger)) {
+            let distance = ((x - home_x).powi(2) + (y - home_y).powi(2)).sqrt();
+            *finger_travel.entry((hand, finger)).or_insert(0.0) +=
+                2.0 * distance * KEY_UNIT_MM * count as f64;
+        }
+    }
+
+    let total: u64 = finger_keys.values().sum();
+    let fingers: Vec<FingerLoad> = FINGERS
+        .iter()
+        .map(|&(hand, finger)| {
+            let keys = finger_keys.get(&(hand, finger)).copied().unwrap_or(0);
+            FingerLoad {
+                hand,
+                finger,
+                keys,
+                share: share(keys, total),
+                travel_mm: finger_travel.get(&(hand, finger)).copied().unwrap_or(0.0),
+            }
+        })
+        .collect();
+    let left_hand_keys = fingers
+        .iter()
+        .filter(|f| f.hand == Hand::Left)
+        .map(|f| f.keys)
+        .sum();
+
+    LayoutAnalysis {
+        layout,
+        finger_keys: total,
+        thumb_keys,
+        unmapped_keys,
+        travel_mm: fingers.iter().map(|f| f.travel_mm).sum(),
+        fingers,
+        left_hand_keys,
+        right_hand_keys: total - left_hand_keys,
+        left_hand_share: share(left_hand_keys, total),
+        rows: ROWS
+            .iter()
+            .zip(row_keys)
+            .map(|(&row, keys)| RowUsage {
+                row,
+                keys,
+                share: share(keys, total),
+            })
+            .collect(),
+    }
+}
+
+/// Scores `targets` on key counts that were typed on `source`, using `keyboard_layout`'s geometry.
+pub fn analyze(
+    aggregates: &StatisticsAggregates,
+    keyboard_layout: &str,
+    source: LogicalLayout,
+    targets: &[LogicalLayout],
+) -> Vec<LayoutAnalysis> {
+    let board = Board::from_layout_id(keyboard_layout);
+    targets
+        .iter()
+        .map(|&target| {
+            analyze_counts(
+                &remap(&aggregates.key_counts_all, source, target),
+                board,
+                target,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scores_the_same_text_on_each_layout() {
+        for layout in LogicalLayout::ALL {
+            let rows = layout.rows();
+            for (row, codes) in POSITIONS.iter().enumerate() {
+                assert_eq!(rows[row].chars().count(), codes.len(), "{:?}", layout);
+            }
+        }
+
+        // "the" typed on QWERTY, plus Space and an arrow key.
+        let mut aggregates = StatisticsAggregates::default();
+        for (code, count) in [
+            ("KeyT", 10),
+            ("KeyH", 10),
+            ("KeyE", 10),
+            ("Space", 5),
+            ("ArrowUp", 1),
+        ] {
+            aggregates.key_counts_all.insert(Arc::from(code), count);
+        }
+
+        let results = analyze(
+            &aggregates,
+            "tkl_80",
+            LogicalLayout::Qwerty,
+            &[LogicalLayout::Qwerty, LogicalLayout::Colemak],
+        );
+        let (qwerty, colemak) = (&results[0], &results[1]);
+        assert_eq!(qwerty.finger_keys, 30);
+        assert_eq!(qwerty.thumb_keys, 5);
+        assert_eq!(qwerty.unmapped_keys, 1);
+        assert_eq!(qwerty.left_hand_keys, 20);
+        // QWERTY: only H is on the home row; Colemak puts all three there.
+        assert!((qwerty.rows[2].share - 1.0 / 3.0).abs() < 1e-9);
+        assert!((colemak.rows[2].share - 1.0).abs() < 1e-9);
+        assert!(colemak.travel_mm < qwerty.travel_mm);
+    }
+}
@@ -0,0 +1,303 @@
+//! Pauses and resumes global listening from `Settings::listening_schedule` and the
+//! "pause for N minutes" timer.
+//!
+//! The schedule only acts when its verdict changes, so a manual toggle from the tray sticks until
+//! the next scheduled transition.
+
+use crate::core::{app_log, MeritStorage};
+use crate::models::{ListeningScheduleRule, ScheduleAction};
+use chrono::{Datelike, Local, NaiveDateTime, Timelike};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use rand::Rng;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+pub const MAX_SCHEDULE_RULES: usize = 100;
+pub const MAX_PAUSE_MINUTES: u32 = 24 * 60;
+const MINUTES_PER_DAY: u32 = 24 * 60;
+const POLL_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseReason {
+    Timer,
+    Schedule,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ListeningChanged {
+    pub enabled: bool,
+    pub reason: Option<PauseReason>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ListeningScheduleStatus {
+    pub listening: bool,
+    pub paused_by: Option<PauseReason>,
+    pub paused_until_ms: Option<i64>,
+}
+
+/// The schedule's last verdict; `None` until the first evaluation.
+static LAST_VERDICT: Lazy<Mutex<Option<Option<PauseReason>>>> = Lazy::new(|| Mutex::new(None));
+
+fn covers(rule: &ListeningScheduleRule, weekday: u8, minute: u32) -> bool {
+    let on = |day: u8| rule.weekdays.is_empty() || rule.weekdays.contains(&day);
+    let yesterday = (weekday + 6) % 7;
+    if rule.start_minute < rule.end_minute {
+        on(weekday) && (rule.start_minute..rule.end_minute).contains(&minute)
+    } else {
+        // Runs past midnight; the early-morning part belongs to the previous day's rule.
+        (on(weekday) && minute >= rule.start_minute) || (on(yesterday) && minute < rule.end_minute)
+    }
+}
+
+/// Why listening should be paused at `now`, or `None` if the schedule has no objection.
+pub fn scheduled_pause(
+    rules: &[ListeningScheduleRule],
+    paused_until_ms: Option<i64>,
+    now: NaiveDateTime,
+    now_ms: i64,
+) -> Option<PauseReason> {
+    if paused_until_ms.is_some_and(|until| until > now_ms) {
+        return Some(PauseReason::Timer);
+    }
+    let weekday = now.weekday().num_days_from_monday() as u8;
+    let minute = now.hour() * 60 + now.minute();
+    let mut has_focus_hours = false;
+    let mut in_focus_hours = false;
+    for rule in rules {
+        let active = covers(rule, weekday, minute);
+        match rule.action {
+            ScheduleAction::Pause if active => return Some(PauseReason::Schedule),
+            ScheduleAction::Pause => {}
+            ScheduleAction::Listen => {
+                has_focus_hours = true;
+                in_focus_hours |= active;
+            }
+        }
+    }
+    (has_focus_hours && !in_focus_hours).then_some(PauseReason::Schedule)
+}
+
+pub fn generate_id() -> String {
+    let now = chrono::Utc::now().timestamp_millis();
+    let rand_part: u32 = rand::thread_rng().gen();
+    format!("ls_{}_{:08x}", now, rand_part)
+}
+
+/// Clamps ranges into a day, drops empty ranges and unknown weekdays, and gives every rule a
+/// unique id.
+pub fn normalize(rules: Vec<ListeningScheduleRule>) -> Vec<ListeningScheduleRule> {
+    let mut seen = HashSet::new();
+    let mut out = Vec::with_capacity(rules.len().min(MAX_SCHEDULE_RULES));
+    for mut rule in rules.into_iter().take(MAX_SCHEDULE_RULES) {
+        rule.start_minute = rule.start_minute.min(MINUTES_PER_DAY - 1);
+        rule.end_minute = rule.end_minute.min(MINUTES_PER_DAY);
+        // Would otherwise read as a full day running past midnight, e.g. a defaulted 0/0 rule.
+        if rule.start_minute == rule.end_minute {
+            continue;
+        }
+        rule.weekdays.retain(|d| *d < 7);
+        rule.weekdays.sort_unstable();
+        rule.weekdays.dedup();
+
+        rule.id = rule.id.trim().to_string();
+        while !crate::core::app_rules::is_valid_id(&rule.id) || seen.contains(&rule.id) {
+            rule.id = generate_id();
+        }
+        seen.insert(rule.id.clone());
+        out.push(rule);
+    }
+    out
+}
+
+fn log_transition(app: &AppHandle, enabled: bool, reason: Option<PauseReason>, until: Option<i64>) {
+    let _ = app_log::append(
+        app,
+        app_log::AppLogRecord {
+            ts_ms: chrono::Utc::now().timestamp_millis(),
+            level: "info".to_string(),
+            scope: "listening_schedule".to_string(),
+            message: if enabled { "resumed" } else { "paused" }.to_string(),
+            data: Some(serde_json::json!({ "reason": reason, "paused_until_ms": until })),
+        },
+    );
+}
+
+/// Tells windows and the tray that listening was switched, by the schedule or by hand.
+pub fn notify_listening_changed(app: &AppHandle, enabled: bool, reason: Option<PauseReason>) {
+    let _ = app.emit("listening-changed", ListeningChanged { enabled, reason });
+    let _ = crate::tray_menu::refresh_tray_menu(app);
+}
+
+/// Re-checks the schedule and switches listening if its verdict changed since the last check.
+pub fn evaluate(app: &AppHandle) {
+    let now = Local::now();
+    let now_ms = now.timestamp_millis();
+    let (rules, until) = {
+        let storage = MeritStorage::instance();
+        let mut storage = storage.write();
+        let mut settings = storage.get_settings();
+        if settings
+            .listening_paused_until_ms
+            .is_some_and(|until| until <= now_ms)
+        {
+            settings.listening_paused_until_ms = None;
+            storage.set_settings(settings.clone());
+            crate::core::persistence::request_save();
+        }
+        (
+            settings.listening_schedule,
+            settings.listening_paused_until_ms,
+        )
+    };
+
+    let verdict = scheduled_pause(&rules, until, now.naive_local(), now_ms);
+    let previous = LAST_VERDICT.lock().replace(verdict);
+    match previous {
+        Some(previous) if previous == verdict => return,
+        // Nothing scheduled at startup: leave listening as it is.
+        None if verdict.is_none() => return,
+        _ => {}
+    }
+
+    let enabled = verdict.is_none();
+    crate::core::set_listening_enabled(enabled);
+    log_transition(app, enabled, verdict, until);
+    notify_listening_changed(app, enabled, verdict);
+}
+
+/// A manual start overrides the schedule until its next transition and cancels a pause timer.
+pub fn resume_manually() {
+    let now = Local::now();
+    let rules = {
+        let storage = MeritStorage::instance();
+        let mut storage = storage.write();
+        let mut settings = storage.get_settings();
+        if settings.listening_paused_until_ms.take().is_some() {
+            storage.set_settings(settings.clone());
+            crate::core::persistence::request_save();
+        }
+        settings.listening_schedule
+    };
+    let verdict = scheduled_pause(&rules, None, now.naive_local(), now.timestamp_millis());
+    *LAST_VERDICT.lock() = Some(verdict);
+}
+
+pub fn status() -> ListeningScheduleStatus {
+    let now = Local::now();
+    let settings = MeritStorage::instance().read().get_settings();
+    ListeningScheduleStatus {
+        listening: crate::core::is_listening_enabled(),
+        paused_by: scheduled_pause(
+            &settings.listening_schedule,
+            settings.listening_paused_until_ms,
+            now.naive_local(),
+            now.timestamp_millis(),
+        ),
+        paused_until_ms: settings.listening_paused_until_ms,
+    }
+}
+
+pub fn init(app_handle: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            evaluate(&app_handle);
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(
+        weekdays: &[u8],
+        start: u32,
+        end: u32,
+        action: ScheduleAction,
+    ) -> ListeningScheduleRule {
+        ListeningScheduleRule {
+            id: String::new(),
+            weekdays: weekdays.to_vec(),
+            start_minute: start,
+            end_minute: end,
+            action,
+        }
+    }
+
+    fn at(date: &str, hh: u32, mm: u32) -> NaiveDateTime {
+        chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .unwrap()
+            .and_hms_opt(hh, mm, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn pause_rules_focus_hours_and_timer() {
+        // 2024-03-04 is a Monday.
+        let night = normalize(vec![rule(&[4, 9], 22 * 60, 6 * 60, ScheduleAction::Pause)]);
+        assert_eq!(night[0].weekdays, [4]);
+        assert_eq!(
+            scheduled_pause(&night, None, at("2024-03-08", 23, 0), 0),
+            Some(PauseReason::Schedule)
+        );
+        assert_eq!(
+            scheduled_pause(&night, None, at("2024-03-09", 5, 59), 0),
+            Some(PauseReason::Schedule)
+        );
+        assert_eq!(
+            scheduled_pause(&night, None, at("2024-03-09", 6, 0), 0),
+            None
+        );
+        assert_eq!(
+            scheduled_pause(&night, None, at("2024-03-04", 23, 0), 0),
+            None
+        );
+
+        let work = vec![rule(
+            &[0, 1, 2, 3, 4],
+            9 * 60,
+            18 * 60,
+            ScheduleAction::Listen,
+        )];
+        assert_eq!(
+            scheduled_pause(&work, None, at("2024-03-04", 10, 0), 0),
+            None
+        );
+        assert_eq!(
+            scheduled_pause(&work, None, at("2024-03-04", 18, 0), 0),
+            Some(PauseReason::Schedule)
+        );
+        assert_eq!(
+            scheduled_pause(&work, None, at("2024-03-10", 10, 0), 0),
+            Some(PauseReason::Schedule)
+        );
+
+        assert_eq!(
+            scheduled_pause(&work, Some(1_000), at("2024-03-04", 10, 0), 999),
+            Some(PauseReason::Timer)
+        );
+        assert_eq!(
+            scheduled_pause(&work, Some(1_000), at("2024-03-04", 10, 0), 1_000),
+            None
+        );
+    }
+
+    #[test]
+    fn normalize_drops_empty_ranges() {
+        let rules = normalize(vec![
+            ListeningScheduleRule::default(),
+            rule(&[], 9 * 60, 9 * 60, ScheduleAction::Listen),
+            // Equal only after clamping the start into the day.
+            rule(&[], MINUTES_PER_DAY + 60, MINUTES_PER_DAY - 1, ScheduleAction::Pause),
+            rule(&[], 23 * 60, MINUTES_PER_DAY + 60, ScheduleAction::Pause),
+        ]);
+        assert_eq!(rules.len(), 1);
+        assert_eq!((rules[0].start_minute, rules[0].end_minute), (23 * 60, MINUTES_PER_DAY));
+    }
+}
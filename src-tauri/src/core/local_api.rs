@@ -0,0 +1,442 @@
+//! Opt-in, read-only HTTP/JSON API on `127.0.0.1` for dashboards and status bars.
+//!
+//! Every request must carry the token from settings, either as `Authorization: Bearer <token>` or
+//! as a `?token=` query parameter:
+//!
+//! ```text
+//! curl -H "Authorization: Bearer $TOKEN" http://127.0.0.1:47321/v1/today
+//! ```
+//!
+//! `/v1/heatmap.png` renders a display's heatmap the same way as the PNG export (`blur` is the
+//! gaussian sigma in pixels):
+//!
+//! ```text
+//! curl -o move.png "http://127.0.0.1:47321/v1/heatmap.png?token=$TOKEN&display=DP-1&kind=move&ramp=magma"
+//! ```
+
+use crate::core::heatmap_render::{ColorRamp, HeatScale, HeatmapRenderOptions};
+use crate::core::MeritStorage;
+use crate::models::click_heatmap::{CLICK_HEATMAP_BASE_COLS, CLICK_HEATMAP_BASE_ROWS};
+use crate::models::{HeatmapKind, Settings};
+use once_cell::sync::Lazy;
+use parking_lot::{Mutex, RwLock};
+use rand::Rng;
+use serde::Serialize;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddrV4, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+pub const DEFAULT_PORT: u16 = 47321;
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(150);
+const IO_TIMEOUT: Duration = Duration::from_secs(2);
+const JSON: &str = "application/json; charset=utf-8";
+const PNG: &str = "image/png";
+
+/// Status, content type and body.
+type Reply = (u16, &'static str, Vec<u8>);
+
+struct Server {
+    port: u16,
+    /// Shared with the accept loop, so a new token applies without rebinding the port.
+    token: Arc<RwLock<String>>,
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl Server {
+    /// Stops the accept loop and waits for it, so the port is free once this returns.
+    fn shut_down(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        let _ = self.thread.join();
+    }
+}
+
+static SERVER: Lazy<Mutex<Option<Server>>> = Lazy::new(|| Mutex::new(None));
+static LAST_ERROR: Lazy<Mutex<Option<String>>> = Lazy::new(|| Mutex::new(None));
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalApiStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    pub error: Option<String>,
+}
+
+pub fn generate_token() -> String {
+    let mut rng = rand::thread_rng();
+    (0..32)
+        .map(|_| format!("{:x}", rng.gen_range(0..16u8)))
+        .collect()
+}
+
+pub fn status() -> LocalApiStatus {
+    let server = SERVER.lock();
+    LocalApiStatus {
+        running: server.is_some(),
+        port: server.as_ref().map(|s| s.port),
+        error: LAST_ERROR.lock().clone(),
+    }
+}
+
+/// Starts, stops or restarts the server to match `settings`. A token change on the same port
+/// keeps the running listener.
+pub fn apply_settings(settings: &Settings) {
+    let mut server = SERVER.lock();
+    let wanted = settings.local_api_enabled && !settings.local_api_token.trim().is_empty();
+
+    if let Some(current) = server.as_ref() {
+        if wanted && current.port == settings.local_api_port {
+            *current.token.write() = settings.local_api_token.clone();
+            return;
+        }
+    }
+    if let Some(current) = server.take() {
+        current.shut_down();
+    }
+    *LAST_ERROR.lock() = None;
+    if !wanted {
+        return;
+    }
+
+    match start(settings.local_api_port, settings.local_api_token.clone()) {
+        Ok(started) => *server = Some(started),
+        Err(e) => {
+            eprintln!("{}", e);
+            *LAST_ERROR.lock() = Some(e);
+        }
+    }
+}
+
+fn start(port: u16, token: String) -> Result<Server, String> {
+    let listener = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, port))
+        .map_err(|e| format!("Failed to bind local API on 127.0.0.1:{}: {}", port, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("Failed to configure local API listener: {}", e))?;
+
+    let token = Arc::new(RwLock::new(token));
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = Arc::clone(&stop);
+    let thread_token = Arc::clone(&token);
+    let thread = std::thread::spawn(move || {
+        // Polling keeps shutdown simple: dropping the listener on stop frees the port.
+        while !thread_stop.load(Ordering::SeqCst) {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let token = thread_token.read().clone();
+                    if let Err(e) = handle_connection(stream, &token) {
+                        eprintln!("local API: {}", e);
+                    }
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+                Err(e) => {
+                    eprintln!("local API accept failed: {}", e);
+                    std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                }
+            }
+        }
+    });
+
+    Ok(Server {
+        port,
+        token,
+        stop,
+        thread,
+    })
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: Vec<(String, String)>,
+    bearer: Option<String>,
+}
+
+impl Request {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.query
+            .iter()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.as_str())
+            .filter(|v| !v.is_empty())
+    }
+}
+
+fn percent_decode(raw: &str) -> String {
+    let hex = |b: u8| (b as char).to_digit(16).map(|d| d as u8);
+    let bytes = raw.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' if i + 2 < bytes.len() => match (hex(bytes[i + 1]), hex(bytes[i + 2])) {
+                (Some(hi), Some(lo)) => {
+                    out.push(hi << 4 | lo);
+                    i += 2;
+                }
+                _ => out.push(b'%'),
+            },
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn parse_request(head: &str) -> Option<Request> {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let query = query
+        .split('&')
+        .filter(|p| !p.is_empty())
+        .map(|pair| {
+            let (k, v) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(k), percent_decode(v))
+        })
+        .collect();
+
+    let bearer = lines
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("authorization"))
+        .and_then(|(_, value)| value.trim().strip_prefix("Bearer ").map(|t| t.trim().to_string()));
+
+    Some(Request {
+        method,
+        path: path.to_string(),
+        query,
+        bearer,
+    })
+}
+
+fn token_matches(given: &str, expected: &str) -> bool {
+    // Length is not secret; compare the bytes without an early exit.
+    given.len() == expected.len()
+        && given
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn read_head(stream: &mut TcpStream) -> Result<String, String> {
+    let mut buf = Vec::with_capacity(1024);
+    let mut chunk = [0u8; 1024];
+    loop {
+        let n = stream
+            .read(&mut chunk)
+            .map_err(|e| format!("Failed to read request: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if buf.windows(4).any(|w| w == b"\r\n\r\n") {
+            break;
+        }
+        if buf.len() > MAX_REQUEST_BYTES {
+            return Err("request header too large".to_string());
+        }
+    }
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+fn handle_connection(mut stream: TcpStream, token: &str) -> Result<(), String> {
+    stream
+        .set_nonblocking(false)
+        .map_err(|e| format!("Failed to configure connection: {}", e))?;
+    let _ = stream.set_read_timeout(Some(IO_TIMEOUT));
+    let _ = stream.set_write_timeout(Some(IO_TIMEOUT));
+
+    let head = read_head(&mut stream)?;
+    let (status, content_type, body) = match parse_request(&head) {
+        Some(req) => respond(&req, token),
+        None => error(400, "malformed request"),
+    };
+    write_response(&mut stream, status, content_type, &body)
+}
+
+fn error(status: u16, message: &str) -> Reply {
+    (status, JSON, serde_json::json!({ "error": message }).to_string().into_bytes())
+}
+
+fn json<T: Serialize>(value: Result<T, String>) -> Reply {
+    match value.and_then(|v| {
+        serde_json::to_vec(&v).map_err(|e| format!("Failed to serialize response: {}", e))
+    }) {
+        Ok(body) => (200, JSON, body),
+        Err(e) => error(500, &e),
+    }
+}
+
+/// Parses a snake_case enum value such as `kind=move` the way serde would from JSON.
+fn enum_param<T: serde::de::DeserializeOwned>(req: &Request, name: &str) -> Result<Option<T>, String> {
+    req.param(name)
+        .map(|v| {
+            serde_json::from_value(serde_json::Value::String(v.to_string()))
+                .map_err(|_| format!("invalid {}: {}", name, v))
+        })
+        .transpose()
+}
+
+fn heatmap_png(req: &Request) -> Reply {
+    let Some(display_id) = req.param("display") else {
+        return error(400, "missing display");
+    };
+    let parsed = (|| -> Result<(HeatmapKind, HeatmapRenderOptions), String> {
+        let defaults = HeatmapRenderOptions::default();
+        let number = |name: &str| -> Result<Option<f64>, String> {
+            req.param(name)
+                .map(|v| v.parse::<f64>().map_err(|_| format!("invalid {}: {}", name, v)))
+                .transpose()
+        };
+        let options = HeatmapRenderOptions {
+            ramp: enum_param::<ColorRamp>(req, "ramp")?.unwrap_or(defaults.ramp),
+            scale: enum_param::<HeatScale>(req, "scale")?.unwrap_or(defaults.scale),
+            blur_sigma: number("blur")?.map(|v| v as f32).unwrap_or(defaults.blur_sigma),
+            transparent: req
+                .param("transparent")
+                .map(|v| !matches!(v, "0" | "false"))
+                .unwrap_or(defaults.transparent),
+            size: number("size")?.map(|v| v as u32).unwrap_or(defaults.size),
+        };
+        Ok((enum_param(req, "kind")?.unwrap_or_default(), options))
+    })();
+    let (kind, options) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => return error(400, &e),
+    };
+
+    let rendered = crate::core::click_heatmap::load_base_cells(kind, display_id, req.param("date"))
+        .and_then(|(cells, _)| {
+            crate::core::heatmap_render::render_png(
+                &cells,
+                CLICK_HEATMAP_BASE_COLS,
+                CLICK_HEATMAP_BASE_ROWS,
+                crate::core::click_heatmap::known_monitor_size(display_id),
+                &options,
+            )
+        });
+    match rendered {
+        Ok(png) => (200, PNG, png),
+        Err(e) => error(500, &e),
+    }
+}
+
+fn respond(req: &Request, token: &str) -> Reply {
+    let given = req.bearer.as_deref().or_else(|| req.param("token"));
+    if !given.is_some_and(|t| token_matches(t, token)) {
+        return error(401, "missing or invalid token");
+    }
+    if req.method != "GET" {
+        return error(405, "only GET is supported");
+    }
+
+    match req.path.trim_end_matches('/') {
+        "/v1/stats" => json(Ok(MeritStorage::instance().read().get_stats().lite())),
+        "/v1/today" => json(Ok(MeritStorage::instance().read().get_stats().today)),
+        "/v1/recent" => {
+            let days = req
+                .param("days")
+                .and_then(|v| v.parse::<usize>().ok())
+                .unwrap_or(7)
+                .clamp(1, 4000);
+            let today = MeritStorage::instance().read().get_stats().today.lite();
+            json(
+                crate::core::history_db::load_recent_days_lite(days - 1).map(|mut history| {
+                    history.retain(|d| d.date != today.date);
+                    history.truncate(days - 1);
+                    history.insert(0, today);
+                    history
+                }),
+            )
+        }
+        "/v1/aggregates" => {
+            let (start, end) = (req.param("start"), req.param("end"));
+            if let (Some(a), Some(b)) = (start, end) {
+                if a > b {
+                    return error(400, "invalid date range: start > end");
+                }
+            }
+            json(crate::core::history_db::load_statistics_aggregates(start, end))
+        }
+        "/v1/heatmap.png" => heatmap_png(req),
+        _ => error(404, "not found"),
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+fn write_response(
+    stream: &mut TcpStream,
+    status: u16,
+    content_type: &str,
+    body: &[u8],
+) -> Result<(), String> {
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        status,
+        reason(status),
+        content_type,
+        body.len()
+    );
+    stream
+        .write_all(head.as_bytes())
+        .and_then(|_| stream.write_all(body))
+        .map_err(|e| format!("Failed to write response: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_query_and_bearer_token() {
+        let req = parse_request(
+            "GET /v1/aggregates?start=2024-01-01&end=2024%2D01%2D31 HTTP/1.1\r\nHost: x\r\nauthorization: Bearer abc\r\n\r\n",
+        )
+        .unwrap();
+        assert_eq!(req.path, "/v1/aggregates");
+        assert_eq!(req.param("end"), Some("2024-01-31"));
+        assert_eq!(req.bearer.as_deref(), Some("abc"));
+
+        assert_eq!(respond(&req, "abd").0, 401);
+        let req = parse_request("GET /nope?token=abc HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(respond(&req, "abc").0, 404);
+
+        let req = parse_request("GET /v1/heatmap.png?token=abc HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(respond(&req, "abc").0, 400);
+        let req =
+            parse_request("GET /v1/heatmap.png?token=abc&display=DP-1&ramp=jet HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(respond(&req, "abc").0, 400);
+    }
+
+    #[test]
+    fn shut_down_frees_the_port_for_a_restart() {
+        let port = TcpListener::bind(SocketAddrV4::new(Ipv4Addr::LOCALHOST, 0))
+            .and_then(|l| l.local_addr())
+            .unwrap()
+            .port();
+        start(port, "abc".into()).unwrap().shut_down();
+        let server = start(port, "def".into()).unwrap();
+        assert_eq!(*server.token.read(), "def");
+        server.shut_down();
+    }
+}
@@ -4,7 +4,8 @@ use std::sync::mpsc;
 pub enum RawMouseButton {
     Left,
     Right,
-    Other,
+    /// `kCGMouseEventButtonNumber` (2 = middle, 3/4 = back/forward).
+    Other(i64),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -14,7 +15,10 @@ pub enum RawInputEvent {
     /// `keycode` is the inferred *logical* keycode (after macOS modifier remaps).
     KeyDown { keycode: u16, flags: u64 },
     MouseDown { button: RawMouseButton, x: f64, y: f64 },
+    MouseUp { button: RawMouseButton },
     MouseMove { x: f64, y: f64 },
+    /// Scroll deltas in lines; momentum (inertia) events are dropped.
+    Scroll { dx: f64, dy: f64 },
 }
 
 // CoreGraphics constants: CGEventFlags (CGEventTypes.h)
@@ -240,10 +244,14 @@ mod imp {
     const K_CG_EVENT_LEFT_MOUSE_DOWN: CGEventType = 1;
     const K_CG_EVENT_RIGHT_MOUSE_DOWN: CGEventType = 3;
     const K_CG_EVENT_OTHER_MOUSE_DOWN: CGEventType = 25;
+    const K_CG_EVENT_LEFT_MOUSE_UP: CGEventType = 2;
+    const K_CG_EVENT_RIGHT_MOUSE_UP: CGEventType = 4;
+    const K_CG_EVENT_OTHER_MOUSE_UP: CGEventType = 26;
     const K_CG_EVENT_MOUSE_MOVED: CGEventType = 5;
     const K_CG_EVENT_LEFT_MOUSE_DRAGGED: CGEventType = 6;
     const K_CG_EVENT_RIGHT_MOUSE_DRAGGED: CGEventType = 7;
     const K_CG_EVENT_OTHER_MOUSE_DRAGGED: CGEventType = 27;
+    const K_CG_EVENT_SCROLL_WHEEL: CGEventType = 22;
     const K_CG_EVENT_KEY_DOWN: CGEventType = 10;
     const K_CG_EVENT_FLAGS_CHANGED: CGEventType = 12;
 
@@ -252,6 +260,12 @@ mod imp {
 
     // CoreGraphics constant: kCGKeyboardEventKeycode
     const K_CG_KEYBOARD_EVENT_KEYCODE: CGEventField = 9;
+    // CoreGraphics constant: kCGMouseEventButtonNumber
+    const K_CG_MOUSE_EVENT_BUTTON_NUMBER: CGEventField = 3;
+    // CoreGraphics constants: kCGScrollWheelEventFixedPtDeltaAxis1/2, kCGScrollWheelEventMomentumPhase
+    const K_CG_SCROLL_WHEEL_EVENT_FIXED_PT_DELTA_AXIS_1: CGEventField = 93;
+    const K_CG_SCROLL_WHEEL_EVENT_FIXED_PT_DELTA_AXIS_2: CGEventField = 94;
+    const K_CG_SCROLL_WHEEL_EVENT_MOMENTUM_PHASE: CGEventField = 123;
 
     #[link(name = "CoreGraphics", kind = "framework")]
     extern "C" {
@@ -272,6 +286,7 @@ mod imp {
         fn CGEventTapEnable(tap: CFMachPortRef, enable: bool);
 
         fn CGEventGetIntegerValueField(event: CGEventRef, field: CGEventField) -> i64;
+        fn CGEventGetDoubleValueField(event: CGEventRef, field: CGEventField) -> f64;
         fn CGEventGetFlags(event: CGEventRef) -> CGEventFlags;
         fn CGEventGetLocation(event: CGEventRef) -> CGPoint;
     }
@@ -378,12 +393,29 @@ mod imp {
                 }
                 K_CG_EVENT_OTHER_MOUSE_DOWN => {
                     let p = unsafe { CGEventGetLocation(event) };
+                    let number = unsafe {
+                        CGEventGetIntegerValueField(event, K_CG_MOUSE_EVENT_BUTTON_NUMBER)
+                    };
                     RawInputEvent::MouseDown {
-                        button: super::RawMouseButton::Other,
+                        button: super::RawMouseButton::Other(number),
                         x: p.x,
                         y: p.y,
                     }
                 }
+                K_CG_EVENT_LEFT_MOUSE_UP => RawInputEvent::MouseUp {
+                    button: super::RawMouseButton::Left,
+                },
+                K_CG_EVENT_RIGHT_MOUSE_UP => RawInputEvent::MouseUp {
+                    button: super::RawMouseButton::Right,
+                },
+                K_CG_EVENT_OTHER_MOUSE_UP => {
+                    let number = unsafe {
+                        CGEventGetIntegerValueField(event, K_CG_MOUSE_EVENT_BUTTON_NUMBER)
+                    };
+                    RawInputEvent::MouseUp {
+                        button: super::RawMouseButton::Other(number),
+                    }
+                }
                 K_CG_EVENT_MOUSE_MOVED
                 | K_CG_EVENT_LEFT_MOUSE_DRAGGED
                 | K_CG_EVENT_RIGHT_MOUSE_DRAGGED
@@ -391,6 +423,21 @@ mod imp {
                     let p = unsafe { CGEventGetLocation(event) };
                     RawInputEvent::MouseMove { x: p.x, y: p.y }
                 }
+                K_CG_EVENT_SCROLL_WHEEL => {
+                    let momentum = unsafe {
+                        CGEventGetIntegerValueField(event, K_CG_SCROLL_WHEEL_EVENT_MOMENTUM_PHASE)
+                    };
+                    if momentum != 0 {
+                        return;
+                    }
+                    let dy = unsafe {
+                        CGEventGetDoubleValueField(event, K_CG_SCROLL_WHEEL_EVENT_FIXED_PT_DELTA_AXIS_1)
+                    };
+                    let dx = unsafe {
+                        CGEventGetDoubleValueField(event, K_CG_SCROLL_WHEEL_EVENT_FIXED_PT_DELTA_AXIS_2)
+                    };
+                    RawInputEvent::Scroll { dx, dy }
+                }
                 _ => return,
             };
 
@@ -426,10 +473,14 @@ mod imp {
             K_CG_EVENT_LEFT_MOUSE_DOWN,
             K_CG_EVENT_RIGHT_MOUSE_DOWN,
             K_CG_EVENT_OTHER_MOUSE_DOWN,
+            K_CG_EVENT_LEFT_MOUSE_UP,
+            K_CG_EVENT_RIGHT_MOUSE_UP,
+            K_CG_EVENT_OTHER_MOUSE_UP,
             K_CG_EVENT_MOUSE_MOVED,
             K_CG_EVENT_LEFT_MOUSE_DRAGGED,
             K_CG_EVENT_RIGHT_MOUSE_DRAGGED,
             K_CG_EVENT_OTHER_MOUSE_DRAGGED,
+            K_CG_EVENT_SCROLL_WHEEL,
         ]);
 
         let tap = unsafe {
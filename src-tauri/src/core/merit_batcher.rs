@@ -1,6 +1,6 @@
 use crate::core::merit_storage::{KeyboardCounts, MouseCounts};
 use crate::core::MeritStorage;
-use crate::models::{InputEvent, InputOrigin, InputSource};
+use crate::models::{InputEvent, InputOrigin, InputSource, KeyPrivacyLevel};
 use once_cell::sync::Lazy;
 use rand::rngs::SmallRng;
 use rand::{Rng, SeedableRng};
@@ -11,6 +11,9 @@ use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tauri::{AppHandle, Emitter, Manager};
 
 use super::active_app::AppContext;
+use super::app_rules::AppCounting;
+use super::key_privacy;
+use super::typing_speed;
 
 const MAX_DIGIT: u64 = 9;
 const ANIM_EMIT_INTERVAL: Duration = Duration::from_millis(120);
@@ -156,13 +159,61 @@ pub fn enqueue_merit_trigger(
     );
 }
 
+/// Drops input from ignored apps and strips key detail per app rule and `key_privacy_level`. Runs
+/// before aggregation, which loses the link between keys and the app they were typed in.
+fn apply_counting_rules(triggers: &mut Vec<Trigger>) -> u64 {
+    let storage = MeritStorage::instance();
+    let storage = storage.read();
+    let level = storage.key_privacy_level();
+    let mut cache: HashMap<Arc<str>, AppCounting> = HashMap::new();
+    let mut typed = 0u64;
+    triggers.retain_mut(|trigger| {
+        let counting = match trigger.app.as_ref() {
+            Some(app) if trigger.key.origin == InputOrigin::Global => *cache
+                .entry(Arc::clone(&app.id))
+                .or_insert_with(|| storage.app_counting(trigger.key.origin, Some(app))),
+            app => storage.app_counting(trigger.key.origin, app),
+        };
+        if counting == AppCounting::Ignore {
+            return false;
+        }
+        if trigger.key.source != InputSource::Keyboard {
+            return true;
+        }
+        if trigger.key.origin == InputOrigin::Global
+            && typing_speed::is_typing_key(trigger.key_code.as_deref(), trigger.shortcut.as_deref())
+        {
+            typed = typed.saturating_add(trigger.count);
+        }
+        if counting == AppCounting::CountWithoutDetails || level == KeyPrivacyLevel::TotalsOnly {
+            trigger.key_code = None;
+            trigger.is_shifted = None;
+            trigger.shortcut = None;
+        } else if level == KeyPrivacyLevel::Categories {
+            trigger.key_code = trigger
+                .key_code
+                .take()
+                .and_then(|code| key_privacy::reduce_code(level, &code));
+            trigger.shortcut = trigger
+                .shortcut
+                .take()
+                .and_then(|shortcut| key_privacy::reduce_shortcut(level, &shortcut));
+        }
+        true
+    });
+    typed
+}
+
 fn process_triggers(
-    triggers: Vec<Trigger>,
+    mut triggers: Vec<Trigger>,
     rng: &mut SmallRng,
     anim: &mut HashMap<Key, AnimState>,
     stats_dirty: &mut bool,
     stats_handle: &mut Option<AppHandle>,
 ) {
+    // Global typing keystrokes that were kept, counted before key codes are stripped.
+    let typed = apply_counting_rules(&mut triggers);
+
     let mut by_key: HashMap<Key, (u64, AppHandle)> = HashMap::new();
     let mut by_app: HashMap<(InputOrigin, InputSource, Arc<str>), (u64, Option<Arc<str>>)> =
         HashMap::new();
@@ -231,6 +282,7 @@ fn process_triggers(
                         .and_modify(|v| *v = v.saturating_add(trigger.count))
                         .or_insert(trigger.count);
                 }
+                InputSource::Scroll => {}
             }
         }
 
@@ -259,7 +311,9 @@ fn process_triggers(
     }
 
     let mut allowed: HashMap<Key, bool> = HashMap::new();
-    {
+    let mut timeline = crate::core::history_db::TimelineDelta::default();
+    let mut session_apps: Vec<crate::core::sessions::AppCount> = Vec::new();
+    let (timeline_bucket_minutes, session_idle_secs) = {
         let storage = MeritStorage::instance();
         let mut storage = storage.write();
         for (key, (count, _)) in &by_key {
@@ -270,20 +324,29 @@ fn process_triggers(
                     key_counts_shifted: keyboard_key_counts_shifted.get(&key.origin),
                     shortcut_counts: shortcut_counts.get(&key.origin),
                 }),
-                InputSource::MouseSingle => None,
+                InputSource::MouseSingle | InputSource::Scroll => None,
             };
 
             let mouse = match key.source {
-                InputSource::Keyboard => None,
                 InputSource::MouseSingle => Some(MouseCounts {
                     mouse_button_counts: mouse_button_counts.get(&key.origin),
                 }),
+                InputSource::Keyboard | InputSource::Scroll => None,
             };
 
             let added = storage.add_merit_silent(key.origin, key.source, *count, keyboard, mouse);
             allowed.insert(*key, added);
             if added {
                 *stats_dirty = true;
+                match key.source {
+                    InputSource::Keyboard => {
+                        timeline.keyboard = timeline.keyboard.saturating_add(*count)
+                    }
+                    InputSource::MouseSingle => {
+                        timeline.mouse_single = timeline.mouse_single.saturating_add(*count)
+                    }
+                    InputSource::Scroll => {}
+                }
             }
         }
 
@@ -294,8 +357,31 @@ fn process_triggers(
             };
             if storage.add_app_merit_silent(*origin, *source, *count, Some(&app)) {
                 *stats_dirty = true;
+                session_apps.push((app.id, app.name, *count));
             }
         }
+
+        (
+            storage.timeline_bucket_minutes(),
+            storage.session_idle_threshold_secs(),
+        )
+    };
+    crate::core::sessions::record_input(
+        timeline.keyboard,
+        timeline.mouse_single,
+        &session_apps,
+        session_idle_secs,
+    );
+    crate::core::history_db::record_timeline(timeline, timeline_bucket_minutes);
+    let keyboard_counted = allowed
+        .get(&Key {
+            origin: InputOrigin::Global,
+            source: InputSource::Keyboard,
+        })
+        .copied()
+        .unwrap_or(false);
+    if keyboard_counted {
+        typing_speed::record_keys(typed);
     }
 
     if !*stats_dirty {
@@ -407,6 +493,19 @@ fn emit_stats_updated(app_handle: &AppHandle) {
         "merit-updated",
         &stats,
     );
+
+    let speed = typing_speed::current();
+    if crate::core::main_window_bounds::is_visible() {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.emit("typing-speed", &speed);
+        }
+    }
+    crate::core::ui_emit::emit_to_any_visible_windows(
+        app_handle,
+        &["settings", "custom_statistics"],
+        "typing-speed",
+        &speed,
+    );
     crate::core::persistence::request_save();
 }
 
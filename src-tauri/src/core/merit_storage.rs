@@ -5,6 +5,7 @@ use crate::models::{
     CustomStatisticsTemplate,
     InputOrigin,
     InputSource,
+    KeyPrivacyLevel,
     MeritStats,
     Settings,
     WindowPlacement,
@@ -18,10 +19,17 @@ use std::sync::Arc;
 use tauri::{AppHandle, Emitter, Manager};
 
 use super::active_app::AppContext;
+use super::app_rules::{self, AppCounting};
+use super::key_codes;
+use super::key_privacy;
+use super::mouse_gestures::MouseGesture;
 
 static STORAGE: Lazy<Arc<RwLock<MeritStorage>>> =
     Lazy::new(|| Arc::new(RwLock::new(MeritStorage::new())));
 
+const MAX_ACHIEVEMENT_HISTORY: usize = 800;
+const MAX_ACHIEVEMENT_INDEX: usize = 1800;
+
 pub struct KeyboardCounts<'a> {
     pub key_counts: Option<&'a HashMap<Arc<str>, u64>>,
     pub key_counts_unshifted: Option<&'a HashMap<Arc<str>, u64>>,
@@ -70,6 +78,22 @@ impl MeritStorage {
         self.settings.clone()
     }
 
+    pub fn timeline_bucket_minutes(&self) -> u32 {
+        self.settings.timeline_bucket_minutes
+    }
+
+    pub fn session_idle_threshold_secs(&self) -> u32 {
+        self.settings.session_idle_threshold_secs
+    }
+
+    pub fn key_privacy_level(&self) -> KeyPrivacyLevel {
+        self.settings.key_privacy_level
+    }
+
+    pub fn key_bigram_timeout_ms(&self) -> u32 {
+        self.settings.key_bigram_timeout_ms
+    }
+
     pub fn set_settings(&mut self, settings: Settings) {
         self.settings = settings;
     }
@@ -87,9 +111,6 @@ impl MeritStorage {
         records: Vec<AchievementUnlockRecord>,
         app_handle: &AppHandle,
     ) -> Vec<AchievementUnlockRecord> {
-        const MAX_HISTORY: usize = 800;
-        const MAX_INDEX: usize = 1800;
-
         let mut inserted: Vec<AchievementUnlockRecord> = Vec::new();
         if records.is_empty() {
             return inserted;
@@ -130,16 +151,16 @@ impl MeritStorage {
         self.achievements
             .unlock_index
             .sort_by(|a, b| b.unlocked_at_ms.cmp(&a.unlocked_at_ms));
-        if self.achievements.unlock_index.len() > MAX_INDEX {
-            self.achievements.unlock_index.truncate(MAX_INDEX);
+        if self.achievements.unlock_index.len() > MAX_ACHIEVEMENT_INDEX {
+            self.achievements.unlock_index.truncate(MAX_ACHIEVEMENT_INDEX);
         }
 
         self.achievements.unlock_history.extend(inserted.clone());
         self.achievements
             .unlock_history
             .sort_by(|a, b| b.unlocked_at_ms.cmp(&a.unlocked_at_ms));
-        if self.achievements.unlock_history.len() > MAX_HISTORY {
-            self.achievements.unlock_history.truncate(MAX_HISTORY);
+        if self.achievements.unlock_history.len() > MAX_ACHIEVEMENT_HISTORY {
+            self.achievements.unlock_history.truncate(MAX_ACHIEVEMENT_HISTORY);
         }
 
         let _ = app_handle.emit("achievements-updated", self.achievements.clone());
@@ -147,6 +168,15 @@ impl MeritStorage {
         inserted
     }
 
+    pub fn merge_achievements(&mut self, other: &AchievementState, app_handle: &AppHandle) -> usize {
+        let added = self.achievements.merge_from(other);
+        self.achievements.unlock_index.truncate(MAX_ACHIEVEMENT_INDEX);
+        self.achievements.unlock_history.truncate(MAX_ACHIEVEMENT_HISTORY);
+        let _ = app_handle.emit("achievements-updated", self.achievements.clone());
+        crate::core::persistence::request_save();
+        added
+    }
+
     pub fn clear_achievement_history(&mut self, app_handle: &AppHandle) {
         self.achievements.unlock_history.clear();
         let _ = app_handle.emit("achievements-updated", self.achievements.clone());
@@ -212,7 +242,7 @@ impl MeritStorage {
         keyboard: Option<KeyboardCounts<'_>>,
         mouse: Option<MouseCounts<'_>>,
     ) -> bool {
-        if !self.should_count(origin, source) || count == 0 {
+        if !self.should_count(origin, source, None) || count == 0 {
             return false;
         }
 
@@ -242,6 +272,7 @@ impl MeritStorage {
                     }
                 }
             }
+            InputSource::Scroll => {}
         }
         true
     }
@@ -253,7 +284,7 @@ impl MeritStorage {
         count: u64,
         app: Option<&AppContext>,
     ) -> bool {
-        if !self.should_count(origin, source) || count == 0 {
+        if !self.should_count(origin, source, app) || count == 0 {
             return false;
         }
 
@@ -285,6 +316,52 @@ impl MeritStorage {
         true
     }
 
+    /// Scroll activity is tracked with mouse input, independent of whether it counts as merit.
+    pub fn add_scroll_silent(
+        &mut self,
+        app: &AppContext,
+        ticks: u64,
+        vertical: u64,
+        horizontal: u64,
+    ) -> bool {
+        if ticks == 0 && vertical == 0 && horizontal == 0 {
+            return false;
+        }
+
+        if !self.settings.enable_mouse_single
+            || self.app_counting(InputOrigin::Global, Some(app)) == AppCounting::Ignore
+        {
+            return false;
+        }
+
+        self.stats
+            .add_scroll(&app.id, app.name.as_ref(), ticks, vertical, horizontal);
+        self.drain_history_to_db();
+        true
+    }
+
+    /// Double-clicks and drags with `button` count only where a press of that button would.
+    pub fn add_mouse_gesture_silent(
+        &mut self,
+        app: &AppContext,
+        button: &str,
+        gesture: MouseGesture,
+    ) -> bool {
+        if !self.settings.enable_mouse_single
+            || !self.mouse_button_counted(button)
+            || self.app_counting(InputOrigin::Global, Some(app)) == AppCounting::Ignore
+        {
+            return false;
+        }
+
+        match gesture {
+            MouseGesture::DoubleClick => self.stats.add_double_click(),
+            MouseGesture::Drag { distance_px } => self.stats.add_drag(distance_px),
+        }
+        self.drain_history_to_db();
+        true
+    }
+
     pub fn clear_history(&mut self, app_handle: &AppHandle) {
         self.stats.clear_history();
         let stats = self.stats.lite();
@@ -319,6 +396,44 @@ impl MeritStorage {
         crate::core::persistence::request_save();
     }
 
+    /// Date key of the live day after rolling it over to the current date. Callers that also
+    /// write the live day must keep holding the lock, or the key can go stale at midnight.
+    pub fn live_date_key(&mut self) -> String {
+        self.stats.normalize_today();
+        self.stats.today.date.to_string()
+    }
+
+    pub fn merge_imported_history(
+        &mut self,
+        today: Option<&crate::models::DailyStats>,
+        imported_total: u64,
+        app_handle: &AppHandle,
+    ) {
+        self.stats.merge_imported(today, imported_total);
+        self.drain_history_to_db();
+        let stats = self.stats.lite();
+        if crate::core::main_window_bounds::is_visible() {
+            if let Some(window) = app_handle.get_webview_window("main") {
+                let _ = window.emit("merit-updated", &stats);
+            }
+        }
+        crate::core::ui_emit::emit_to_any_visible_windows(
+            app_handle,
+            &["settings", "custom_statistics"],
+            "merit-updated",
+            &stats,
+        );
+        crate::core::persistence::request_save();
+    }
+
+    /// Reduces the key and shortcut counters held in memory to `level`.
+    pub fn scrub_key_details(&mut self, level: KeyPrivacyLevel) {
+        key_privacy::reduce_day(&mut self.stats.today, level);
+        for day in &mut self.stats.history {
+            key_privacy::reduce_day(day, level);
+        }
+    }
+
     pub fn update_settings(&mut self, settings: Settings) {
         self.settings = settings;
         crate::core::persistence::request_save();
@@ -364,15 +479,44 @@ impl MeritStorage {
         crate::core::persistence::request_save();
     }
 
-    fn should_count(&self, origin: InputOrigin, source: InputSource) -> bool {
-        match origin {
+    /// Applies `Settings::app_rules` to global input attributed to `app`.
+    pub fn app_counting(&self, origin: InputOrigin, app: Option<&AppContext>) -> AppCounting {
+        if origin == InputOrigin::App || self.settings.app_rules.is_empty() {
+            return AppCounting::Count;
+        }
+        match app {
+            Some(app) => app_rules::resolve(&self.settings.app_rules, app),
+            None => app_rules::resolve(&self.settings.app_rules, &AppContext::unknown()),
+        }
+    }
+
+    /// Whether a global keystroke in `app` may be recorded by key code.
+    pub fn key_details_allowed(&self, app: &AppContext) -> bool {
+        self.settings.enable_keyboard
+            && self.settings.key_privacy_level != KeyPrivacyLevel::TotalsOnly
+            && self.app_counting(InputOrigin::Global, Some(app)) == AppCounting::Count
+    }
+
+    /// Whether a global press of the mouse button `code` counts as merit.
+    pub fn mouse_button_counted(&self, code: &str) -> bool {
+        if code == "MouseMiddle" {
+            return self.settings.enable_mouse_middle;
+        }
+        !key_codes::is_side_mouse_button(code) || self.settings.enable_mouse_side_buttons
+    }
+
+    /// `app` is `None` for totals already filtered per app (see `merit_batcher`).
+    fn should_count(&self, origin: InputOrigin, source: InputSource, app: Option<&AppContext>) -> bool {
+        let enabled = match origin {
             // Explicit in-app action should always count, independent of global input listening toggles.
             InputOrigin::App => true,
             InputOrigin::Global => match source {
                 InputSource::Keyboard => self.settings.enable_keyboard,
                 InputSource::MouseSingle => self.settings.enable_mouse_single,
+                InputSource::Scroll => self.settings.enable_scroll,
             },
-        }
+        };
+        enabled && (app.is_none() || self.app_counting(origin, app) != AppCounting::Ignore)
     }
 
     fn drain_history_to_db(&mut self) {
@@ -381,5 +525,7 @@ impl MeritStorage {
         }
         let days = std::mem::take(&mut self.stats.history);
         crate::core::history_db::enqueue_bulk_upsert_daily(days);
+        // A day just rolled over; a good moment to drop expired rows.
+        crate::core::history_db::enqueue_prune();
     }
 }
@@ -1,25 +1,43 @@
 pub mod active_app;
 pub mod app_icons;
 pub mod app_log;
+pub mod app_rules;
 pub mod app_updater;
 pub mod activity;
+pub mod backup;
 pub mod auto_updater;
 pub mod click_heatmap;
+pub mod data_export;
+pub mod data_import;
 pub mod date_key;
+#[cfg(target_os = "linux")]
+pub mod evdev_input;
+pub mod finger_map;
+pub mod heatmap_render;
 pub mod intern;
 pub mod history_db;
 pub mod notification_env;
 pub mod perf;
 pub mod keyboard_piano;
 pub mod input_listener;
+pub mod key_bigrams;
 pub mod key_codes;
+pub mod key_privacy;
+pub mod layout_analysis;
+pub mod listening_schedule;
+pub mod local_api;
 pub mod macos_event_tap;
 pub mod macos_permissions;
 pub mod main_window_bounds;
 pub mod merit_batcher;
 pub mod merit_storage;
 pub mod mouse_distance;
+pub mod mouse_gestures;
+pub mod mouse_scroll;
 pub mod persistence;
+pub mod sessions;
+pub mod typing_accuracy;
+pub mod typing_speed;
 pub mod ui_emit;
 pub mod window_placement;
 pub mod wooden_fish_skins;
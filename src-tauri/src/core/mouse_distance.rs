@@ -16,6 +16,8 @@ const FLUSH_INTERVAL: Duration = Duration::from_millis(650);
 const MONITOR_REFRESH_INTERVAL: Duration = Duration::from_secs(30);
 const MAX_JUMP_PX: f64 = 2400.0;
 const SEND_INTERVAL_MS: u64 = 90;
+/// Minimum gap between cursor position samples for the movement heatmap.
+const HEATMAP_SAMPLE_INTERVAL_MS: u64 = 100;
 
 #[derive(Debug, Clone, Default)]
 struct CursorState {
@@ -94,6 +96,7 @@ struct LocalState {
     current_display_id: Option<Arc<str>>,
     current_mp: u64,
     last_send_ms: u64,
+    last_sample_ms: u64,
     had_tracking_enabled: bool,
 }
 
@@ -183,13 +186,14 @@ pub fn init(app_handle: AppHandle) {
                 continue;
             }
 
-            let (stats, carry) = crate::core::perf::time(
+            let (stats, carry, timeline) = crate::core::perf::time(
                 crate::core::perf::TimerKind::MouseDistanceFlush,
                 || {
                     let mut carry: HashMap<Arc<str>, u64> = HashMap::new();
                     let storage = MeritStorage::instance();
                     let mut storage = storage.write();
                     let mut changed = false;
+                    let mut recorded_px = 0u64;
 
                     for (display_id, mp) in drained {
                         if mp == 0 {
@@ -212,14 +216,24 @@ pub fn init(app_handle: AppHandle) {
                             px,
                         ) {
                             changed = true;
+                            recorded_px = recorded_px.saturating_add(px);
                         }
                     }
 
                     let stats = changed.then(|| storage.get_stats().lite());
-                    (stats, carry)
+                    (stats, carry, (recorded_px, storage.timeline_bucket_minutes()))
                 },
             );
 
+            let (recorded_px, bucket_minutes) = timeline;
+            crate::core::history_db::record_timeline(
+                crate::core::history_db::TimelineDelta {
+                    distance_px: recorded_px,
+                    ..Default::default()
+                },
+                bucket_minutes,
+            );
+
             let Some(stats) = stats else {
                 // No effective change (e.g. tracking disabled while flushing).
                 last_flush = Instant::now();
@@ -276,18 +290,9 @@ pub fn record_mouse_move(space: CoordinateSpace, x: f64, y: f64) {
         let now = now_ms();
         LOCAL.with(|local| {
             let mut local = local.borrow_mut();
-
-            if !enabled {
-                if local.had_tracking_enabled {
-                    local.cursor = CursorState::default();
-                    local.current_display_id = None;
-                    local.current_mp = 0;
-                    local.last_send_ms = 0;
-                    local.had_tracking_enabled = false;
-                }
+            if !sync_tracking_enabled(&mut local, enabled) {
                 return;
             }
-            local.had_tracking_enabled = true;
 
             let monitors_version = MONITORS_VERSION.load(Ordering::Relaxed);
             if local.cursor.monitors_version != monitors_version {
@@ -345,21 +350,20 @@ pub fn record_mouse_move(space: CoordinateSpace, x: f64, y: f64) {
                 let dx = px - local.cursor.x;
                 let dy = py - local.cursor.y;
                 if dx.is_finite() && dy.is_finite() && dx.abs() <= MAX_JUMP_PX && dy.abs() <= MAX_JUMP_PX {
-                    let dist = (dx * dx + dy * dy).sqrt();
-                    if dist.is_finite() && dist > 0.0 {
-                        let mp = (dist * MP_PER_PX as f64).round();
-                        if mp.is_finite() && mp > 0.0 {
-                            let mp_u64 = mp as u64;
-                            let cur_id = local.current_display_id.as_ref();
-                            if cur_id.is_none()
-                                || cur_id.is_some_and(|id| id.as_ref() != display_id.as_ref())
-                            {
-                                flush_local(&mut local);
-                                local.current_display_id = Some(Arc::clone(&display_id));
-                            }
-                            local.current_mp = local.current_mp.saturating_add(mp_u64);
-                        }
-                    }
+                    accumulate(&mut local, &display_id, (dx * dx + dy * dy).sqrt());
+                }
+            }
+
+            if let Some(m) = monitor.as_ref() {
+                if now.saturating_sub(local.last_sample_ms) >= HEATMAP_SAMPLE_INTERVAL_MS {
+                    local.last_sample_ms = now;
+                    crate::core::click_heatmap::record_move_sample(
+                        &m.id,
+                        px - m.pos_x,
+                        py - m.pos_y,
+                        m.width,
+                        m.height,
+                    );
                 }
             }
 
@@ -367,15 +371,81 @@ pub fn record_mouse_move(space: CoordinateSpace, x: f64, y: f64) {
             local.cursor.y = py;
             local.cursor.has_position = true;
             local.cursor.monitor = monitor;
+            send_if_due(&mut local, now);
+        });
+    })
+}
+
+/// Relative motion from raw devices (evdev), where the absolute cursor position is unknown.
+///
+/// Device counts are taken as pixels, so pointer acceleration is not reflected. Distance goes to
+/// the only monitor when there is exactly one, otherwise to the unknown display.
+pub fn record_relative_move(dx: f64, dy: f64) {
+    crate::core::perf::time(crate::core::perf::TimerKind::MouseDistanceMove, || {
+        if !(dx.is_finite() && dy.is_finite()) || dx.abs() > MAX_JUMP_PX || dy.abs() > MAX_JUMP_PX {
+            return;
+        }
 
-            if local.current_mp > 0 && now.saturating_sub(local.last_send_ms) >= SEND_INTERVAL_MS {
-                flush_local(&mut local);
-                local.last_send_ms = now;
+        let enabled = TRACKING_ENABLED.load(Ordering::Relaxed);
+        let now = now_ms();
+        LOCAL.with(|local| {
+            let mut local = local.borrow_mut();
+            if !sync_tracking_enabled(&mut local, enabled) {
+                return;
             }
+
+            let display_id = match MONITORS.read().as_slice() {
+                [only] => Arc::clone(&only.id),
+                _ => Arc::clone(&UNKNOWN_DISPLAY_ID),
+            };
+            accumulate(&mut local, &display_id, (dx * dx + dy * dy).sqrt());
+            send_if_due(&mut local, now);
         });
     })
 }
 
+/// Resets local state once tracking is switched off; returns whether tracking is on.
+fn sync_tracking_enabled(local: &mut LocalState, enabled: bool) -> bool {
+    if !enabled {
+        if local.had_tracking_enabled {
+            local.cursor = CursorState::default();
+            local.current_display_id = None;
+            local.current_mp = 0;
+            local.last_send_ms = 0;
+            local.last_sample_ms = 0;
+            local.had_tracking_enabled = false;
+        }
+        return false;
+    }
+    local.had_tracking_enabled = true;
+    true
+}
+
+fn accumulate(local: &mut LocalState, display_id: &Arc<str>, dist: f64) {
+    if !(dist.is_finite() && dist > 0.0) {
+        return;
+    }
+    crate::core::mouse_gestures::record_motion(dist);
+    let mp = (dist * MP_PER_PX as f64).round();
+    if !(mp.is_finite() && mp > 0.0) {
+        return;
+    }
+
+    let cur_id = local.current_display_id.as_ref();
+    if cur_id.is_none() || cur_id.is_some_and(|id| id.as_ref() != display_id.as_ref()) {
+        flush_local(local);
+        local.current_display_id = Some(Arc::clone(display_id));
+    }
+    local.current_mp = local.current_mp.saturating_add(mp as u64);
+}
+
+fn send_if_due(local: &mut LocalState, now: u64) {
+    if local.current_mp > 0 && now.saturating_sub(local.last_send_ms) >= SEND_INTERVAL_MS {
+        flush_local(local);
+        local.last_send_ms = now;
+    }
+}
+
 fn monitor_for_point(space: CoordinateSpace, x: f64, y: f64) -> Option<MonitorSnapshot> {
     let monitors = MONITORS.read();
     let monitor = monitors.iter().find(|m| m.contains(space, x, y));
@@ -0,0 +1,224 @@
+//! Double-click and drag recognition from global mouse presses, releases and motion.
+//!
+//! Motion arrives from `mouse_distance` in physical pixels (device counts on evdev), so both the
+//! drag threshold and the double-click slop are path lengths rather than straight-line offsets.
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::core::active_app;
+use crate::core::MeritStorage;
+
+/// Travel while held before a press becomes a drag.
+const DRAG_THRESHOLD_PX: f64 = 8.0;
+/// Travel allowed between the two presses of a double-click.
+const DOUBLE_CLICK_SLOP_PX: f64 = 6.0;
+/// Used where the platform has no readable setting (GTK and KDE both default to 400 ms).
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const DEFAULT_DOUBLE_CLICK_MS: u64 = 400;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MouseGesture {
+    DoubleClick,
+    Drag { distance_px: u64 },
+}
+
+#[derive(Debug)]
+struct Held {
+    code: Arc<str>,
+    travelled: f64,
+}
+
+#[derive(Debug)]
+struct LastClick {
+    code: Arc<str>,
+    at: Instant,
+    travelled: f64,
+}
+
+#[derive(Debug)]
+struct GestureTracker {
+    double_click: Duration,
+    held: Option<Held>,
+    last_click: Option<LastClick>,
+}
+
+impl GestureTracker {
+    fn new(double_click: Duration) -> Self {
+        Self {
+            double_click,
+            held: None,
+            last_click: None,
+        }
+    }
+
+    fn press(&mut self, code: &Arc<str>, at: Instant) -> Option<MouseGesture> {
+        self.held = Some(Held {
+            code: Arc::clone(code),
+            travelled: 0.0,
+        });
+
+        let double = self.last_click.take().is_some_and(|prev| {
+            prev.code == *code
+                && at.saturating_duration_since(prev.at) <= self.double_click
+                && prev.travelled <= DOUBLE_CLICK_SLOP_PX
+        });
+        if double {
+            // A third press starts over instead of chaining another double-click.
+            return Some(MouseGesture::DoubleClick);
+        }
+        self.last_click = Some(LastClick {
+            code: Arc::clone(code),
+            at,
+            travelled: 0.0,
+        });
+        None
+    }
+
+    fn moved(&mut self, distance_px: f64) {
+        if let Some(held) = self.held.as_mut() {
+            held.travelled += distance_px;
+        }
+        if let Some(last) = self.last_click.as_mut() {
+            last.travelled += distance_px;
+        }
+    }
+
+    fn release(&mut self, code: &str) -> Option<MouseGesture> {
+        match &self.held {
+            Some(held) if held.code.as_ref() == code => {}
+            _ => return None,
+        }
+        let held = self.held.take()?;
+        if held.travelled < DRAG_THRESHOLD_PX {
+            return None;
+        }
+        // A drag is not the first half of a double-click.
+        self.last_click = None;
+        Some(MouseGesture::Drag {
+            distance_px: held.travelled.round() as u64,
+        })
+    }
+}
+
+static TRACKER: Lazy<Mutex<GestureTracker>> =
+    Lazy::new(|| Mutex::new(GestureTracker::new(double_click_interval())));
+
+#[cfg(target_os = "windows")]
+fn double_click_interval() -> Duration {
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::GetDoubleClickTime;
+    Duration::from_millis(u64::from(unsafe { GetDoubleClickTime() }))
+}
+
+#[cfg(target_os = "macos")]
+fn double_click_interval() -> Duration {
+    use std::os::raw::{c_char, c_void};
+
+    type Id = *mut c_void;
+    type Sel = *mut c_void;
+
+    #[link(name = "objc")]
+    extern "C" {
+        fn objc_getClass(name: *const c_char) -> Id;
+        fn sel_registerName(name: *const c_char) -> Sel;
+        fn objc_msgSend();
+    }
+    #[link(name = "AppKit", kind = "framework")]
+    extern "C" {}
+
+    // +[NSEvent doubleClickInterval], in seconds.
+    let seconds = unsafe {
+        let class = objc_getClass(b"NSEvent\0".as_ptr() as *const c_char);
+        if class.is_null() {
+            0.0
+        } else {
+            let selector = sel_registerName(b"doubleClickInterval\0".as_ptr() as *const c_char);
+            let func: extern "C" fn(Id, Sel) -> f64 =
+                std::mem::transmute(objc_msgSend as *const ());
+            func(class, selector)
+        }
+    };
+    if seconds.is_finite() && seconds > 0.0 {
+        Duration::from_secs_f64(seconds)
+    } else {
+        Duration::from_millis(500)
+    }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn double_click_interval() -> Duration {
+    Duration::from_millis(DEFAULT_DOUBLE_CLICK_MS)
+}
+
+/// A global button press with its canonical code (see `key_codes`).
+pub fn record_press(code: &Arc<str>) {
+    let gesture = TRACKER.lock().press(code, Instant::now());
+    if let Some(gesture) = gesture {
+        store(code, gesture);
+    }
+}
+
+pub fn record_release(code: &str) {
+    let gesture = TRACKER.lock().release(code);
+    if let Some(gesture) = gesture {
+        store(code, gesture);
+    }
+}
+
+/// Pointer travel in physical pixels, fed from `mouse_distance`.
+pub fn record_motion(distance_px: f64) {
+    if !(distance_px.is_finite() && distance_px > 0.0) {
+        return;
+    }
+    TRACKER.lock().moved(distance_px);
+}
+
+fn store(button: &str, gesture: MouseGesture) {
+    let app = active_app::current_or_unknown();
+    let changed = {
+        let storage = MeritStorage::instance();
+        let mut storage = storage.write();
+        storage.add_mouse_gesture_silent(&app, button, gesture)
+    };
+    // The click that completed the gesture brings the next `merit-updated` emit.
+    if changed {
+        crate::core::persistence::request_save();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recognizes_double_clicks_and_drags() {
+        let left: Arc<str> = Arc::from("MouseLeft");
+        let t0 = Instant::now();
+        let mut tracker = GestureTracker::new(Duration::from_millis(400));
+
+        assert_eq!(tracker.press(&left, t0), None);
+        assert_eq!(tracker.release("MouseLeft"), None);
+        tracker.moved(2.0);
+        assert_eq!(
+            tracker.press(&left, t0 + Duration::from_millis(250)),
+            Some(MouseGesture::DoubleClick)
+        );
+        tracker.release("MouseLeft");
+        // The third press does not chain onto the double-click.
+        assert_eq!(tracker.press(&left, t0 + Duration::from_millis(400)), None);
+        tracker.release("MouseLeft");
+        // Too slow to pair with the previous press.
+        assert_eq!(tracker.press(&left, t0 + Duration::from_millis(900)), None);
+
+        tracker.moved(5.0);
+        tracker.moved(7.4);
+        assert_eq!(
+            tracker.release("MouseLeft"),
+            Some(MouseGesture::Drag { distance_px: 12 })
+        );
+        // A drag never pairs up with the next press.
+        assert_eq!(tracker.press(&left, t0 + Duration::from_millis(1000)), None);
+    }
+}
@@ -0,0 +1,199 @@
+//! Scroll wheel activity: ticks and distance per day and per app. Fed from `input_listener`.
+//!
+//! Deltas are in wheel steps (lines); fractional steps from high-resolution wheels and
+//! trackpads carry over until they add up. Scrolling only counts as merit through
+//! `InputSource::Scroll` when `Settings::enable_scroll` is on.
+
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::active_app::{self, AppContext};
+use crate::core::merit_batcher::enqueue_merit_trigger;
+use crate::core::MeritStorage;
+use crate::models::{InputOrigin, InputSource};
+
+const MILLI_PER_STEP: u64 = 1000;
+const FLUSH_INTERVAL: Duration = Duration::from_millis(650);
+/// Larger single deltas are treated as glitches (some drivers report page scrolls this way).
+const MAX_STEPS_PER_EVENT: f64 = 100.0;
+
+static SCROLL_TX: OnceCell<mpsc::Sender<ScrollDelta>> = OnceCell::new();
+
+struct ScrollDelta {
+    app: AppContext,
+    vertical: f64,
+    horizontal: f64,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct Pending {
+    name: Option<Arc<str>>,
+    ticks: u64,
+    vertical_milli: u64,
+    horizontal_milli: u64,
+}
+
+impl Pending {
+    fn add(&mut self, delta: &ScrollDelta) {
+        if self.name.is_none() {
+            self.name = delta.app.name.as_ref().map(Arc::clone);
+        }
+        self.ticks = self.ticks.saturating_add(1);
+        self.vertical_milli = self.vertical_milli.saturating_add(to_milli(delta.vertical));
+        self.horizontal_milli = self
+            .horizontal_milli
+            .saturating_add(to_milli(delta.horizontal));
+    }
+
+    /// Splits off whole steps, leaving the fractional remainder in `self`.
+    fn take_steps(&mut self) -> (u64, u64, u64) {
+        let ticks = std::mem::take(&mut self.ticks);
+        let vertical = self.vertical_milli / MILLI_PER_STEP;
+        let horizontal = self.horizontal_milli / MILLI_PER_STEP;
+        self.vertical_milli %= MILLI_PER_STEP;
+        self.horizontal_milli %= MILLI_PER_STEP;
+        (ticks, vertical, horizontal)
+    }
+}
+
+fn to_milli(steps: f64) -> u64 {
+    (steps.abs() * MILLI_PER_STEP as f64).round() as u64
+}
+
+pub fn init(app_handle: AppHandle) {
+    let (tx, rx) = mpsc::channel::<ScrollDelta>();
+    if SCROLL_TX.set(tx).is_err() {
+        return;
+    }
+
+    std::thread::spawn(move || {
+        let mut pending: HashMap<Arc<str>, Pending> = HashMap::new();
+        let mut last_flush = Instant::now();
+
+        loop {
+            let delta = if pending.values().any(|p| p.ticks > 0) {
+                match rx.recv_timeout(FLUSH_INTERVAL.saturating_sub(last_flush.elapsed())) {
+                    Ok(delta) => Some(delta),
+                    Err(mpsc::RecvTimeoutError::Timeout) => None,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => return,
+                }
+            } else {
+                match rx.recv() {
+                    Ok(delta) => Some(delta),
+                    Err(_) => return,
+                }
+            };
+            if let Some(delta) = delta {
+                pending
+                    .entry(Arc::clone(&delta.app.id))
+                    .or_default()
+                    .add(&delta);
+            }
+
+            if last_flush.elapsed() < FLUSH_INTERVAL {
+                continue;
+            }
+            last_flush = Instant::now();
+            flush(&app_handle, &mut pending);
+        }
+    });
+}
+
+fn flush(app_handle: &AppHandle, pending: &mut HashMap<Arc<str>, Pending>) {
+    let stats = {
+        let storage = MeritStorage::instance();
+        let mut storage = storage.write();
+        let mut changed = false;
+        for (id, p) in pending.iter_mut() {
+            if p.ticks == 0 {
+                continue;
+            }
+            let (ticks, vertical, horizontal) = p.take_steps();
+            let app = AppContext {
+                id: Arc::clone(id),
+                name: p.name.as_ref().map(Arc::clone),
+            };
+            changed |= storage.add_scroll_silent(&app, ticks, vertical, horizontal);
+        }
+        changed.then(|| storage.get_stats().lite())
+    };
+    // Only apps still mid-scroll keep their remainder.
+    pending.retain(|_, p| p.vertical_milli > 0 || p.horizontal_milli > 0);
+
+    let Some(stats) = stats else {
+        return;
+    };
+    if crate::core::main_window_bounds::is_visible() {
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.emit("merit-updated", &stats);
+        }
+    }
+    crate::core::ui_emit::emit_to_any_visible_windows(
+        app_handle,
+        &["settings", "custom_statistics"],
+        "merit-updated",
+        &stats,
+    );
+    crate::core::persistence::request_save();
+}
+
+/// Records one scroll event of `dx`/`dy` steps and offers it to the merit batcher as a tick.
+pub fn record_scroll(app_handle: &AppHandle, dx: f64, dy: f64) {
+    if !(dx.is_finite() && dy.is_finite()) || (dx == 0.0 && dy == 0.0) {
+        return;
+    }
+    if dx.abs() > MAX_STEPS_PER_EVENT || dy.abs() > MAX_STEPS_PER_EVENT {
+        return;
+    }
+
+    let app = active_app::current_or_unknown();
+    if let Some(tx) = SCROLL_TX.get() {
+        let _ = tx.send(ScrollDelta {
+            app: app.clone(),
+            vertical: dy,
+            horizontal: dx,
+        });
+    }
+    enqueue_merit_trigger(
+        app_handle.clone(),
+        InputOrigin::Global,
+        InputSource::Scroll,
+        1u64,
+        None,
+        None,
+        None,
+        Some(app),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn carries_fractional_steps_between_flushes() {
+        let app = AppContext::unknown();
+        let mut pending = Pending::default();
+        for (vertical, horizontal) in [(0.4, 0.0), (-0.4, 0.0), (0.4, 1.0)] {
+            pending.add(&ScrollDelta {
+                app: app.clone(),
+                vertical,
+                horizontal,
+            });
+        }
+        assert_eq!(pending.take_steps(), (3, 1, 1));
+        assert_eq!(pending.vertical_milli, 200);
+
+        pending.add(&ScrollDelta {
+            app,
+            vertical: 0.8,
+            horizontal: 0.0,
+        });
+        assert_eq!(pending.take_steps(), (1, 1, 0));
+        assert_eq!(pending.take_steps(), (0, 0, 0));
+    }
+}
@@ -2,6 +2,7 @@ use crate::models::{
     AchievementState,
     ClickHeatmapState,
     CustomStatisticsTemplate,
+    KeyPrivacyLevel,
     MeritStats,
     Settings,
     WindowPlacement,
@@ -18,7 +19,7 @@ use std::time::{Duration, Instant};
 
 use super::MeritStorage;
 
-const CURRENT_STATE_VERSION: u32 = 7;
+const CURRENT_STATE_VERSION: u32 = 9;
 
 #[derive(Clone)]
 struct PersistContext {
@@ -49,6 +50,90 @@ struct PersistedState {
 static SAVE_TX: Lazy<Mutex<Option<Sender<()>>>> = Lazy::new(|| Mutex::new(None));
 static PERSIST_CONTEXT: Lazy<Mutex<Option<PersistContext>>> = Lazy::new(|| Mutex::new(None));
 static WRITE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+static RECOVERY_REPORT: Lazy<Mutex<Option<StateRecoveryReport>>> = Lazy::new(|| Mutex::new(None));
+
+/// Describes how an unreadable `state.json` was handled at startup.
+#[derive(Debug, Clone, Serialize)]
+pub struct StateRecoveryReport {
+    pub quarantined_path: String,
+    pub error: String,
+    /// Top-level sections that were read back from the broken file.
+    pub recovered: Vec<String>,
+    /// Sections that fell back to defaults.
+    pub reset: Vec<String>,
+}
+
+pub fn recovery_report() -> Option<StateRecoveryReport> {
+    RECOVERY_REPORT.lock().clone()
+}
+
+/// Returns the recovery report from startup, at most once.
+pub fn take_recovery_report() -> Option<StateRecoveryReport> {
+    RECOVERY_REPORT.lock().take()
+}
+
+fn quarantine_path(path: &Path) -> PathBuf {
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "state".to_string());
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+    let mut candidate = path.with_file_name(format!("{}.corrupt-{}.json", stem, stamp));
+    let mut n = 1;
+    while candidate.exists() {
+        n += 1;
+        candidate = path.with_file_name(format!("{}.corrupt-{}-{}.json", stem, stamp, n));
+    }
+    candidate
+}
+
+fn salvage_section<T: serde::de::DeserializeOwned + Default>(
+    root: Option<&serde_json::Value>,
+    key: &str,
+    recovered: &mut Vec<String>,
+    reset: &mut Vec<String>,
+) -> T {
+    let value = root
+        .and_then(|v| v.get(key))
+        .and_then(|v| serde_json::from_value::<T>(v.clone()).ok());
+    match value {
+        Some(v) => {
+            recovered.push(key.to_string());
+            v
+        }
+        None => {
+            reset.push(key.to_string());
+            T::default()
+        }
+    }
+}
+
+/// Rebuilds whatever top-level sections of a broken state file still deserialize on their own.
+fn salvage_state(bytes: &[u8]) -> (PersistedState, Vec<String>, Vec<String>) {
+    let root = serde_json::from_slice::<serde_json::Value>(bytes).ok();
+    let root = root.as_ref().filter(|v| v.is_object());
+    let mut recovered = Vec::new();
+    let mut reset = Vec::new();
+    let state = PersistedState {
+        version: root
+            .and_then(|v| v.get("version"))
+            .and_then(|v| v.as_u64())
+            .and_then(|v| u32::try_from(v).ok())
+            .unwrap_or_else(default_state_version),
+        stats: salvage_section(root, "stats", &mut recovered, &mut reset),
+        settings: salvage_section(root, "settings", &mut recovered, &mut reset),
+        achievements: salvage_section(root, "achievements", &mut recovered, &mut reset),
+        window_placements: salvage_section(root, "window_placements", &mut recovered, &mut reset),
+        click_heatmap: salvage_section(root, "click_heatmap", &mut recovered, &mut reset),
+        custom_statistics_templates: salvage_section(
+            root,
+            "custom_statistics_templates",
+            &mut recovered,
+            &mut reset,
+        ),
+    };
+    (state, recovered, reset)
+}
 
 pub fn init(storage: std::sync::Arc<parking_lot::RwLock<MeritStorage>>, path: PathBuf) {
     *PERSIST_CONTEXT.lock() = Some(PersistContext {
@@ -98,6 +183,63 @@ pub fn flush_now() {
     }
 }
 
+/// Writes the current in-memory state to `path` (used for backups) without touching `state.json`.
+pub fn write_snapshot_to(path: &Path) -> io::Result<()> {
+    let ctx = PERSIST_CONTEXT.lock().clone();
+    let Some(ctx) = ctx else {
+        return Err(io::Error::other("persistence not initialized"));
+    };
+    write_snapshot(&ctx.storage, path)
+}
+
+/// Replaces `state.json` with `src` and reloads it into memory. Holding the write lock keeps a
+/// debounced save from overwriting the restored file with the old in-memory state.
+pub fn restore_state_file(src: &Path) -> io::Result<()> {
+    let ctx = PERSIST_CONTEXT.lock().clone();
+    let Some(ctx) = ctx else {
+        return Err(io::Error::other("persistence not initialized"));
+    };
+
+    let _guard = WRITE_LOCK.lock();
+    let bytes = fs::read(src)?;
+    serde_json::from_slice::<PersistedState>(&bytes)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let tmp = ctx.path.with_extension("restore.tmp");
+    fs::write(&tmp, &bytes)?;
+    let _ = fs::remove_file(&ctx.path);
+    fs::rename(&tmp, &ctx.path)?;
+
+    let Some((mut stats, settings, achievements, window_placements, click_heatmap, templates)) =
+        load(&ctx.path)?
+    else {
+        return Ok(());
+    };
+    if !stats.history.is_empty() {
+        crate::core::history_db::enqueue_bulk_upsert_daily(std::mem::take(&mut stats.history));
+    }
+    crate::core::keyboard_piano::apply_settings(&settings);
+    let mut storage = ctx.storage.write();
+    storage.set_stats(stats);
+    storage.set_settings(settings);
+    storage.set_achievements(achievements);
+    storage.set_window_placements(window_placements);
+    storage.set_click_heatmap(click_heatmap);
+    storage.set_custom_statistics_templates(templates);
+    Ok(())
+}
+
+/// Reduces the key detail in a saved state file other than `state.json`, such as a backup's copy.
+pub fn scrub_state_file(path: &Path, level: KeyPrivacyLevel) -> io::Result<()> {
+    let bytes = fs::read(path)?;
+    let mut state: PersistedState = serde_json::from_slice(&bytes)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    crate::core::key_privacy::reduce_day(&mut state.stats.today, level);
+    for day in &mut state.stats.history {
+        crate::core::key_privacy::reduce_day(day, level);
+    }
+    write_state_atomically(path, &state)
+}
+
 pub fn load(
     path: &Path,
 ) -> io::Result<
@@ -115,8 +257,24 @@ pub fn load(
     }
 
     let bytes = fs::read(path)?;
-    let mut state: PersistedState = serde_json::from_slice(&bytes)
-        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+    let mut should_rewrite = false;
+    let mut state: PersistedState = match serde_json::from_slice(&bytes) {
+        Ok(state) => state,
+        Err(e) => {
+            // Keep the broken file out of the way so the next save can't overwrite it.
+            let quarantined = quarantine_path(path);
+            fs::rename(path, &quarantined)?;
+            let (state, recovered, reset) = salvage_state(&bytes);
+            *RECOVERY_REPORT.lock() = Some(StateRecoveryReport {
+                quarantined_path: quarantined.to_string_lossy().to_string(),
+                error: e.to_string(),
+                recovered,
+                reset,
+            });
+            should_rewrite = true;
+            state
+        }
+    };
 
     state.stats.normalize_today();
     state.stats.today.recompute_counters();
@@ -129,12 +287,17 @@ pub fn load(
     // history vector. Instead, ensure `total_merit` is at least (sum of DB daily totals + today's total).
     let db_total = crate::core::history_db::load_total_merit_all_time().unwrap_or(0);
     let min_total = db_total.saturating_add(state.stats.today.total);
-    let mut should_rewrite = false;
     if min_total > state.stats.total_merit {
         state.stats.total_merit = min_total;
         should_rewrite = true;
     }
 
+    // v8 added `settings.app_rules`; older files get an empty list, hand edits get valid ids.
+    state.settings.app_rules = crate::core::app_rules::normalize(state.settings.app_rules);
+    // v9 added `settings.listening_schedule` and the pause timer.
+    state.settings.listening_schedule =
+        crate::core::listening_schedule::normalize(state.settings.listening_schedule);
+
     // One-time migration: drop high-cardinality historical fields and move to the latest format.
     // Best-effort only; failure to rewrite shouldn't prevent the app from starting.
     if state.version < CURRENT_STATE_VERSION {
@@ -200,3 +363,65 @@ fn write_snapshot(
 
     write_state_atomically(path, &state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn salvage_recovers_sections_that_still_parse() {
+        let bytes = serde_json::to_vec(&serde_json::json!({
+            "version": 7,
+            "stats": { "total_merit": "not a number" },
+            "settings": Settings::default(),
+            "achievements": AchievementState::default(),
+            "custom_statistics_templates": [],
+        }))
+        .unwrap();
+
+        let (state, recovered, reset) = salvage_state(&bytes);
+        assert_eq!(state.version, 7);
+        assert_eq!(recovered, ["settings", "achievements", "custom_statistics_templates"]);
+        assert_eq!(reset, ["stats", "window_placements", "click_heatmap"]);
+    }
+
+    #[test]
+    fn salvage_of_truncated_file_resets_everything() {
+        let (state, recovered, reset) = salvage_state(b"{\"stats\": {\"total_merit\": 12");
+        assert_eq!(state.version, default_state_version());
+        assert!(recovered.is_empty());
+        assert_eq!(reset.len(), 6);
+    }
+
+    #[test]
+    fn scrub_state_file_reduces_today_and_history() {
+        let path = std::env::temp_dir().join(format!(
+            "cyber-zen-scrub-state-{}.json",
+            chrono::Utc::now().timestamp_millis()
+        ));
+        let mut stats = MeritStats::default();
+        stats.today.key_counts.insert("KeyA".into(), 3);
+        stats.today.shortcut_counts.insert("Ctrl+KeyC".into(), 1);
+        let mut day = stats.today.clone();
+        day.date = chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        stats.history.push(day);
+        let state = PersistedState {
+            version: CURRENT_STATE_VERSION,
+            stats,
+            settings: Settings::default(),
+            achievements: AchievementState::default(),
+            window_placements: BTreeMap::new(),
+            click_heatmap: ClickHeatmapState::default(),
+            custom_statistics_templates: Vec::new(),
+        };
+        write_state_atomically(&path, &state).unwrap();
+
+        scrub_state_file(&path, KeyPrivacyLevel::TotalsOnly).unwrap();
+        let scrubbed: PersistedState = serde_json::from_slice(&fs::read(&path).unwrap()).unwrap();
+        for day in std::iter::once(&scrubbed.stats.today).chain(&scrubbed.stats.history) {
+            assert!(day.key_counts.is_empty());
+            assert!(day.shortcut_counts.is_empty());
+        }
+        let _ = fs::remove_file(&path);
+    }
+}
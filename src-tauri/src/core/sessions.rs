@@ -0,0 +1,255 @@
+use crate::core::{activity, history_db, MeritStorage};
+use crate::models::SessionRecord;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const IDLE_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// `(app_id, app_name, count)` for input attributed to an app.
+pub type AppCount = (Arc<str>, Option<Arc<str>>, u64);
+
+struct OpenSession {
+    start_ms: u64,
+    last_input_ms: u64,
+    date_key: Arc<str>,
+    keyboard: u64,
+    mouse_single: u64,
+    apps: HashMap<Arc<str>, (u64, Option<Arc<str>>)>,
+}
+
+impl OpenSession {
+    fn new(now: u64, date_key: Arc<str>) -> Self {
+        Self {
+            start_ms: now,
+            last_input_ms: now,
+            date_key,
+            keyboard: 0,
+            mouse_single: 0,
+            apps: HashMap::new(),
+        }
+    }
+
+    fn record(&self, ongoing: bool) -> SessionRecord {
+        // Ties go to the lexicographically smaller id so the result is stable.
+        let dominant = self
+            .apps
+            .iter()
+            .max_by(|(a_id, (a, _)), (b_id, (b, _))| a.cmp(b).then_with(|| b_id.cmp(a_id)));
+        SessionRecord {
+            start_ms: self.start_ms,
+            end_ms: self.last_input_ms,
+            date_key: self.date_key.to_string(),
+            keyboard: self.keyboard,
+            mouse_single: self.mouse_single,
+            app_id: dominant.map(|(id, _)| id.to_string()),
+            app_name: dominant.and_then(|(_, (_, name))| name.as_deref().map(str::to_string)),
+            ongoing,
+        }
+    }
+}
+
+static OPEN: Lazy<Mutex<Option<OpenSession>>> = Lazy::new(|| Mutex::new(None));
+static CHECKER_STARTED: AtomicBool = AtomicBool::new(false);
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+fn threshold_ms(secs: u32) -> u64 {
+    u64::from(secs.max(1)).saturating_mul(1_000)
+}
+
+fn persist(session: OpenSession, wait: bool) {
+    if let Err(e) = history_db::record_session(session.record(false), wait) {
+        eprintln!("{}", e);
+    }
+}
+
+/// Feeds counted input into the open session, starting a new one after an idle gap.
+pub fn record_input(
+    keyboard: u64,
+    mouse_single: u64,
+    apps: &[AppCount],
+    idle_threshold_secs: u32,
+) {
+    if keyboard == 0 && mouse_single == 0 {
+        return;
+    }
+
+    let now = now_ms();
+    let date_key = crate::core::date_key::today_key_arc();
+    let done = {
+        let mut guard = OPEN.lock();
+        advance(
+            &mut guard,
+            now,
+            date_key,
+            keyboard,
+            mouse_single,
+            apps,
+            threshold_ms(idle_threshold_secs),
+        )
+    };
+    if let Some(done) = done {
+        persist(done, false);
+    }
+}
+
+/// Adds input at `now` to `open`. Returns the previous session if it ended, either after an idle
+/// gap or because the day changed (sessions are listed per day, so they never span midnight).
+fn advance(
+    open: &mut Option<OpenSession>,
+    now: u64,
+    date_key: Arc<str>,
+    keyboard: u64,
+    mouse_single: u64,
+    apps: &[AppCount],
+    threshold_ms: u64,
+) -> Option<OpenSession> {
+    let ended = open.as_ref().is_some_and(|session| {
+        now.saturating_sub(session.last_input_ms) >= threshold_ms || session.date_key != date_key
+    });
+    let done = if ended { open.take() } else { None };
+
+    let open = open.get_or_insert_with(|| OpenSession::new(now, date_key));
+    open.last_input_ms = now;
+    open.keyboard = open.keyboard.saturating_add(keyboard);
+    open.mouse_single = open.mouse_single.saturating_add(mouse_single);
+    for (id, name, count) in apps {
+        open.apps
+            .entry(Arc::clone(id))
+            .and_modify(|(c, n)| {
+                *c = c.saturating_add(*count);
+                if n.is_none() {
+                    *n = name.clone();
+                }
+            })
+            .or_insert((*count, name.clone()));
+    }
+    done
+}
+
+/// The session that is still in progress, if any.
+pub fn current() -> Option<SessionRecord> {
+    OPEN.lock().as_ref().map(|open| open.record(true))
+}
+
+/// Ends the open session and waits for it to be written. Used on quit.
+pub fn close_now() {
+    let done = OPEN.lock().take();
+    if let Some(done) = done {
+        persist(done, true);
+    }
+}
+
+/// Closes the open session once the user has been idle past the configured threshold.
+pub fn init() {
+    if CHECKER_STARTED.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    std::thread::spawn(|| loop {
+        std::thread::sleep(IDLE_CHECK_INTERVAL);
+
+        let threshold = {
+            let storage = MeritStorage::instance();
+            let storage = storage.read();
+            threshold_ms(storage.session_idle_threshold_secs())
+        };
+        if activity::idle_for_ms() < threshold {
+            continue;
+        }
+
+        let now = now_ms();
+        let done = {
+            let mut guard = OPEN.lock();
+            let expired = guard
+                .as_ref()
+                .is_some_and(|open| now.saturating_sub(open.last_input_ms) >= threshold);
+            if expired {
+                guard.take()
+            } else {
+                None
+            }
+        };
+        if let Some(done) = done {
+            persist(done, false);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: u64 = 60_000;
+    const IDLE: u64 = 5 * MINUTE;
+
+    fn app(id: &str, count: u64) -> AppCount {
+        (Arc::from(id), Some(Arc::from(id.to_uppercase())), count)
+    }
+
+    #[test]
+    fn record_input_continues_ends_on_idle_and_splits_at_midnight() {
+        let day1: Arc<str> = Arc::from("2026-03-01");
+        let day2: Arc<str> = Arc::from("2026-03-02");
+        let start = 1_000_000;
+        let mut open = None;
+
+        // Input within the idle threshold extends the same session.
+        assert!(advance(
+            &mut open,
+            start,
+            day1.clone(),
+            3,
+            0,
+            &[app("editor", 3)],
+            IDLE
+        )
+        .is_none());
+        assert!(advance(
+            &mut open,
+            start + IDLE - 1,
+            day1.clone(),
+            1,
+            2,
+            &[app("browser", 2), app("editor", 1)],
+            IDLE
+        )
+        .is_none());
+        let current = open.as_ref().unwrap().record(true);
+        assert_eq!(current.start_ms, start);
+        assert_eq!(current.end_ms, start + IDLE - 1);
+        assert_eq!((current.keyboard, current.mouse_single), (4, 2));
+        assert_eq!(current.app_id.as_deref(), Some("editor"));
+        assert_eq!(current.app_name.as_deref(), Some("EDITOR"));
+
+        // A gap of the full threshold closes it; the new input starts the next session.
+        let resumed = start + 2 * IDLE - 1;
+        let done = advance(&mut open, resumed, day1.clone(), 0, 1, &[], IDLE).unwrap();
+        let done = done.record(false);
+        assert_eq!((done.start_ms, done.end_ms), (start, start + IDLE - 1));
+        assert_eq!(done.date_key, "2026-03-01");
+        let current = open.as_ref().unwrap().record(true);
+        assert_eq!(
+            (current.start_ms, current.keyboard, current.mouse_single),
+            (resumed, 0, 1)
+        );
+        assert_eq!(current.app_id, None);
+
+        // Input just after midnight ends the session even without a gap.
+        let after_midnight = resumed + MINUTE;
+        let done = advance(&mut open, after_midnight, day2, 2, 0, &[], IDLE).unwrap();
+        assert_eq!(done.record(false).date_key, "2026-03-01");
+        let current = open.as_ref().unwrap().record(true);
+        assert_eq!(current.date_key, "2026-03-02");
+        assert_eq!((current.start_ms, current.keyboard), (after_midnight, 2));
+    }
+}
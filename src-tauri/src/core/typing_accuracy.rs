@@ -0,0 +1,208 @@
+//! Correction ratio and "burst then delete" tracking. Fed per key press from `input_listener` and
+//! stored hourly in `daily_corrections`.
+
+use crate::core::active_app::AppContext;
+use crate::core::history_db::{self, CorrectionDelta};
+use crate::core::key_privacy;
+use crate::core::MeritStorage;
+use crate::models::{DailyStats, TypingAccuracyDay};
+use chrono::Timelike;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Keys further apart than this end a burst.
+const BURST_GAP_MS: u64 = 1_000;
+/// Typing keys in quick succession that make a burst.
+const BURST_MIN_KEYS: u32 = 5;
+/// Corrections right after a burst that count it as deleted.
+const BURST_DELETE_MIN_KEYS: u32 = 3;
+
+const CORRECTION_CODES: [&str; 3] = ["Backspace", "Delete", "cat:deletion"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyKind {
+    Typing,
+    Correction,
+    /// Navigation, function keys and shortcuts: they end a burst without being typing.
+    Other,
+}
+
+/// `None` for keys that don't affect tracking at all (modifiers).
+fn classify(code: &str, has_shortcut: bool) -> Option<KeyKind> {
+    // Ctrl+Backspace and friends still delete text.
+    if code == "Backspace" || code == "Delete" {
+        return Some(KeyKind::Correction);
+    }
+    match key_privacy::category(code) {
+        "cat:modifier" => None,
+        _ if has_shortcut => Some(KeyKind::Other),
+        "cat:letter" | "cat:digit" | "cat:punctuation" | "cat:whitespace" | "cat:numpad" => {
+            Some(KeyKind::Typing)
+        }
+        _ => Some(KeyKind::Other),
+    }
+}
+
+#[derive(Default)]
+struct Tracker {
+    last_key_ms: Option<u64>,
+    burst_keys: u32,
+    /// Corrections since the current burst ended.
+    deleting: u32,
+}
+
+impl Tracker {
+    fn record(&mut self, kind: KeyKind, now_ms: u64) -> CorrectionDelta {
+        if self
+            .last_key_ms
+            .is_none_or(|last| now_ms.saturating_sub(last) > BURST_GAP_MS)
+        {
+            self.burst_keys = 0;
+            self.deleting = 0;
+        }
+        self.last_key_ms = Some(now_ms);
+
+        let mut delta = CorrectionDelta::default();
+        match kind {
+            KeyKind::Typing => {
+                if self.deleting > 0 {
+                    self.burst_keys = 0;
+                    self.deleting = 0;
+                }
+                self.burst_keys = self.burst_keys.saturating_add(1);
+                delta.typed_keys = 1;
+                if self.burst_keys == BURST_MIN_KEYS {
+                    delta.bursts = 1;
+                }
+            }
+            KeyKind::Correction => {
+                delta.correction_keys = 1;
+                if self.burst_keys >= BURST_MIN_KEYS {
+                    self.deleting = self.deleting.saturating_add(1);
+                    if self.deleting == BURST_DELETE_MIN_KEYS {
+                        delta.burst_deletes = 1;
+                    }
+                }
+            }
+            KeyKind::Other => {
+                self.burst_keys = 0;
+                self.deleting = 0;
+            }
+        }
+        delta
+    }
+}
+
+static TRACKER: Lazy<Mutex<Tracker>> = Lazy::new(|| Mutex::new(Tracker::default()));
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Records a global key press. Skipped where key details may not be stored (keyboard counting
+/// off, `TotalsOnly`, or an app rule that ignores the app or strips its details).
+pub fn record_key(code: &str, has_shortcut: bool, app: &AppContext) {
+    let Some(kind) = classify(code, has_shortcut) else {
+        return;
+    };
+    if !MeritStorage::instance().read().key_details_allowed(app) {
+        return;
+    }
+    let delta = TRACKER.lock().record(kind, now_ms());
+    history_db::record_corrections(
+        crate::core::date_key::today_key_arc(),
+        chrono::Local::now().hour(),
+        delta,
+    );
+}
+
+/// Backspace/Delete share of a day's counted keys; `None` without key details.
+fn key_count_correction_ratio(day: &DailyStats) -> Option<f64> {
+    let total: u64 = day.key_counts.values().sum();
+    if total == 0 {
+        return None;
+    }
+    let corrections: u64 = CORRECTION_CODES
+        .iter()
+        .filter_map(|code| day.key_counts.get(*code))
+        .sum();
+    Some(corrections as f64 / total as f64)
+}
+
+/// Hourly accuracy for `date_key`; today's key counts come from memory, so they're current.
+pub fn day(date_key: &str) -> Result<TypingAccuracyDay, String> {
+    let (hourly, stored) = history_db::load_corrections(date_key)?;
+    let today = crate::core::date_key::today_key_arc();
+    let stats = if today.as_ref() == date_key {
+        Some(MeritStorage::instance().read().get_stats().today)
+    } else {
+        stored
+    };
+    Ok(TypingAccuracyDay::from_hours(
+        date_key.to_string(),
+        hourly,
+        stats.as_ref().and_then(key_count_correction_ratio),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_bursts_deleted_right_away() {
+        let mut tracker = Tracker::default();
+        let mut total = CorrectionDelta::default();
+        let mut at = 1_000;
+        let mut press = |tracker: &mut Tracker, code: &str, gap: u64| {
+            at += gap;
+            let delta = tracker.record(classify(code, false).unwrap(), at);
+            total.typed_keys += delta.typed_keys;
+            total.correction_keys += delta.correction_keys;
+            total.bursts += delta.bursts;
+            total.burst_deletes += delta.burst_deletes;
+        };
+
+        // A fast burst of six keys, then three quick Backspaces.
+        for _ in 0..6 {
+            press(&mut tracker, "KeyA", 100);
+        }
+        for _ in 0..3 {
+            press(&mut tracker, "Backspace", 100);
+        }
+        // A slow fix after a pause is a plain correction.
+        press(&mut tracker, "KeyB", 2_000);
+        press(&mut tracker, "Backspace", 100);
+        // Arrow keys end a burst before it can be deleted.
+        for _ in 0..5 {
+            press(&mut tracker, "KeyC", 100);
+        }
+        press(&mut tracker, "ArrowLeft", 100);
+        for _ in 0..3 {
+            press(&mut tracker, "Delete", 100);
+        }
+
+        assert_eq!(total.typed_keys, 12);
+        assert_eq!(total.correction_keys, 7);
+        assert_eq!(total.bursts, 2);
+        assert_eq!(total.burst_deletes, 1);
+        assert_eq!(classify("ShiftLeft", false), None);
+        assert_eq!(classify("KeyZ", true), Some(KeyKind::Other));
+
+        let mut stats = DailyStats::new(chrono::NaiveDate::from_ymd_opt(2024, 3, 1).unwrap());
+        stats.key_counts.insert("KeyA".into(), 18);
+        stats.key_counts.insert("Backspace".into(), 2);
+        assert_eq!(key_count_correction_ratio(&stats), Some(0.1));
+        stats.key_counts.clear();
+        stats.key_counts.insert("cat:letter".into(), 9);
+        stats.key_counts.insert("cat:deletion".into(), 1);
+        stats.key_counts.insert("cat:editing".into(), 10);
+        assert_eq!(key_count_correction_ratio(&stats), Some(0.05));
+        stats.key_counts.clear();
+        assert_eq!(key_count_correction_ratio(&stats), None);
+    }
+}
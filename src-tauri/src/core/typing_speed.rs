@@ -0,0 +1,161 @@
+//! Rolling typing speed from counted keystrokes. Feeds the hourly `daily_typing_speed` series and
+//! the live `typing-speed` event.
+
+use crate::core::history_db::{self, TypingSpeedDelta};
+use crate::models::statistics::KEYS_PER_WORD;
+use chrono::Timelike;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const WINDOW_MS: u64 = 60_000;
+/// Longer gaps between keystrokes are pauses, not typing time.
+const IDLE_GAP_MS: u64 = 5_000;
+/// A rolling reading needs this much typing time in the window before it is reported.
+const MIN_ACTIVE_MS: u64 = 10_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TypingSpeed {
+    pub kpm: f64,
+    pub wpm: f64,
+    pub today_average_kpm: f64,
+    pub today_peak_kpm: f64,
+}
+
+#[derive(Default)]
+struct Tracker {
+    last_key_ms: Option<u64>,
+    /// `(at_ms, keys, active_ms)` per recorded batch within the last `WINDOW_MS`.
+    window: VecDeque<(u64, u64, u64)>,
+    date_key: Option<Arc<str>>,
+    today: TypingSpeedDelta,
+}
+
+impl Tracker {
+    fn record(&mut self, keys: u64, now_ms: u64, date_key: &Arc<str>) -> TypingSpeedDelta {
+        if self.date_key.as_ref() != Some(date_key) {
+            self.date_key = Some(Arc::clone(date_key));
+            self.today = TypingSpeedDelta::default();
+        }
+
+        let gap = self.last_key_ms.map(|last| now_ms.saturating_sub(last));
+        let (keys, active_ms) = match gap {
+            Some(gap) if gap <= IDLE_GAP_MS => (keys, gap),
+            // The first keystroke after a pause only starts the clock.
+            _ => (keys.saturating_sub(1), 0),
+        };
+        self.last_key_ms = Some(now_ms);
+        self.window.push_back((now_ms, keys, active_ms));
+        while self
+            .window
+            .front()
+            .is_some_and(|(at, _, _)| at.saturating_add(WINDOW_MS) < now_ms)
+        {
+            self.window.pop_front();
+        }
+
+        let delta = TypingSpeedDelta {
+            keys,
+            active_ms,
+            peak_kpm: self.rolling_kpm().unwrap_or(0.0),
+        };
+        self.today.keys = self.today.keys.saturating_add(delta.keys);
+        self.today.active_ms = self.today.active_ms.saturating_add(delta.active_ms);
+        self.today.peak_kpm = self.today.peak_kpm.max(delta.peak_kpm);
+        delta
+    }
+
+    fn rolling_kpm(&self) -> Option<f64> {
+        let (keys, active_ms) = self
+            .window
+            .iter()
+            .fold((0u64, 0u64), |(k, a), (_, keys, active)| {
+                (k + keys, a + active)
+            });
+        (active_ms >= MIN_ACTIVE_MS).then(|| keys as f64 * 60_000.0 / active_ms as f64)
+    }
+
+    fn current(&self, now_ms: u64) -> TypingSpeed {
+        let typing = self
+            .last_key_ms
+            .is_some_and(|last| now_ms.saturating_sub(last) <= IDLE_GAP_MS);
+        let kpm = if typing {
+            self.rolling_kpm().unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        let today_average_kpm = if self.today.active_ms > 0 {
+            self.today.keys as f64 * 60_000.0 / self.today.active_ms as f64
+        } else {
+            0.0
+        };
+        TypingSpeed {
+            kpm,
+            wpm: kpm / KEYS_PER_WORD,
+            today_average_kpm,
+            today_peak_kpm: self.today.peak_kpm,
+        }
+    }
+}
+
+static TRACKER: Lazy<Mutex<Tracker>> = Lazy::new(|| Mutex::new(Tracker::default()));
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64
+}
+
+/// Whether a keystroke counts as typing: modifiers and shortcuts don't.
+pub fn is_typing_key(code: Option<&str>, shortcut: Option<&str>) -> bool {
+    shortcut.is_none()
+        && code.is_none_or(|code| crate::core::key_privacy::category(code) != "cat:modifier")
+}
+
+/// Records `keys` typing keystrokes that were just counted.
+pub fn record_keys(keys: u64) {
+    if keys == 0 {
+        return;
+    }
+    let date_key = crate::core::date_key::today_key_arc();
+    let hour = chrono::Local::now().hour();
+    let delta = TRACKER.lock().record(keys, now_ms(), &date_key);
+    history_db::record_typing_speed(date_key, hour, delta);
+}
+
+pub fn current() -> TypingSpeed {
+    TRACKER.lock().current(now_ms())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolling_speed_skips_idle_gaps() {
+        let day: Arc<str> = Arc::from("2024-03-01");
+        let mut tracker = Tracker::default();
+        // 61 keys 200 ms apart: 60 timed keys over 12 s = 300 kpm.
+        for i in 0..61 {
+            tracker.record(1, 1_000 + i * 200, &day);
+        }
+        let speed = tracker.current(13_000);
+        assert!((speed.kpm - 300.0).abs() < 1e-9);
+        assert!((speed.wpm - 60.0).abs() < 1e-9);
+
+        // A long pause adds no typing time and the first key after it isn't timed.
+        let delta = tracker.record(1, 40_000, &day);
+        assert_eq!((delta.keys, delta.active_ms), (0, 0));
+        assert!((tracker.current(40_000).today_average_kpm - 300.0).abs() < 1e-9);
+        assert_eq!(tracker.current(50_000).kpm, 0.0);
+        assert!((tracker.current(50_000).today_peak_kpm - 300.0).abs() < 1e-9);
+
+        assert!(is_typing_key(Some("KeyA"), None));
+        assert!(!is_typing_key(Some("ShiftLeft"), None));
+        assert!(!is_typing_key(Some("KeyC"), Some("Ctrl+KeyC")));
+    }
+}
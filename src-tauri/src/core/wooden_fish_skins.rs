@@ -790,6 +790,18 @@ pub fn export_png_base64_to_app_data(
     let png_bytes = BASE64_STANDARD
         .decode(png_base64.as_bytes())
         .context("PNG base64 解码失败")?;
+    export_png_bytes_to_app_data(app, file_name, export_dir, export_path, &png_bytes)
+}
+
+/// Writes encoded PNG bytes to `export_path`, or to `file_name` inside `export_dir` (default: the
+/// app data `exports` folder). Returns the written path.
+pub fn export_png_bytes_to_app_data(
+    app: &AppHandle,
+    file_name: &str,
+    export_dir: Option<&str>,
+    export_path: Option<&str>,
+    png_bytes: &[u8],
+) -> Result<String> {
     if png_bytes.is_empty() {
         return Err(anyhow!("PNG 内容为空"));
     }
@@ -799,11 +811,11 @@ pub fn export_png_base64_to_app_data(
             MAX_EXPORT_PNG_BYTES / 1024 / 1024
         ));
     }
-    let _ = png_dimensions(&png_bytes).context("PNG 不是有效的 PNG")?;
+    let _ = png_dimensions(png_bytes).context("PNG 不是有效的 PNG")?;
 
     let file_name = sanitize_file_name_with_ext(file_name, "cover.png", ".png");
     let path = resolve_export_png_path(app, export_path, export_dir, &file_name)?;
-    fs::write(&path, &png_bytes)
+    fs::write(&path, png_bytes)
         .with_context(|| format!("写入导出文件失败：{}", path.display()))?;
     Ok(path.to_string_lossy().to_string())
 }
@@ -844,7 +856,7 @@ fn sanitize_zip_file_name_only(name: &str) -> Result<String> {
     Ok(sanitize_zip_file_name(base))
 }
 
-fn resolve_export_zip_path(
+pub fn resolve_export_zip_path(
     app: &AppHandle,
     export_path: Option<&str>,
     export_dir: Option<&str>,
@@ -134,6 +134,28 @@ pub fn run() {
                 }
             }
 
+            if let Some(report) = core::persistence::recovery_report() {
+                let _ = core::app_log::append(
+                    &app_handle,
+                    core::app_log::AppLogRecord {
+                        ts_ms: chrono::Utc::now().timestamp_millis(),
+                        level: "error".to_string(),
+                        scope: "persistence".to_string(),
+                        message: "state_recovered".to_string(),
+                        data: serde_json::to_value(&report).ok(),
+                    },
+                );
+                // Give the webviews time to register listeners; late windows can still fetch the
+                // report with `take_state_recovery_report`.
+                let app_handle = app_handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+                    if let Some(report) = core::persistence::take_recovery_report() {
+                        let _ = app_handle.emit("state-recovered", report);
+                    }
+                });
+            }
+
             tray_menu::create_tray(&app_handle)?;
 
             {
@@ -143,6 +165,14 @@ pub fn run() {
             }
 
             core::persistence::init(MeritStorage::instance(), state_path);
+            {
+                let settings = MeritStorage::instance().read().get_settings();
+                core::history_db::set_retention(core::history_db::RetentionPolicy::from_settings(
+                    &settings,
+                ));
+                core::local_api::apply_settings(&settings);
+            }
+            core::sessions::init();
 
             // One-time migration: move legacy click heatmaps out of `state.json` into SQLite.
             // This is kicked off before global input listening is initialized so newly recorded
@@ -175,6 +205,8 @@ pub fn run() {
                 .map_err(|e| Box::new(std::io::Error::new(std::io::ErrorKind::Other, e)))?;
             core::main_window_bounds::refresh_from_app_handle(&app_handle);
             core::auto_updater::init(app_handle.clone());
+            core::backup::init(app_handle.clone());
+            core::listening_schedule::init(app_handle.clone());
             {
                 let app_handle = app_handle.clone();
                 tauri::async_runtime::spawn(async move {
@@ -189,15 +221,40 @@ pub fn run() {
             commands::achievements::get_achievement_state,
             commands::achievements::append_achievement_unlocks,
             commands::achievements::clear_achievement_history,
+            commands::backup::list_backups,
+            commands::backup::create_backup,
+            commands::backup::restore_backup,
+            commands::backup::delete_backup,
+            commands::backup::take_state_recovery_report,
             commands::merit::get_merit_stats,
             commands::merit::get_recent_days,
             commands::merit::get_recent_days_lite,
+            commands::merit::export_all_data,
+            commands::merit::import_data,
             commands::merit::get_history_aggregates,
+            commands::merit::get_history_buckets,
+            commands::merit::get_day_timeline,
+            commands::merit::get_typing_speed,
+            commands::merit::get_typing_accuracy,
+            commands::merit::get_key_bigram_stats,
+            commands::merit::analyze_keyboard_layouts,
+            commands::merit::list_sessions,
+            commands::merit::get_session_stats,
+            commands::history::get_history_db_stats,
+            commands::history::prune_history,
+            commands::history::vacuum_history,
+            commands::history::scrub_key_details,
+            commands::settings::get_local_api_status,
+            commands::app_rules::list_app_rules,
+            commands::app_rules::upsert_app_rule,
+            commands::app_rules::delete_app_rule,
             commands::merit::add_merit,
             commands::merit::clear_history,
             commands::merit::reset_all_merit,
             commands::input::start_input_listening,
             commands::input::stop_input_listening,
+            commands::input::pause_input_listening_for,
+            commands::input::get_listening_schedule_status,
             commands::input::is_input_listening,
             commands::input::update_input_settings,
             commands::input::get_input_listener_error,
@@ -240,6 +297,7 @@ pub fn run() {
             commands::click_heatmap::get_display_monitors,
             commands::click_heatmap::get_click_heatmap_grid,
             commands::click_heatmap::clear_click_heatmap,
+            commands::click_heatmap::export_heatmap_png,
             commands::custom_statistics_templates::get_custom_statistics_templates,
             commands::custom_statistics_templates::upsert_custom_statistics_template,
             commands::custom_statistics_templates::delete_custom_statistics_template,
@@ -39,3 +39,42 @@ pub struct AchievementState {
     pub unlock_index: Vec<AchievementUnlockRecord>,
     pub unlock_history: Vec<AchievementUnlockRecord>,
 }
+
+impl AchievementState {
+    /// Merges unlock records from `other`, skipping `(achievement_id, cadence, period_key)`
+    /// duplicates. Returns how many records were new to the unlock index.
+    pub fn merge_from(&mut self, other: &AchievementState) -> usize {
+        fn key(r: &AchievementUnlockRecord) -> (String, AchievementCadence, String) {
+            (r.achievement_id.clone(), r.cadence, r.period_key.clone())
+        }
+
+        let mut index_seen: std::collections::HashSet<_> = self.unlock_index.iter().map(key).collect();
+        let mut added = 0;
+        for rec in &other.unlock_index {
+            if rec.achievement_id.trim().is_empty() || rec.period_key.trim().is_empty() {
+                continue;
+            }
+            if index_seen.insert(key(rec)) {
+                self.unlock_index.push(rec.clone());
+                added += 1;
+            }
+        }
+
+        let mut history_seen: std::collections::HashSet<_> =
+            self.unlock_history.iter().map(key).collect();
+        for rec in &other.unlock_history {
+            if rec.achievement_id.trim().is_empty() || rec.period_key.trim().is_empty() {
+                continue;
+            }
+            if history_seen.insert(key(rec)) {
+                self.unlock_history.push(rec.clone());
+            }
+        }
+
+        self.unlock_index
+            .sort_by_key(|r| std::cmp::Reverse(r.unlocked_at_ms));
+        self.unlock_history
+            .sort_by_key(|r| std::cmp::Reverse(r.unlocked_at_ms));
+        added
+    }
+}
@@ -7,6 +7,16 @@ pub const CLICK_HEATMAP_BASE_LEN: usize = CLICK_HEATMAP_BASE_COLS * CLICK_HEATMA
 
 const CURRENT_VERSION: u32 = 2;
 
+/// Which positions a heatmap grid counts. Both kinds share the base grid and storage layout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HeatmapKind {
+    #[default]
+    Click,
+    /// Cursor positions sampled while the pointer moves.
+    Move,
+}
+
 fn default_version() -> u32 {
     CURRENT_VERSION
 }
@@ -18,6 +18,8 @@ pub struct HourlyStats {
     pub keyboard: u64,
     #[serde(default)]
     pub mouse_single: u64,
+    #[serde(default)]
+    pub scroll: u64,
 }
 
 impl HourlyStats {
@@ -30,6 +32,7 @@ impl HourlyStats {
         match source {
             InputSource::Keyboard => self.keyboard = self.keyboard.saturating_add(count),
             InputSource::MouseSingle => self.mouse_single = self.mouse_single.saturating_add(count),
+            InputSource::Scroll => self.scroll = self.scroll.saturating_add(count),
         }
     }
 }
@@ -46,6 +49,8 @@ pub enum InputOrigin {
 pub enum InputSource {
     Keyboard,
     MouseSingle,
+    /// Scroll wheel ticks; only counted when `Settings::enable_scroll` is on.
+    Scroll,
 }
 
 impl<'de> Deserialize<'de> for InputSource {
@@ -58,6 +63,7 @@ impl<'de> Deserialize<'de> for InputSource {
             "keyboard" => Ok(Self::Keyboard),
             "mouse_single" => Ok(Self::MouseSingle),
             "mouse_double" => Ok(Self::MouseSingle),
+            "scroll" => Ok(Self::Scroll),
             _ => Err(de::Error::custom(format!("invalid input source: {}", raw))),
         }
     }
@@ -80,6 +86,8 @@ pub struct DailyStats {
     #[serde(default)]
     pub mouse_single: u64,
     #[serde(default)]
+    pub scroll: u64,
+    #[serde(default)]
     pub first_event_at_ms: Option<u64>,
     #[serde(default)]
     pub last_event_at_ms: Option<u64>,
@@ -87,6 +95,23 @@ pub struct DailyStats {
     pub mouse_move_distance_px: u64,
     #[serde(default)]
     pub mouse_move_distance_px_by_display: HashMap<String, u64>,
+    /// Scroll events, whether or not scrolling counts as merit.
+    #[serde(default)]
+    pub scroll_ticks: u64,
+    /// Scrolled distance in wheel steps (lines).
+    #[serde(default)]
+    pub scroll_distance_vertical: u64,
+    #[serde(default)]
+    pub scroll_distance_horizontal: u64,
+    /// Double-clicks, using the OS double-click interval.
+    #[serde(default)]
+    pub double_clicks: u64,
+    /// Press, movement past the drag threshold, release.
+    #[serde(default)]
+    pub drags: u64,
+    /// Pointer travel while dragging, in physical pixels.
+    #[serde(default)]
+    pub drag_distance_px: u64,
     #[serde(default = "default_hourly_stats")]
     pub hourly: Vec<HourlyStats>,
     #[serde(default)]
@@ -113,6 +138,8 @@ pub struct DailyStatsLite {
     #[serde(default)]
     pub mouse_single: u64,
     #[serde(default)]
+    pub scroll: u64,
+    #[serde(default)]
     pub first_event_at_ms: Option<u64>,
     #[serde(default)]
     pub last_event_at_ms: Option<u64>,
@@ -120,6 +147,23 @@ pub struct DailyStatsLite {
     pub mouse_move_distance_px: u64,
     #[serde(default)]
     pub mouse_move_distance_px_by_display: HashMap<String, u64>,
+    /// Scroll events, whether or not scrolling counts as merit.
+    #[serde(default)]
+    pub scroll_ticks: u64,
+    /// Scrolled distance in wheel steps (lines).
+    #[serde(default)]
+    pub scroll_distance_vertical: u64,
+    #[serde(default)]
+    pub scroll_distance_horizontal: u64,
+    /// Double-clicks, using the OS double-click interval.
+    #[serde(default)]
+    pub double_clicks: u64,
+    /// Press, movement past the drag threshold, release.
+    #[serde(default)]
+    pub drags: u64,
+    /// Pointer travel while dragging, in physical pixels.
+    #[serde(default)]
+    pub drag_distance_px: u64,
     #[serde(default = "default_hourly_stats")]
     pub hourly: Vec<HourlyStats>,
 }
@@ -134,6 +178,10 @@ pub struct AppInputStats {
     pub keyboard: u64,
     #[serde(default)]
     pub mouse_single: u64,
+    #[serde(default)]
+    pub scroll: u64,
+    #[serde(default)]
+    pub scroll_ticks: u64,
 }
 
 impl AppInputStats {
@@ -150,6 +198,7 @@ impl AppInputStats {
         match source {
             InputSource::Keyboard => self.keyboard = self.keyboard.saturating_add(count),
             InputSource::MouseSingle => self.mouse_single = self.mouse_single.saturating_add(count),
+            InputSource::Scroll => self.scroll = self.scroll.saturating_add(count),
         }
     }
 }
@@ -161,10 +210,17 @@ impl DailyStats {
             total: 0,
             keyboard: 0,
             mouse_single: 0,
+            scroll: 0,
             first_event_at_ms: None,
             last_event_at_ms: None,
             mouse_move_distance_px: 0,
             mouse_move_distance_px_by_display: HashMap::new(),
+            scroll_ticks: 0,
+            scroll_distance_vertical: 0,
+            scroll_distance_horizontal: 0,
+            double_clicks: 0,
+            drags: 0,
+            drag_distance_px: 0,
             hourly: default_hourly_stats(),
             key_counts: HashMap::new(),
             key_counts_unshifted: HashMap::new(),
@@ -197,10 +253,17 @@ impl DailyStats {
             total: self.total,
             keyboard: self.keyboard,
             mouse_single: self.mouse_single,
+            scroll: self.scroll,
             first_event_at_ms: self.first_event_at_ms,
             last_event_at_ms: self.last_event_at_ms,
             mouse_move_distance_px: self.mouse_move_distance_px,
             mouse_move_distance_px_by_display: self.mouse_move_distance_px_by_display.clone(),
+            scroll_ticks: self.scroll_ticks,
+            scroll_distance_vertical: self.scroll_distance_vertical,
+            scroll_distance_horizontal: self.scroll_distance_horizontal,
+            double_clicks: self.double_clicks,
+            drags: self.drags,
+            drag_distance_px: self.drag_distance_px,
             hourly: self.hourly.clone(),
         }
     }
@@ -235,6 +298,9 @@ impl DailyStats {
             InputSource::MouseSingle => {
                 self.mouse_single = self.mouse_single.saturating_add(count);
             }
+            InputSource::Scroll => {
+                self.scroll = self.scroll.saturating_add(count);
+            }
         }
     }
 
@@ -264,6 +330,42 @@ impl DailyStats {
             .or_insert(px);
     }
 
+    /// Records scroll activity (not merit) for the day and for `app_id`.
+    pub fn add_scroll(
+        &mut self,
+        app_id: &Arc<str>,
+        app_name: Option<&Arc<str>>,
+        ticks: u64,
+        vertical: u64,
+        horizontal: u64,
+    ) {
+        if ticks == 0 && vertical == 0 && horizontal == 0 {
+            return;
+        }
+        self.record_event_at_ms(u64::try_from(Utc::now().timestamp_millis()).unwrap_or(0));
+        self.scroll_ticks = self.scroll_ticks.saturating_add(ticks);
+        self.scroll_distance_vertical = self.scroll_distance_vertical.saturating_add(vertical);
+        self.scroll_distance_horizontal = self.scroll_distance_horizontal.saturating_add(horizontal);
+
+        let entry = self.app_input_counts.entry(Arc::clone(app_id)).or_default();
+        if entry.name.is_none() {
+            entry.name = app_name.map(Arc::clone);
+        }
+        entry.scroll_ticks = entry.scroll_ticks.saturating_add(ticks);
+        if self.app_input_counts.len() > MAX_APP_ENTRIES_PER_DAY {
+            self.prune_app_input_counts(app_id);
+        }
+    }
+
+    pub fn add_double_click(&mut self) {
+        self.double_clicks = self.double_clicks.saturating_add(1);
+    }
+
+    pub fn add_drag(&mut self, distance_px: u64) {
+        self.drags = self.drags.saturating_add(1);
+        self.drag_distance_px = self.drag_distance_px.saturating_add(distance_px);
+    }
+
     pub fn add_hourly_merit(&mut self, hour: usize, source: InputSource, count: u64) {
         if count == 0 {
             return;
@@ -281,10 +383,16 @@ impl DailyStats {
         // Older versions persisted per-event records and recomputed totals from them.
         // Current best practice is to persist only aggregated counters to keep state compact.
         self.normalize_hourly();
-        self.total = self.keyboard.saturating_add(self.mouse_single);
+        self.total = self
+            .keyboard
+            .saturating_add(self.mouse_single)
+            .saturating_add(self.scroll);
 
         for v in self.app_input_counts.values_mut() {
-            v.total = v.keyboard.saturating_add(v.mouse_single);
+            v.total = v
+                .keyboard
+                .saturating_add(v.mouse_single)
+                .saturating_add(v.scroll);
         }
     }
 
@@ -329,6 +437,64 @@ impl DailyStats {
         self.app_input_counts.retain(|k, _| keep.contains(k));
     }
 
+    /// Adds every counter of `other` (usually the same date recorded on another machine).
+    pub fn merge_from(&mut self, other: &DailyStats) {
+        self.keyboard = self.keyboard.saturating_add(other.keyboard);
+        self.mouse_single = self.mouse_single.saturating_add(other.mouse_single);
+        self.scroll = self.scroll.saturating_add(other.scroll);
+        if let Some(v) = other.first_event_at_ms {
+            self.record_event_at_ms(v);
+        }
+        if let Some(v) = other.last_event_at_ms {
+            self.record_event_at_ms(v);
+        }
+
+        self.add_mouse_move_distance_px(other.mouse_move_distance_px);
+        for (id, px) in &other.mouse_move_distance_px_by_display {
+            self.mouse_move_distance_px_by_display
+                .entry(id.clone())
+                .and_modify(|v| *v = v.saturating_add(*px))
+                .or_insert(*px);
+        }
+        self.scroll_ticks = self.scroll_ticks.saturating_add(other.scroll_ticks);
+        self.scroll_distance_vertical = self
+            .scroll_distance_vertical
+            .saturating_add(other.scroll_distance_vertical);
+        self.scroll_distance_horizontal = self
+            .scroll_distance_horizontal
+            .saturating_add(other.scroll_distance_horizontal);
+        self.double_clicks = self.double_clicks.saturating_add(other.double_clicks);
+        self.drags = self.drags.saturating_add(other.drags);
+        self.drag_distance_px = self.drag_distance_px.saturating_add(other.drag_distance_px);
+
+        self.normalize_hourly();
+        for (bucket, theirs) in self.hourly.iter_mut().zip(other.hourly.iter()) {
+            bucket.total = bucket.total.saturating_add(theirs.total);
+            bucket.keyboard = bucket.keyboard.saturating_add(theirs.keyboard);
+            bucket.mouse_single = bucket.mouse_single.saturating_add(theirs.mouse_single);
+            bucket.scroll = bucket.scroll.saturating_add(theirs.scroll);
+        }
+
+        self.add_key_counts(&other.key_counts);
+        self.add_key_unshifted_counts(&other.key_counts_unshifted);
+        self.add_key_shifted_counts(&other.key_counts_shifted);
+        self.add_shortcut_counts(&other.shortcut_counts);
+        self.add_mouse_button_counts(&other.mouse_button_counts);
+
+        for (app_id, theirs) in &other.app_input_counts {
+            let entry = self.app_input_counts.entry(Arc::clone(app_id)).or_default();
+            if entry.name.is_none() {
+                entry.name = theirs.name.as_ref().map(Arc::clone);
+            }
+            entry.keyboard = entry.keyboard.saturating_add(theirs.keyboard);
+            entry.mouse_single = entry.mouse_single.saturating_add(theirs.mouse_single);
+            entry.scroll = entry.scroll.saturating_add(theirs.scroll);
+            entry.scroll_ticks = entry.scroll_ticks.saturating_add(theirs.scroll_ticks);
+        }
+
+        self.recompute_counters();
+    }
+
     pub fn add_key_counts(&mut self, counts: &HashMap<Arc<str>, u64>) {
         if counts.is_empty() {
             return;
@@ -480,6 +646,29 @@ impl MeritStats {
             .add_mouse_move_distance_px_for_display(display_id, px);
     }
 
+    pub fn add_scroll(
+        &mut self,
+        app_id: &Arc<str>,
+        app_name: Option<&Arc<str>>,
+        ticks: u64,
+        vertical: u64,
+        horizontal: u64,
+    ) {
+        self.normalize_today();
+        self.today
+            .add_scroll(app_id, app_name, ticks, vertical, horizontal);
+    }
+
+    pub fn add_double_click(&mut self) {
+        self.normalize_today();
+        self.today.add_double_click();
+    }
+
+    pub fn add_drag(&mut self, distance_px: u64) {
+        self.normalize_today();
+        self.today.add_drag(distance_px);
+    }
+
     pub fn add_app_merit(
         &mut self,
         app_id: &Arc<str>,
@@ -559,6 +748,18 @@ impl MeritStats {
         }
     }
 
+    /// Folds imported history into lifetime totals; `today` is merged into the live day when it
+    /// matches its date (other days are written straight to SQLite by the importer). No rollover
+    /// happens here, so the live day is still the one the importer skipped.
+    pub fn merge_imported(&mut self, today: Option<&DailyStats>, imported_total: u64) {
+        if let Some(day) = today {
+            if day.date == self.today.date {
+                self.today.merge_from(day);
+            }
+        }
+        self.total_merit = self.total_merit.saturating_add(imported_total);
+    }
+
     pub fn clear_history(&mut self) {
         self.history.clear();
         self.recompute_counters();
@@ -7,9 +7,17 @@ pub mod custom_statistics_template;
 pub mod statistics;
 
 pub use merit::{DailyStats, DailyStatsLite, InputEvent, InputOrigin, InputSource, MeritStats, MeritStatsLite};
-pub use click_heatmap::ClickHeatmapState;
-pub use settings::{MouseDistanceDisplaySettings, Settings, StatisticsBlockState};
+pub use click_heatmap::{ClickHeatmapState, HeatmapKind};
+pub use settings::{
+    AppRule, AppRuleMode, AppRuleUpsert, HistoryRetentionSettings, KeyPrivacyLevel,
+    ListeningScheduleRule, MouseDistanceDisplaySettings, ScheduleAction, Settings,
+    StatisticsBlockState,
+};
 pub use window_placement::WindowPlacement;
 pub use achievements::{AchievementCadence, AchievementState, AchievementUnlockRecord};
 pub use custom_statistics_template::{CustomStatisticsTemplate, CustomStatisticsTemplateUpsert};
-pub use statistics::StatisticsAggregates;
+pub use statistics::{
+    KeyBigramCount, KeyBigramStats, SessionRecord, SessionStats, StatisticsAggregates,
+    StatisticsBucket, StatisticsGranularity, TimelineBucket, TypingAccuracyDay,
+    TypingAccuracyHour, TypingSpeedDay, TypingSpeedHour,
+};
@@ -31,6 +31,91 @@ pub struct StatisticsBlockState {
     pub collapsed: bool,
 }
 
+/// How many days of per-day rows `history.sqlite3` keeps for each kind of data. `0` keeps rows
+/// forever, which is the default for everything: pruning deletes history, so it is opt-in.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct HistoryRetentionSettings {
+    pub daily_stats_days: u32,
+    pub key_counts_days: u32,
+    pub mouse_button_counts_days: u32,
+    /// Also covers the hourly typing speed and correction series.
+    pub hourly_days: u32,
+    pub app_input_days: u32,
+    pub click_heatmap_days: u32,
+    pub sessions_days: u32,
+}
+
+/// What a per-app rule does with input while the app is in front.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum AppRuleMode {
+    /// Nothing is counted or stored.
+    #[default]
+    Ignore,
+    /// Totals are counted; keys, shifted keys and shortcuts are not stored.
+    CountWithoutDetails,
+    /// Allowlist entry: once any rule uses it, apps without such a rule are ignored.
+    CountOnly,
+}
+
+/// What listening does while a schedule rule's time range is active.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScheduleAction {
+    /// Listening is paused inside the range.
+    #[default]
+    Pause,
+    /// Focus hours: once any rule uses it, listening is paused outside all such ranges.
+    Listen,
+}
+
+/// A weekly time range in local time. `start_minute`/`end_minute` count from midnight; an end
+/// before the start runs past midnight into the next day. Empty ranges (start equal to end) are
+/// dropped when settings are saved.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct ListeningScheduleRule {
+    pub id: String,
+    /// 0 = Monday … 6 = Sunday, for the day the range starts on; empty means every day.
+    pub weekdays: Vec<u8>,
+    pub start_minute: u32,
+    pub end_minute: u32,
+    pub action: ScheduleAction,
+}
+
+/// How much key-level detail is recorded; totals are always kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum KeyPrivacyLevel {
+    /// Individual key codes and shortcuts.
+    #[default]
+    Full,
+    /// Key codes are replaced by coarse categories such as `cat:letter`.
+    Categories,
+    /// No per-key or per-shortcut counts at all.
+    TotalsOnly,
+}
+
+/// Matches an app by id (bundle id, exe path, Wayland app_id) or display name, case-insensitively.
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AppRule {
+    pub id: String,
+    pub app_id: Option<String>,
+    pub app_name: Option<String>,
+    pub mode: AppRuleMode,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct AppRuleUpsert {
+    pub id: Option<String>,
+    pub app_id: Option<String>,
+    pub app_name: Option<String>,
+    pub mode: AppRuleMode,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct Settings {
@@ -39,6 +124,13 @@ pub struct Settings {
     pub auto_update_enabled: bool,
     pub enable_keyboard: bool,
     pub enable_mouse_single: bool,
+    /// Middle clicks count as merit (with `enable_mouse_single`).
+    pub enable_mouse_middle: bool,
+    /// Back/forward and other extra buttons count as merit (with `enable_mouse_single`).
+    pub enable_mouse_side_buttons: bool,
+    /// Count scroll wheel ticks as merit; scroll activity is recorded either way.
+    #[serde(default = "default_false")]
+    pub enable_scroll: bool,
     #[serde(default = "default_false")]
     pub keyboard_piano_enabled: bool,
     #[serde(default = "default_keyboard_piano_volume")]
@@ -85,6 +177,20 @@ pub struct Settings {
     #[serde(default = "default_false")]
     pub achievement_notifications_enabled: bool,
     pub statistics_blocks: Vec<StatisticsBlockState>,
+    pub timeline_bucket_minutes: u32,
+    pub timeline_retention_days: u32,
+    pub session_idle_threshold_secs: u32,
+    pub history_retention: HistoryRetentionSettings,
+    pub local_api_enabled: bool,
+    pub local_api_port: u16,
+    pub local_api_token: String,
+    pub app_rules: Vec<AppRule>,
+    pub key_privacy_level: KeyPrivacyLevel,
+    /// Consecutive keys further apart than this don't form a bigram.
+    pub key_bigram_timeout_ms: u32,
+    pub listening_schedule: Vec<ListeningScheduleRule>,
+    /// Set by "pause for N minutes"; kept across restarts until it expires.
+    pub listening_paused_until_ms: Option<i64>,
 }
 
 impl Default for Settings {
@@ -94,6 +200,9 @@ impl Default for Settings {
             auto_update_enabled: false,
             enable_keyboard: true,
             enable_mouse_single: true,
+            enable_mouse_middle: true,
+            enable_mouse_side_buttons: true,
+            enable_scroll: false,
             keyboard_piano_enabled: false,
             keyboard_piano_volume: default_keyboard_piano_volume(),
             keyboard_piano_scale: default_keyboard_piano_scale(),
@@ -204,6 +313,18 @@ impl Default for Settings {
                     collapsed: false,
                 },
             ],
+            timeline_bucket_minutes: 1,
+            timeline_retention_days: 30,
+            session_idle_threshold_secs: 300,
+            history_retention: HistoryRetentionSettings::default(),
+            local_api_enabled: false,
+            local_api_port: 47321,
+            local_api_token: String::new(),
+            app_rules: Vec::new(),
+            key_privacy_level: KeyPrivacyLevel::Full,
+            key_bigram_timeout_ms: 1000,
+            listening_schedule: Vec::new(),
+            listening_paused_until_ms: None,
         }
     }
 }
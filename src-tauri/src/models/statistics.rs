@@ -1,4 +1,5 @@
-use super::merit::{AppInputStats, HourlyStats};
+use super::merit::{AppInputStats, DailyStatsLite, HourlyStats};
+use chrono::{Datelike, Duration, NaiveDate};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -22,6 +23,383 @@ pub struct StatisticsAggregates {
     pub app_input_counts: HashMap<Arc<str>, AppInputStats>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StatisticsGranularity {
+    #[default]
+    Day,
+    /// ISO weeks, starting on Monday.
+    Week,
+    Month,
+    Year,
+}
+
+impl StatisticsGranularity {
+    pub fn bucket_start(self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => date,
+            Self::Week => date - Duration::days(i64::from(date.weekday().num_days_from_monday())),
+            Self::Month => date.with_day(1).unwrap_or(date),
+            Self::Year => date.with_ordinal(1).unwrap_or(date),
+        }
+    }
+
+    pub fn bucket_end(self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Day => start,
+            Self::Week => start + Duration::days(6),
+            Self::Month => start
+                .checked_add_months(chrono::Months::new(1))
+                .and_then(|d| d.pred_opt())
+                .unwrap_or(start),
+            Self::Year => NaiveDate::from_ymd_opt(start.year(), 12, 31).unwrap_or(start),
+        }
+    }
+}
+
+/// `DailyStatsLite` rolled up over one day/week/month/year.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatisticsBucket {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+    /// Days in the bucket that have any recorded stats.
+    #[serde(default)]
+    pub active_days: u32,
+    #[serde(default)]
+    pub total: u64,
+    #[serde(default)]
+    pub keyboard: u64,
+    #[serde(default)]
+    pub mouse_single: u64,
+    #[serde(default)]
+    pub first_event_at_ms: Option<u64>,
+    #[serde(default)]
+    pub last_event_at_ms: Option<u64>,
+    #[serde(default)]
+    pub mouse_move_distance_px: u64,
+    #[serde(default)]
+    pub mouse_move_distance_px_by_display: HashMap<String, u64>,
+    #[serde(default)]
+    pub scroll: u64,
+    #[serde(default)]
+    pub scroll_ticks: u64,
+    #[serde(default)]
+    pub scroll_distance_vertical: u64,
+    #[serde(default)]
+    pub scroll_distance_horizontal: u64,
+    #[serde(default)]
+    pub double_clicks: u64,
+    #[serde(default)]
+    pub drags: u64,
+    #[serde(default)]
+    pub drag_distance_px: u64,
+    #[serde(default)]
+    pub hourly: Vec<HourlyStats>,
+}
+
+impl StatisticsBucket {
+    pub fn new(granularity: StatisticsGranularity, date: NaiveDate) -> Self {
+        let start = granularity.bucket_start(date);
+        Self {
+            start,
+            end: granularity.bucket_end(start),
+            active_days: 0,
+            total: 0,
+            keyboard: 0,
+            mouse_single: 0,
+            first_event_at_ms: None,
+            last_event_at_ms: None,
+            mouse_move_distance_px: 0,
+            mouse_move_distance_px_by_display: HashMap::new(),
+            scroll: 0,
+            scroll_ticks: 0,
+            scroll_distance_vertical: 0,
+            scroll_distance_horizontal: 0,
+            double_clicks: 0,
+            drags: 0,
+            drag_distance_px: 0,
+            hourly: vec![HourlyStats::default(); 24],
+        }
+    }
+
+    pub fn add_day(&mut self, day: &DailyStatsLite) {
+        self.active_days = self.active_days.saturating_add(1);
+        self.total = self.total.saturating_add(day.total);
+        self.keyboard = self.keyboard.saturating_add(day.keyboard);
+        self.mouse_single = self.mouse_single.saturating_add(day.mouse_single);
+        self.first_event_at_ms = match (self.first_event_at_ms, day.first_event_at_ms) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        self.last_event_at_ms = self.last_event_at_ms.max(day.last_event_at_ms);
+        self.mouse_move_distance_px = self
+            .mouse_move_distance_px
+            .saturating_add(day.mouse_move_distance_px);
+        for (id, px) in &day.mouse_move_distance_px_by_display {
+            let v = self
+                .mouse_move_distance_px_by_display
+                .entry(id.clone())
+                .or_default();
+            *v = v.saturating_add(*px);
+        }
+        self.scroll = self.scroll.saturating_add(day.scroll);
+        self.scroll_ticks = self.scroll_ticks.saturating_add(day.scroll_ticks);
+        self.scroll_distance_vertical = self
+            .scroll_distance_vertical
+            .saturating_add(day.scroll_distance_vertical);
+        self.scroll_distance_horizontal = self
+            .scroll_distance_horizontal
+            .saturating_add(day.scroll_distance_horizontal);
+        self.double_clicks = self.double_clicks.saturating_add(day.double_clicks);
+        self.drags = self.drags.saturating_add(day.drags);
+        self.drag_distance_px = self.drag_distance_px.saturating_add(day.drag_distance_px);
+        for (bucket, theirs) in self.hourly.iter_mut().zip(day.hourly.iter()) {
+            bucket.total = bucket.total.saturating_add(theirs.total);
+            bucket.keyboard = bucket.keyboard.saturating_add(theirs.keyboard);
+            bucket.mouse_single = bucket.mouse_single.saturating_add(theirs.mouse_single);
+            bucket.scroll = bucket.scroll.saturating_add(theirs.scroll);
+        }
+    }
+
+    /// Adds `day` to its bucket in `buckets` (sorted by `start`), creating the bucket if needed.
+    pub fn fold_day(
+        buckets: &mut Vec<Self>,
+        granularity: StatisticsGranularity,
+        day: &DailyStatsLite,
+    ) {
+        let start = granularity.bucket_start(day.date);
+        let idx = match buckets.binary_search_by_key(&start, |b| b.start) {
+            Ok(idx) => idx,
+            Err(idx) => {
+                buckets.insert(idx, Self::new(granularity, day.date));
+                idx
+            }
+        };
+        buckets[idx].add_day(day);
+    }
+}
+
+/// Activity within one timeline bucket; `minute` is the bucket's start as minutes since local
+/// midnight.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TimelineBucket {
+    pub minute: u32,
+    #[serde(default)]
+    pub keyboard: u64,
+    #[serde(default)]
+    pub mouse_single: u64,
+    #[serde(default)]
+    pub distance_px: u64,
+}
+
+/// A stretch of input activity bounded by idle gaps.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionRecord {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    /// Local date the session started on.
+    pub date_key: String,
+    #[serde(default)]
+    pub keyboard: u64,
+    #[serde(default)]
+    pub mouse_single: u64,
+    #[serde(default)]
+    pub app_id: Option<String>,
+    #[serde(default)]
+    pub app_name: Option<String>,
+    /// Still open (not yet persisted).
+    #[serde(default)]
+    pub ongoing: bool,
+}
+
+impl SessionRecord {
+    pub fn duration_ms(&self) -> u64 {
+        self.end_ms.saturating_sub(self.start_ms)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionStats {
+    pub sessions: u64,
+    pub active_ms: u64,
+    pub average_session_ms: u64,
+    pub longest: Option<SessionRecord>,
+    /// Gaps between consecutive sessions on the same day.
+    pub breaks: u64,
+    pub average_break_ms: u64,
+}
+
+/// Words-per-minute figures assume five keystrokes per word.
+pub const KEYS_PER_WORD: f64 = 5.0;
+
+fn keys_per_minute(keys: u64, active_ms: u64) -> f64 {
+    if active_ms == 0 {
+        return 0.0;
+    }
+    keys as f64 * 60_000.0 / active_ms as f64
+}
+
+/// Typing speed within one local hour; idle gaps don't count as typing time.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypingSpeedHour {
+    pub hour: u32,
+    pub keys: u64,
+    pub active_ms: u64,
+    pub average_kpm: f64,
+    pub peak_kpm: f64,
+}
+
+impl TypingSpeedHour {
+    pub fn new(hour: u32, keys: u64, active_ms: u64, peak_kpm: f64) -> Self {
+        Self {
+            hour,
+            keys,
+            active_ms,
+            average_kpm: keys_per_minute(keys, active_ms),
+            peak_kpm,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypingSpeedDay {
+    pub date_key: String,
+    pub keys: u64,
+    pub active_ms: u64,
+    pub average_kpm: f64,
+    pub average_wpm: f64,
+    pub peak_kpm: f64,
+    pub peak_wpm: f64,
+    pub hourly: Vec<TypingSpeedHour>,
+}
+
+impl TypingSpeedDay {
+    pub fn from_hours(date_key: String, hourly: Vec<TypingSpeedHour>) -> Self {
+        let keys = hourly.iter().map(|h| h.keys).sum();
+        let active_ms = hourly.iter().map(|h| h.active_ms).sum();
+        let peak_kpm = hourly.iter().map(|h| h.peak_kpm).fold(0.0, f64::max);
+        let average_kpm = keys_per_minute(keys, active_ms);
+        Self {
+            date_key,
+            keys,
+            active_ms,
+            average_kpm,
+            average_wpm: average_kpm / KEYS_PER_WORD,
+            peak_kpm,
+            peak_wpm: peak_kpm / KEYS_PER_WORD,
+            hourly,
+        }
+    }
+}
+
+/// Share of keystrokes that were corrections (Backspace/Delete).
+fn correction_ratio(typed_keys: u64, correction_keys: u64) -> f64 {
+    let total = typed_keys.saturating_add(correction_keys);
+    if total == 0 {
+        return 0.0;
+    }
+    correction_keys as f64 / total as f64
+}
+
+/// Typed keys that survived, assuming each correction removes one of them.
+fn accuracy(typed_keys: u64, correction_keys: u64) -> f64 {
+    if typed_keys == 0 {
+        return if correction_keys == 0 { 1.0 } else { 0.0 };
+    }
+    (1.0 - correction_keys as f64 / typed_keys as f64).max(0.0)
+}
+
+/// Corrections against typed keys within one local hour.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypingAccuracyHour {
+    pub hour: u32,
+    pub typed_keys: u64,
+    pub correction_keys: u64,
+    /// Runs of fast typing.
+    pub bursts: u64,
+    /// Bursts followed straight away by several corrections.
+    pub burst_deletes: u64,
+    pub correction_ratio: f64,
+    pub accuracy: f64,
+}
+
+impl TypingAccuracyHour {
+    pub fn new(
+        hour: u32,
+        typed_keys: u64,
+        correction_keys: u64,
+        bursts: u64,
+        burst_deletes: u64,
+    ) -> Self {
+        Self {
+            hour,
+            typed_keys,
+            correction_keys,
+            bursts,
+            burst_deletes,
+            correction_ratio: correction_ratio(typed_keys, correction_keys),
+            accuracy: accuracy(typed_keys, correction_keys),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TypingAccuracyDay {
+    pub date_key: String,
+    pub typed_keys: u64,
+    pub correction_keys: u64,
+    pub bursts: u64,
+    pub burst_deletes: u64,
+    pub correction_ratio: f64,
+    pub accuracy: f64,
+    /// Backspace/Delete share of the day's key counts, which also cover days from before the
+    /// live tracker. `None` when the day has no key details.
+    pub key_count_correction_ratio: Option<f64>,
+    pub hourly: Vec<TypingAccuracyHour>,
+}
+
+impl TypingAccuracyDay {
+    pub fn from_hours(
+        date_key: String,
+        hourly: Vec<TypingAccuracyHour>,
+        key_count_correction_ratio: Option<f64>,
+    ) -> Self {
+        let typed_keys = hourly.iter().map(|h| h.typed_keys).sum();
+        let correction_keys = hourly.iter().map(|h| h.correction_keys).sum();
+        Self {
+            date_key,
+            typed_keys,
+            correction_keys,
+            bursts: hourly.iter().map(|h| h.bursts).sum(),
+            burst_deletes: hourly.iter().map(|h| h.burst_deletes).sum(),
+            correction_ratio: correction_ratio(typed_keys, correction_keys),
+            accuracy: accuracy(typed_keys, correction_keys),
+            key_count_correction_ratio,
+            hourly,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct KeyBigramCount {
+    pub first: Arc<str>,
+    pub second: Arc<str>,
+    pub count: u64,
+}
+
+/// Consecutive-key statistics over a date range. Same-finger pairs exclude repeats of one key.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KeyBigramStats {
+    pub total: u64,
+    pub same_key: u64,
+    pub same_finger: u64,
+    pub same_hand: u64,
+    pub hand_alternations: u64,
+    /// Bigrams involving a key without a home finger (Space, arrows, numpad, ...).
+    pub unmapped: u64,
+    pub top: Vec<KeyBigramCount>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -39,6 +417,7 @@ mod tests {
                 total: 10,
                 keyboard: 6,
                 mouse_single: 4,
+                ..Default::default()
             },
         );
 
@@ -58,4 +437,24 @@ mod tests {
             Some("Example")
         );
     }
+
+    #[test]
+    fn granularity_bucket_bounds() {
+        let d = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        let week = StatisticsGranularity::Week.bucket_start(d);
+        assert_eq!(week, NaiveDate::from_ymd_opt(2024, 2, 26).unwrap());
+        assert_eq!(
+            StatisticsGranularity::Week.bucket_end(week),
+            NaiveDate::from_ymd_opt(2024, 3, 3).unwrap()
+        );
+        let month = StatisticsGranularity::Month.bucket_start(d);
+        assert_eq!(month, NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+        assert_eq!(StatisticsGranularity::Month.bucket_end(month), d);
+        let year = StatisticsGranularity::Year.bucket_start(d);
+        assert_eq!(year, NaiveDate::from_ymd_opt(2024, 1, 1).unwrap());
+        assert_eq!(
+            StatisticsGranularity::Year.bucket_end(year),
+            NaiveDate::from_ymd_opt(2024, 12, 31).unwrap()
+        );
+    }
 }
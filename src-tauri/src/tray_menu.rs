@@ -444,6 +444,7 @@ pub fn handle_menu_event(app: &AppHandle<Wry>, event: tauri::menu::MenuEvent) {
         }
         "quit" => {
             crate::core::window_placement::capture_all_now(app);
+            crate::core::sessions::close_now();
             crate::core::persistence::flush_now();
             app.exit(0);
         }
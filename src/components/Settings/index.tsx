@@ -521,6 +521,32 @@ export function Settings() {
                       <Switch checked={settings.enable_mouse_single} onCheckedChange={(v) => updateSettings({ enable_mouse_single: v })} data-no-drag />
                     }
                   />
+
+                  <SettingRow
+                    title={t('settings.inputMonitoring.mouseMiddle')}
+                    description={t('settings.inputMonitoring.mouseMiddleDesc')}
+                    control={
+                      <Switch
+                        checked={settings.enable_mouse_middle ?? true}
+                        disabled={!settings.enable_mouse_single}
+                        onCheckedChange={(v) => updateSettings({ enable_mouse_middle: v })}
+                        data-no-drag
+                      />
+                    }
+                  />
+
+                  <SettingRow
+                    title={t('settings.inputMonitoring.mouseSideButtons')}
+                    description={t('settings.inputMonitoring.mouseSideButtonsDesc')}
+                    control={
+                      <Switch
+                        checked={settings.enable_mouse_side_buttons ?? true}
+                        disabled={!settings.enable_mouse_single}
+                        onCheckedChange={(v) => updateSettings({ enable_mouse_side_buttons: v })}
+                        data-no-drag
+                      />
+                    }
+                  />
                 </SettingsSection>
 
                 <SettingsSection
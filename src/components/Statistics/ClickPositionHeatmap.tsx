@@ -7,7 +7,7 @@ import { Card } from '@/components/ui/card'
 import { Select, SelectContent, SelectItem, SelectTrigger, SelectValue } from '@/components/ui/select'
 import { COMMANDS, EVENTS } from '@/types/events'
 import type { InputEvent, Settings } from '@/types/merit'
-import type { ClickHeatmapGrid, MonitorInfo } from '@/types/clickHeatmap'
+import type { ClickHeatmapGrid, HeatmapKind, MonitorInfo } from '@/types/clickHeatmap'
 import { cn } from '@/lib/utils'
 import {
   computeHeatThresholds,
@@ -52,6 +52,7 @@ export function ClickPositionHeatmap({
   const [monitors, setMonitors] = useState<MonitorInfo[]>([])
   const [selectedId, setSelectedId] = useState<string>('')
   const [mode, setMode] = useState<'day' | 'total'>(defaultMode)
+  const [kind, setKind] = useState<HeatmapKind>('click')
   const [grid, setGrid] = useState<ClickHeatmapGrid | null>(null)
   const [error, setError] = useState<string | null>(null)
   const refreshTokenRef = useRef<number | null>(null)
@@ -86,6 +87,7 @@ export function ClickPositionHeatmap({
         monitorId: selectedId,
         cols: gridCols,
         rows: gridRows,
+        kind,
       }
       if (mode === 'day' && todayKey) args.dateKey = todayKey
 
@@ -94,7 +96,7 @@ export function ClickPositionHeatmap({
     } catch (e) {
       setError(String(e))
     }
-  }, [gridCols, gridRows, mode, selectedId, todayKey])
+  }, [gridCols, gridRows, kind, mode, selectedId, todayKey])
 
   const scheduleRefresh = useCallback(() => {
     if (refreshTokenRef.current) window.clearTimeout(refreshTokenRef.current)
@@ -116,6 +118,13 @@ export function ClickPositionHeatmap({
     if (mode === 'day' && !todayKey) setMode('total')
   }, [mode, todayKey])
 
+  useEffect(() => {
+    // Movement samples arrive without an event; poll while that map is shown.
+    if (kind !== 'move') return
+    const timer = window.setInterval(() => void fetchGrid(), 5000)
+    return () => window.clearInterval(timer)
+  }, [fetchGrid, kind])
+
   useEffect(() => {
     const unsubscribe = listen<InputEvent>(EVENTS.INPUT_EVENT, (event) => {
       if (event.payload.origin !== 'global') return
@@ -168,14 +177,14 @@ export function ClickPositionHeatmap({
     if (!selectedId) return
     setError(null)
     try {
-      const args: Record<string, unknown> = { displayId: selectedId }
+      const args: Record<string, unknown> = { displayId: selectedId, kind }
       if (mode === 'day' && todayKey) args.dateKey = todayKey
       await invoke(COMMANDS.CLEAR_CLICK_HEATMAP, args)
       await fetchGrid()
     } catch (e) {
       setError(String(e))
     }
-  }, [fetchGrid, mode, selectedId, todayKey])
+  }, [fetchGrid, kind, mode, selectedId, todayKey])
 
   if (!enabled) {
     return (
@@ -197,7 +206,10 @@ export function ClickPositionHeatmap({
                 {mode === 'day' ? t('customStatistics.mode.daily') : t('customStatistics.mode.cumulative')}
                 {mode === 'day' && todayKey ? ` · ${todayKey}` : ''}
               </span>
-              {t('statistics.clickHeatmap.summary', { total: totalClicks.toLocaleString(), max: maxCell.toLocaleString() })}
+              {t(kind === 'move' ? 'statistics.clickHeatmap.moveSummary' : 'statistics.clickHeatmap.summary', {
+                total: totalClicks.toLocaleString(),
+                max: maxCell.toLocaleString(),
+              })}
             </div>
           </div>
           <div className="flex items-center gap-2 shrink-0" data-no-drag>
@@ -242,6 +254,24 @@ export function ClickPositionHeatmap({
             </Select>
           </div>
           <div className="flex items-center gap-2">
+            <Button
+              type="button"
+              variant={kind === 'click' ? 'secondary' : 'outline'}
+              size="sm"
+              onClick={() => setKind('click')}
+              data-no-drag
+            >
+              {t('statistics.clickHeatmap.kindClick')}
+            </Button>
+            <Button
+              type="button"
+              variant={kind === 'move' ? 'secondary' : 'outline'}
+              size="sm"
+              onClick={() => setKind('move')}
+              data-no-drag
+            >
+              {t('statistics.clickHeatmap.kindMove')}
+            </Button>
             <Button variant="secondary" size="sm" onClick={() => void fetchMonitors()}>
               {t('statistics.clickHeatmap.refreshDisplays')}
             </Button>
@@ -22,8 +22,11 @@ export function MouseButtonsHeatmap({ counts, heatLevelCount }: { counts: Counts
   const { t } = useTranslation()
   const buttons = useMemo(
     () => [
-      { code: 'MouseLeft', label: t('statistics.mouseButtonsHeatmap.left'), flex: 1 },
-      { code: 'MouseRight', label: t('statistics.mouseButtonsHeatmap.right'), flex: 1 },
+      { code: 'MouseLeft', label: t('statistics.mouseButtonsHeatmap.left'), flex: 2 },
+      { code: 'MouseMiddle', label: t('statistics.mouseButtonsHeatmap.middle'), flex: 1 },
+      { code: 'MouseRight', label: t('statistics.mouseButtonsHeatmap.right'), flex: 2 },
+      { code: 'MouseBack', label: t('statistics.mouseButtonsHeatmap.back'), flex: 1 },
+      { code: 'MouseForward', label: t('statistics.mouseButtonsHeatmap.forward'), flex: 1 },
     ],
     [t]
   )
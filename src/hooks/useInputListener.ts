@@ -6,11 +6,11 @@ import { useMeritStore } from '../stores/useMeritStore'
 import { useSettingsStore } from '../stores/useSettingsStore'
 import { useMeritDaysStore } from '../stores/useMeritDaysStore'
 import { useMeritDaysLiteStore } from '../stores/useMeritDaysLiteStore'
-import type { MeritStatsLite } from '../types/merit'
+import type { ListeningChanged, MeritStatsLite } from '../types/merit'
 import { COMMANDS, EVENTS } from '../types/events'
 import { isMac } from '../utils/platform'
 
-export type InputListenerErrorCode = 'permission_required' | 'listen_failed'
+export type InputListenerErrorCode = 'permission_required' | 'input_group_required' | 'listen_failed'
 
 export type InputListenerError = {
   code: InputListenerErrorCode
@@ -96,6 +96,16 @@ export function useInputListener() {
       .catch(() => {})
   }, [])
 
+  useEffect(() => {
+    const unlisten = listen<ListeningChanged>(EVENTS.LISTENING_CHANGED, (event) => {
+      setIsListening(event.payload.enabled)
+    })
+
+    return () => {
+      unlisten.then((fn) => fn())
+    }
+  }, [])
+
   useEffect(() => {
     const unlisten = listen<InputListenerError>(EVENTS.INPUT_LISTENER_ERROR, (event) => {
       setRawError({ code: event.payload.code, detail: event.payload.message })
@@ -112,6 +122,9 @@ export function useInputListener() {
     if (rawError.code === 'permission_required') {
       return { code: rawError.code, message: t('errors.inputMonitoringRequiredMac') }
     }
+    if (rawError.code === 'input_group_required') {
+      return { code: rawError.code, message: t('errors.inputGroupRequiredLinux'), detail: rawError.detail }
+    }
     return {
       code: rawError.code,
       message: rawError.detail ?? t('errors.unknown'),
@@ -192,6 +192,10 @@ export const zhCN = {
       keyboardInputDesc: '监听键盘按键',
       mouseClick: '鼠标单击',
       mouseClickDesc: '监听鼠标单击事件',
+      mouseMiddle: '鼠标中键',
+      mouseMiddleDesc: '中键单击计入功德',
+      mouseSideButtons: '鼠标侧键',
+      mouseSideButtonsDesc: '后退/前进等额外按键计入功德',
       permissionDialogTitle: '需要输入监控权限',
       permissionDialogBody: '开启 macOS「输入监控」权限后才能接收全局键盘/鼠标事件。',
       openSystemSettings: '打开系统设置',
@@ -619,6 +623,8 @@ export const zhCN = {
   errors: {
     inputMonitoringRequiredMac:
       '需要开启 macOS「输入监控」权限：系统设置 → 隐私与安全性 → 输入监控。',
+    inputGroupRequiredLinux:
+      '无法读取 /dev/input 下的输入设备：请将当前用户加入 input 组（sudo usermod -aG input $USER），注销并重新登录后点击“启动”。',
     unknown: '未知错误',
   },
   statistics: {
@@ -959,11 +965,17 @@ export const zhCN = {
       noData: '暂无鼠标按钮记录',
       left: '左键',
       right: '右键',
+      middle: '中键',
+      back: '后退',
+      forward: '前进',
     },
     clickHeatmap: {
       title: '点击位置热力图',
       disabled: '请确保已启用全局监听与鼠标单击统计。',
       summary: '总点击 {{total}} · 单格最大 {{max}}',
+      moveSummary: '采样 {{total}} · 单格最大 {{max}}',
+      kindClick: '点击',
+      kindMove: '移动',
       clear: '清空本屏数据',
       selectDisplay: '选择屏幕',
       refreshDisplays: '刷新屏幕',
@@ -1249,6 +1261,10 @@ export const en: TranslationResource = {
       keyboardInputDesc: 'Listen to keyboard events',
       mouseClick: 'Mouse Click',
       mouseClickDesc: 'Listen to mouse click events',
+      mouseMiddle: 'Middle Button',
+      mouseMiddleDesc: 'Count middle clicks as merit',
+      mouseSideButtons: 'Side Buttons',
+      mouseSideButtonsDesc: 'Count back/forward and other extra buttons as merit',
       permissionDialogTitle: 'Input Monitoring Permission Required',
       permissionDialogBody: 'Enable macOS “Input Monitoring” to receive global keyboard/mouse events.',
       openSystemSettings: 'Open System Settings',
@@ -1678,6 +1694,8 @@ export const en: TranslationResource = {
   errors: {
     inputMonitoringRequiredMac:
       'macOS “Input Monitoring” permission is required: System Settings → Privacy & Security → Input Monitoring.',
+    inputGroupRequiredLinux:
+      'Cannot read input devices under /dev/input: add your user to the input group (sudo usermod -aG input $USER), log out and back in, then click “Start”.',
     unknown: 'Unknown error',
   },
   statistics: {
@@ -2018,11 +2036,17 @@ export const en: TranslationResource = {
       noData: 'No mouse button records',
       left: 'Left',
       right: 'Right',
+      middle: 'Middle',
+      back: 'Back',
+      forward: 'Forward',
     },
     clickHeatmap: {
       title: 'Click Position Heatmap',
       disabled: 'Ensure global listening + mouse clicks are enabled.',
       summary: 'Total {{total}} · Max cell {{max}}',
+      moveSummary: 'Samples {{total}} · Max cell {{max}}',
+      kindClick: 'Clicks',
+      kindMove: 'Movement',
       clear: 'Clear this display',
       selectDisplay: 'Select display',
       refreshDisplays: 'Refresh displays',
@@ -2300,6 +2324,10 @@ export const zhTW: TranslationResource = {
       keyboardInputDesc: '監聽鍵盤按鍵',
       mouseClick: '滑鼠單擊',
       mouseClickDesc: '監聽滑鼠單擊事件',
+      mouseMiddle: '滑鼠中鍵',
+      mouseMiddleDesc: '中鍵單擊計入功德',
+      mouseSideButtons: '滑鼠側鍵',
+      mouseSideButtonsDesc: '後退/前進等額外按鍵計入功德',
       permissionDialogTitle: '需要輸入監控權限',
       permissionDialogBody: '開啟 macOS「輸入監控」權限後才能接收全域鍵盤/滑鼠事件。',
       openSystemSettings: '打開系統設定',
@@ -2727,6 +2755,8 @@ export const zhTW: TranslationResource = {
   errors: {
     inputMonitoringRequiredMac:
       '需要開啟 macOS「輸入監控」權限：系統設定 → 隱私與安全性 → 輸入監控。',
+    inputGroupRequiredLinux:
+      '無法讀取 /dev/input 下的輸入裝置：請將目前使用者加入 input 群組（sudo usermod -aG input $USER），登出並重新登入後點擊「啟動」。',
     unknown: '未知錯誤',
   },
   statistics: {
@@ -3067,11 +3097,17 @@ export const zhTW: TranslationResource = {
       noData: '暫無滑鼠按鈕記錄',
       left: '左鍵',
       right: '右鍵',
+      middle: '中鍵',
+      back: '後退',
+      forward: '前進',
     },
     clickHeatmap: {
       title: '點擊位置熱力圖',
       disabled: '請確保已啟用全域監聽與滑鼠單擊統計。',
       summary: '總點擊 {{total}} · 單格最大 {{max}}',
+      moveSummary: '取樣 {{total}} · 單格最大 {{max}}',
+      kindClick: '點擊',
+      kindMove: '移動',
       clear: '清空本螢幕資料',
       selectDisplay: '選擇螢幕',
       refreshDisplays: '刷新螢幕',
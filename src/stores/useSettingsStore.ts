@@ -19,6 +19,9 @@ const defaultSettings: Settings = {
   auto_update_enabled: false,
   enable_keyboard: true,
   enable_mouse_single: true,
+  enable_mouse_middle: true,
+  enable_mouse_side_buttons: true,
+  enable_scroll: false,
   keyboard_piano_enabled: false,
   keyboard_piano_volume: 0.25,
   keyboard_piano_scale: 'pentatonic_major',
@@ -71,6 +74,26 @@ const defaultSettings: Settings = {
     { id: 'app_input_ranking', collapsed: false },
     { id: 'monthly_calendar', collapsed: false },
   ],
+  timeline_bucket_minutes: 1,
+  timeline_retention_days: 30,
+  session_idle_threshold_secs: 300,
+  history_retention: {
+    daily_stats_days: 0,
+    key_counts_days: 0,
+    mouse_button_counts_days: 0,
+    hourly_days: 0,
+    app_input_days: 0,
+    click_heatmap_days: 0,
+    sessions_days: 0,
+  },
+  local_api_enabled: false,
+  local_api_port: 47321,
+  local_api_token: '',
+  app_rules: [],
+  key_privacy_level: 'full',
+  key_bigram_timeout_ms: 1000,
+  listening_schedule: [],
+  listening_paused_until_ms: null,
 }
 
 export const useSettingsStore = create<SettingsState>((set, get) => ({
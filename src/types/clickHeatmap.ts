@@ -15,3 +15,5 @@ export type ClickHeatmapGrid = {
   max: number | string | bigint
   total_clicks: number | string | bigint
 }
+
+export type HeatmapKind = 'click' | 'move'
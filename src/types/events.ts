@@ -1,9 +1,11 @@
 export const EVENTS = {
   INPUT_EVENT: 'input-event',
   INPUT_LISTENER_ERROR: 'input-listener-error',
+  LISTENING_CHANGED: 'listening-changed',
   KEYBOARD_PIANO_KEY: 'keyboard-piano-key',
   CLICK_HEATMAP_UPDATED: 'click-heatmap-updated',
   MERIT_UPDATED: 'merit-updated',
+  TYPING_SPEED: 'typing-speed',
   SETTINGS_UPDATED: 'settings-updated',
   ACHIEVEMENTS_UPDATED: 'achievements-updated',
   WOODEN_FISH_SKINS_UPDATED: 'wooden-fish-skins-updated',
@@ -18,6 +20,8 @@ export const COMMANDS = {
   STOP_INPUT_LISTENING: 'stop_input_listening',
   TOGGLE_INPUT_LISTENING: 'toggle_input_listening',
   IS_INPUT_LISTENING: 'is_input_listening',
+  PAUSE_INPUT_LISTENING_FOR: 'pause_input_listening_for',
+  GET_LISTENING_SCHEDULE_STATUS: 'get_listening_schedule_status',
   GET_INPUT_LISTENER_ERROR: 'get_input_listener_error',
   CHECK_INPUT_MONITORING_PERMISSION: 'check_input_monitoring_permission',
   REQUEST_INPUT_MONITORING_PERMISSION: 'request_input_monitoring_permission',
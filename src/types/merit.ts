@@ -1,23 +1,32 @@
 export interface InputSource {
   Keyboard: 'keyboard'
   MouseSingle: 'mouse_single'
+  Scroll: 'scroll'
 }
 
-export type InputSourceType = 'keyboard' | 'mouse_single'
+export type InputSourceType = 'keyboard' | 'mouse_single' | 'scroll'
 
 export interface DailyStats {
   date: string
   total: number
   keyboard: number
   mouse_single: number
+  scroll?: number
   first_event_at_ms?: number | null
   last_event_at_ms?: number | null
   mouse_move_distance_px?: number
   mouse_move_distance_px_by_display?: Record<string, number>
+  scroll_ticks?: number
+  scroll_distance_vertical?: number
+  scroll_distance_horizontal?: number
+  double_clicks?: number
+  drags?: number
+  drag_distance_px?: number
   hourly?: Array<{
     total: number
     keyboard: number
     mouse_single: number
+    scroll?: number
   }>
   key_counts: Record<string, number>
   key_counts_unshifted?: Record<string, number>
@@ -31,6 +40,8 @@ export interface DailyStats {
       total: number
       keyboard: number
       mouse_single: number
+      scroll?: number
+      scroll_ticks?: number
     }
   >
 }
@@ -40,14 +51,22 @@ export interface DailyStatsLite {
   total: number
   keyboard: number
   mouse_single: number
+  scroll?: number
   first_event_at_ms?: number | null
   last_event_at_ms?: number | null
   mouse_move_distance_px?: number
   mouse_move_distance_px_by_display?: Record<string, number>
+  scroll_ticks?: number
+  scroll_distance_vertical?: number
+  scroll_distance_horizontal?: number
+  double_clicks?: number
+  drags?: number
+  drag_distance_px?: number
   hourly?: Array<{
     total: number
     keyboard: number
     mouse_single: number
+    scroll?: number
   }>
 }
 
@@ -62,11 +81,21 @@ export interface MeritStats {
   history: DailyStats[]
 }
 
+export interface TypingSpeed {
+  kpm: number
+  wpm: number
+  today_average_kpm: number
+  today_peak_kpm: number
+}
+
 export interface Settings {
   app_locale?: 'system' | 'en' | 'zh-CN' | 'zh-TW'
   auto_update_enabled?: boolean
   enable_keyboard: boolean
   enable_mouse_single: boolean
+  enable_mouse_middle?: boolean
+  enable_mouse_side_buttons?: boolean
+  enable_scroll?: boolean
   keyboard_piano_enabled?: boolean
   keyboard_piano_volume?: number
   keyboard_piano_scale?: 'pentatonic_major' | 'major' | 'chromatic'
@@ -117,6 +146,55 @@ export interface Settings {
     id: string
     collapsed?: boolean
   }>
+  timeline_bucket_minutes?: number
+  timeline_retention_days?: number
+  session_idle_threshold_secs?: number
+  history_retention?: {
+    daily_stats_days?: number
+    key_counts_days?: number
+    mouse_button_counts_days?: number
+    hourly_days?: number
+    app_input_days?: number
+    click_heatmap_days?: number
+    sessions_days?: number
+  }
+  local_api_enabled?: boolean
+  local_api_port?: number
+  local_api_token?: string
+  app_rules?: AppRule[]
+  key_privacy_level?: KeyPrivacyLevel
+  key_bigram_timeout_ms?: number
+  listening_schedule?: ListeningScheduleRule[]
+  listening_paused_until_ms?: number | null
+}
+
+export type ScheduleAction = 'pause' | 'listen'
+
+export interface ListeningScheduleRule {
+  id: string
+  /** 0 = Monday … 6 = Sunday; empty means every day. */
+  weekdays: number[]
+  start_minute: number
+  end_minute: number
+  action: ScheduleAction
+}
+
+export type ListeningPauseReason = 'timer' | 'schedule'
+
+export interface ListeningChanged {
+  enabled: boolean
+  reason: ListeningPauseReason | null
+}
+
+export type KeyPrivacyLevel = 'full' | 'categories' | 'totals_only'
+
+export type AppRuleMode = 'ignore' | 'count_without_details' | 'count_only'
+
+export interface AppRule {
+  id: string
+  app_id?: string | null
+  app_name?: string | null
+  mode: AppRuleMode
 }
 
 export interface InputEvent {